@@ -1,6 +1,9 @@
 use super::{AgentDetector, AgentProcess};
-use crate::process::find_claude_processes;
-use crate::session::{AgentType, Session};
+use crate::export::Transcript;
+use crate::export::claude::{find_claude_transcript, load_claude_transcript};
+use crate::process::{find_all_claude_processes, find_claude_processes, ClaudeProcess};
+use crate::session::{AgentType, HistorySession, Session};
+use crate::session::history::get_claude_history;
 use crate::session::parser::get_sessions_internal;
 
 pub struct ClaudeDetector;
@@ -15,17 +18,43 @@ impl AgentDetector for ClaudeDetector {
     }
 
     fn find_processes(&self, system: &sysinfo::System) -> Vec<AgentProcess> {
-        find_claude_processes(system)
-            .into_iter()
-            .map(|p| AgentProcess {
-                pid: p.pid,
-                cpu_usage: p.cpu_usage,
-                cwd: p.cwd,
-            })
-            .collect()
+        find_claude_processes(system).into_iter().map(agent_process).collect()
     }
 
     fn find_sessions(&self, processes: &[AgentProcess]) -> Vec<Session> {
         get_sessions_internal(processes, AgentType::Claude)
     }
+
+    fn find_history(&self) -> Vec<HistorySession> {
+        get_claude_history()
+    }
+
+    fn resume_command(&self, session_id: &str) -> String {
+        format!("claude --resume {}", session_id)
+    }
+
+    fn load_transcript(&self, session_id: &str) -> Result<Transcript, String> {
+        let path = find_claude_transcript(session_id)
+            .ok_or_else(|| format!("No Claude transcript for session {}", session_id))?;
+        load_claude_transcript(session_id, &path).map_err(|e| format!("Failed to read {:?}: {}", path, e))
+    }
+}
+
+/// Every running Claude Code process, including those the session list leaves out
+pub fn find_all_processes(system: &sysinfo::System) -> Vec<AgentProcess> {
+    find_all_claude_processes(system).into_iter().map(agent_process).collect()
+}
+
+fn agent_process(p: ClaudeProcess) -> AgentProcess {
+    AgentProcess {
+        pid: p.pid,
+        cpu_usage: p.cpu_usage,
+        cwd: p.cwd,
+        cmd: p.cmd,
+        memory: p.memory,
+        start_time: p.start_time,
+        stopped: p.stopped,
+        container: p.container,
+        origin: p.origin,
+    }
 }
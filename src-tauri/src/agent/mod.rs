@@ -1,16 +1,33 @@
 pub mod claude;
 pub mod opencode;
 
-use crate::session::{Session, SessionsResponse, AgentType};
+use crate::export::Transcript;
+use crate::process::{suspend, terminate, tree, ContainerInfo, ProcessIdentity, SessionOrigin, TerminateOptions, TerminationResult};
+use crate::session::{HistorySession, ProjectHistory, Session, SessionsResponse, SessionStatus, AgentType};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::sync::Mutex;
 use sysinfo::{ProcessRefreshKind, RefreshKind, System};
 
 /// Common process info shared across agent types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct AgentProcess {
     pub pid: u32,
     pub cpu_usage: f32,
     pub cwd: Option<std::path::PathBuf>,
+    /// Full command line, used to read flags such as --model
+    pub cmd: Vec<String>,
+    /// Resident memory in bytes
+    pub memory: u64,
+    /// Process start time in seconds since the epoch.
+    /// Together with the pid this identifies a process across pid reuse.
+    pub start_time: u64,
+    /// Stopped by SIGSTOP or job control; the session is reported as Paused
+    pub stopped: bool,
+    /// Set for agents in a container; `cwd` is then a path inside the container
+    pub container: Option<ContainerInfo>,
+    /// Terminal, IDE or SDK that launched the agent
+    pub origin: SessionOrigin,
 }
 
 // Shared System instance — refreshed once per poll cycle, used by all detectors.
@@ -18,6 +35,10 @@ pub struct AgentProcess {
 // scans per poll. Now we scan once and share the snapshot.
 static SHARED_SYSTEM: Mutex<Option<System>> = Mutex::new(None);
 
+// Process each live session was last reported with, by session id.
+// Termination checks against it so a stale or reused pid is never signalled.
+static REPORTED_PROCESSES: Lazy<Mutex<HashMap<String, ProcessIdentity>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
 /// Trait for detecting and parsing agent sessions
 pub trait AgentDetector: Send + Sync {
     /// Human-readable name of the agent
@@ -31,20 +52,39 @@ pub trait AgentDetector: Send + Sync {
 
     /// Parse sessions from data files, matched to running processes
     fn find_sessions(&self, processes: &[AgentProcess]) -> Vec<Session>;
+
+    /// List past sessions from data files, whether or not a process is running
+    fn find_history(&self) -> Vec<HistorySession>;
+
+    /// Shell command that resumes a past session in the project directory
+    fn resume_command(&self, session_id: &str) -> String;
+
+    /// Read a session's full transcript, whether or not its process is running
+    fn load_transcript(&self, session_id: &str) -> Result<Transcript, String>;
+}
+
+/// All registered agent detectors
+fn detectors() -> Vec<Box<dyn AgentDetector>> {
+    vec![
+        Box::new(claude::ClaudeDetector),
+        Box::new(opencode::OpenCodeDetector),
+    ]
 }
 
 /// Get all sessions from all registered agent detectors
 pub fn get_all_sessions() -> SessionsResponse {
     use std::collections::HashSet;
-    use crate::session::{status_sort_priority, cleanup_stale_status_entries};
+    use crate::session::{status_sort_priority, cleanup_stale_status_entries, track_session_status};
+    use crate::session::groups::{apply_repo_identity, group_sessions};
+    use crate::session::files::apply_edit_conflicts;
+    use crate::session::activity::record_token_totals;
+    use crate::session::resources::record_resource_samples;
+    use chrono::{DateTime, SecondsFormat, Utc};
 
-    let detectors: Vec<Box<dyn AgentDetector>> = vec![
-        Box::new(claude::ClaudeDetector),
-        Box::new(opencode::OpenCodeDetector),
-    ];
+    let detectors = detectors();
 
-    // Phase 1: Refresh shared system once, discover all processes
-    let all_processes: Vec<Vec<AgentProcess>> = {
+    // Phase 1: Refresh shared system once, discover all processes and their children
+    let (all_processes, children): (Vec<Vec<AgentProcess>>, _) = {
         let mut system_guard = SHARED_SYSTEM.lock().unwrap();
         let system = system_guard.get_or_insert_with(|| {
             log::debug!("Initializing shared System instance");
@@ -67,7 +107,10 @@ pub fn get_all_sessions() -> SessionsResponse {
                 .with_memory()
         );
 
-        detectors.iter().map(|d| d.find_processes(system)).collect()
+        let all_processes: Vec<Vec<AgentProcess>> = detectors.iter().map(|d| d.find_processes(system)).collect();
+        let agent_pids: Vec<u32> = all_processes.iter().flatten().map(|p| p.pid).collect();
+        let children = tree::child_processes(system, &agent_pids);
+        (all_processes, children)
     }; // System lock released here — file I/O below runs without holding it
 
     // Phase 2: Find sessions (file I/O, git subprocesses — no system lock held)
@@ -78,6 +121,44 @@ pub fn get_all_sessions() -> SessionsResponse {
             detector.name(), processes.len(), sessions.len());
         all_sessions.extend(sessions);
     }
+    let processes_by_pid: HashMap<u32, &AgentProcess> = all_processes.iter().flatten().map(|p| (p.pid, p)).collect();
+    for session in &mut all_sessions {
+        session.child_processes = children.get(&session.pid).cloned().unwrap_or_default();
+        let Some(process) = processes_by_pid.get(&session.pid) else {
+            continue;
+        };
+        session.memory = process.memory;
+        session.origin = process.origin;
+        session.process_started_at = DateTime::from_timestamp(process.start_time as i64, 0)
+            .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true));
+        session.tree_cpu_usage = process.cpu_usage + session.child_processes.iter().map(|c| c.cpu_usage).sum::<f32>();
+        session.tree_memory = process.memory + session.child_processes.iter().map(|c| c.memory).sum::<u64>();
+        // Whatever the transcript says, a stopped agent isn't doing anything
+        if process.stopped {
+            session.status = SessionStatus::Paused;
+        }
+    }
+    record_reported_processes(&all_sessions, &all_processes);
+
+    apply_repo_identity(&mut all_sessions);
+    apply_edit_conflicts(&mut all_sessions);
+
+    // Record when each status began and demote long-waiting sessions to Idle
+    track_session_status(&mut all_sessions);
+    record_token_totals(&all_sessions);
+    record_resource_samples(&all_sessions, Utc::now());
+
+    // Policy rules see the same snapshot the frontend gets
+    {
+        let system_guard = SHARED_SYSTEM.lock().unwrap();
+        let is_running = |pid: u32, start_time: u64| system_guard.as_ref()
+            .and_then(|system| system.process(sysinfo::Pid::from_u32(pid)))
+            .is_some_and(|p| p.start_time() == start_time);
+        crate::policy::run_policies(&all_sessions, &is_running);
+    }
+
+    // Remote sessions were tracked by their host's collector and are only listed here
+    all_sessions.extend(crate::remote::remote_sessions());
 
     // Clean up stale status tracking entries for sessions that no longer exist
     let active_ids: HashSet<String> = all_sessions.iter().map(|s| s.id.clone()).collect();
@@ -96,13 +177,154 @@ pub fn get_all_sessions() -> SessionsResponse {
     });
 
     let waiting_count = all_sessions.iter()
-        .filter(|s| matches!(s.status, crate::session::SessionStatus::Waiting))
+        .filter(|s| matches!(s.status, SessionStatus::Waiting))
         .count();
     let total_count = all_sessions.len();
+    let groups = group_sessions(&all_sessions);
 
     SessionsResponse {
         sessions: all_sessions,
         total_count,
         waiting_count,
+        groups,
     }
 }
+
+fn record_reported_processes(sessions: &[Session], processes: &[Vec<AgentProcess>]) {
+    let by_pid: HashMap<u32, &AgentProcess> = processes.iter().flatten().map(|p| (p.pid, p)).collect();
+    let reported = sessions.iter()
+        .filter_map(|s| {
+            let process = by_pid.get(&s.pid)?;
+            Some((s.id.clone(), ProcessIdentity { pid: process.pid, start_time: process.start_time, cmd: process.cmd.clone() }))
+        })
+        .collect();
+    *REPORTED_PROCESSES.lock().unwrap() = reported;
+}
+
+/// Gracefully terminate a live session's agent and the processes it started.
+/// Blocks while escalating from SIGINT to SIGKILL.
+pub fn terminate_session(session_id: &str, options: &TerminateOptions) -> Result<TerminationResult, String> {
+    terminate::terminate_process_tree(&reported_process(session_id)?, options)
+}
+
+/// Stop a live session's agent and the processes it started with SIGSTOP
+pub fn pause_session(session_id: &str) -> Result<Vec<u32>, String> {
+    suspend::stop_process_tree(&reported_process(session_id)?)
+}
+
+/// Let a paused session's agent and its processes run again with SIGCONT
+pub fn continue_session(session_id: &str) -> Result<Vec<u32>, String> {
+    suspend::continue_process_tree(&reported_process(session_id)?)
+}
+
+/// Every Claude Code process running right now, read from a fresh process table.
+/// Unlike the last poll this includes agents the session list doesn't show.
+pub fn running_claude_processes() -> Vec<AgentProcess> {
+    let mut system_guard = SHARED_SYSTEM.lock().unwrap();
+    let system = system_guard.get_or_insert_with(System::new);
+    system.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::All,
+        ProcessRefreshKind::new()
+            .with_cmd(sysinfo::UpdateKind::Always)
+            .with_cwd(sysinfo::UpdateKind::Always)
+    );
+    claude::find_all_processes(system)
+}
+
+fn reported_process(session_id: &str) -> Result<ProcessIdentity, String> {
+    REPORTED_PROCESSES.lock().unwrap()
+        .get(session_id)
+        .cloned()
+        .ok_or_else(|| format!("No active session with id {}", session_id))
+}
+
+/// Terminate one process started by a live session's agent without touching the agent.
+/// The process table is re-read first so a reused pid isn't mistaken for the child.
+pub fn kill_child_process(session_id: &str, child_pid: u32) -> Result<(), String> {
+    let agent_pid = reported_process(session_id)?.pid;
+    let mut system_guard = SHARED_SYSTEM.lock().unwrap();
+    let system = system_guard.get_or_insert_with(System::new);
+    system.refresh_processes_specifics(sysinfo::ProcessesToUpdate::All, ProcessRefreshKind::new());
+    tree::terminate_child(system, agent_pid, child_pid)?;
+    log::info!("Terminated child process {} of agent process {}", child_pid, agent_pid);
+    Ok(())
+}
+
+/// Terminate a process an ended session left running, if the pid still
+/// belongs to the process started at `start_time`
+pub fn terminate_orphaned_process(pid: u32, start_time: u64) -> Result<(), String> {
+    let mut system_guard = SHARED_SYSTEM.lock().unwrap();
+    let system = system_guard.get_or_insert_with(System::new);
+    system.refresh_processes_specifics(sysinfo::ProcessesToUpdate::All, ProcessRefreshKind::new());
+    tree::terminate_orphan(system, pid, start_time)?;
+    log::info!("Terminated orphaned process {}", pid);
+    Ok(())
+}
+
+/// Get past sessions from all agents, grouped by project.
+/// Projects are ordered by their most recent session, sessions newest first.
+pub fn get_session_history() -> Vec<ProjectHistory> {
+    use std::collections::HashMap;
+
+    let mut by_project: HashMap<String, Vec<HistorySession>> = HashMap::new();
+    for detector in detectors() {
+        for session in detector.find_history() {
+            by_project.entry(session.project_path.clone()).or_default().push(session);
+        }
+    }
+
+    let mut projects: Vec<ProjectHistory> = by_project
+        .into_iter()
+        .map(|(project_path, mut sessions)| {
+            sessions.sort_by(|a, b| b.last_activity_at.cmp(&a.last_activity_at));
+            let project_name = project_path
+                .split('/')
+                .rfind(|s| !s.is_empty())
+                .unwrap_or("Unknown")
+                .to_string();
+            ProjectHistory {
+                project_name,
+                project_path,
+                sessions,
+            }
+        })
+        .collect();
+
+    projects.sort_by(|a, b| {
+        let latest_a = a.sessions.first().map(|s| s.last_activity_at.as_str()).unwrap_or("");
+        let latest_b = b.sessions.first().map(|s| s.last_activity_at.as_str()).unwrap_or("");
+        latest_b.cmp(latest_a)
+    });
+
+    projects
+}
+
+/// Session ids end up in shell command lines and file paths, so only plain ids are accepted
+fn validate_session_id(session_id: &str) -> Result<(), String> {
+    let is_valid_id = !session_id.is_empty()
+        && session_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if is_valid_id {
+        Ok(())
+    } else {
+        Err(format!("Invalid session id: {}", session_id))
+    }
+}
+
+fn detector_for(agent_type: &AgentType) -> Result<Box<dyn AgentDetector>, String> {
+    detectors()
+        .into_iter()
+        .find(|d| d.agent_type() == *agent_type)
+        .ok_or_else(|| format!("No detector for agent type {:?}", agent_type))
+}
+
+/// Build the shell command that resumes a past session for the given agent
+pub fn get_resume_command(agent_type: &AgentType, session_id: &str) -> Result<String, String> {
+    validate_session_id(session_id)?;
+    Ok(detector_for(agent_type)?.resume_command(session_id))
+}
+
+/// Read the full transcript of a live or past session
+pub fn load_transcript(agent_type: &AgentType, session_id: &str) -> Result<Transcript, String> {
+    validate_session_id(session_id)?;
+    detector_for(agent_type)?.load_transcript(session_id)
+}
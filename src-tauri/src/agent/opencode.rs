@@ -1,8 +1,13 @@
 use super::{AgentDetector, AgentProcess};
-use crate::session::{AgentType, Session, SessionStatus};
+use crate::export::{MessageRole, Transcript, TranscriptBlock, TranscriptMessage};
+use crate::process::SessionOrigin;
+use crate::session::{AgentType, HistorySession, Session, SessionStatus};
+use once_cell::sync::Lazy;
 use serde::Deserialize;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
 pub struct OpenCodeDetector;
 
@@ -25,6 +30,19 @@ impl AgentDetector for OpenCodeDetector {
         }
         get_opencode_sessions(processes)
     }
+
+    fn find_history(&self) -> Vec<HistorySession> {
+        get_opencode_history()
+    }
+
+    fn resume_command(&self, session_id: &str) -> String {
+        format!("opencode --session {}", session_id)
+    }
+
+    fn load_transcript(&self, session_id: &str) -> Result<Transcript, String> {
+        let storage_path = get_storage_path().ok_or("Could not determine home directory")?;
+        load_opencode_transcript(&storage_path, session_id)
+    }
 }
 
 // JSON structures for OpenCode data files
@@ -45,6 +63,9 @@ struct OpenCodeTime {
     created: u64,
     #[serde(default)]
     updated: u64,
+    /// When an assistant message finished
+    #[serde(default)]
+    completed: u64,
 }
 
 #[derive(Deserialize)]
@@ -68,14 +89,59 @@ struct OpenCodeMessage {
     role: String,
     #[serde(default)]
     time: OpenCodeTime,
+    #[serde(default, rename = "modelID")]
+    model_id: Option<String>,
+    #[serde(default)]
+    tokens: Option<OpenCodeTokens>,
+}
+
+#[derive(Deserialize, Default)]
+struct OpenCodeTokens {
+    #[serde(default)]
+    input: u64,
+    #[serde(default)]
+    output: u64,
+    #[serde(default)]
+    reasoning: u64,
+    #[serde(default)]
+    cache: OpenCodeCacheTokens,
+}
+
+#[derive(Deserialize, Default)]
+struct OpenCodeCacheTokens {
+    #[serde(default)]
+    read: u64,
+    #[serde(default)]
+    write: u64,
 }
 
 #[derive(Deserialize)]
 struct OpenCodePart {
+    #[serde(default)]
+    id: String,
     #[serde(rename = "type")]
     part_type: String,
     #[serde(default)]
     text: Option<String>,
+    /// Tool name of a "tool" part
+    #[serde(default)]
+    tool: Option<String>,
+    #[serde(default, rename = "callID")]
+    call_id: Option<String>,
+    #[serde(default)]
+    state: Option<OpenCodeToolState>,
+}
+
+#[derive(Deserialize)]
+struct OpenCodeToolState {
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    input: serde_json::Value,
+    #[serde(default)]
+    output: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
 }
 
 /// Find running opencode processes using the shared system snapshot
@@ -98,6 +164,13 @@ fn find_opencode_processes(system: &sysinfo::System) -> Vec<AgentProcess> {
                 pid: pid.as_u32(),
                 cpu_usage: cpu,
                 cwd,
+                cmd: process.cmd().iter().map(|s| s.to_string_lossy().to_string()).collect(),
+                memory: process.memory(),
+                start_time: process.start_time(),
+                stopped: process.status() == sysinfo::ProcessStatus::Stop,
+                // OpenCode storage is only read from the host's home
+                container: None,
+                origin: SessionOrigin::Terminal,
             });
         }
     }
@@ -112,8 +185,8 @@ fn get_opencode_sessions(processes: &[AgentProcess]) -> Vec<Session> {
 
     // OpenCode data directory: ~/.local/share/opencode/storage/
     // Note: OpenCode uses XDG convention, not macOS Application Support
-    let storage_path = match dirs::home_dir() {
-        Some(home) => home.join(".local").join("share").join("opencode").join("storage"),
+    let storage_path = match get_storage_path() {
+        Some(path) => path,
         None => return sessions,
     };
 
@@ -187,6 +260,171 @@ fn get_opencode_sessions(processes: &[AgentProcess]) -> Vec<Session> {
     sessions
 }
 
+/// Get the OpenCode storage directory (~/.local/share/opencode/storage)
+fn get_storage_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".local").join("share").join("opencode").join("storage"))
+}
+
+/// A parsed history entry along with the file state it was parsed from
+struct CachedHistoryEntry {
+    session_modified: SystemTime,
+    session_size: u64,
+    /// Adding a message adds a file to the directory, which updates its mtime
+    messages_modified: Option<SystemTime>,
+    /// None for sessions without messages, which aren't listed
+    session: Option<HistorySession>,
+}
+
+/// Cache parsed history entries by session file, like the Claude history.
+/// A session's entry needs every one of its message files, so it is only
+/// re-read when the session file or its message directory changes.
+static HISTORY_CACHE: Lazy<Mutex<HashMap<PathBuf, CachedHistoryEntry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// List all OpenCode sessions on disk, including ones without a running process
+fn get_opencode_history() -> Vec<HistorySession> {
+    let mut history = Vec::new();
+
+    let storage_path = match get_storage_path() {
+        Some(path) if path.exists() => path,
+        _ => return history,
+    };
+
+    // Session files live in one directory per project id, including "global"
+    let session_root = storage_path.join("session");
+    let projects: HashMap<String, String> = load_projects(&storage_path)
+        .into_iter()
+        .map(|p| (p.id, p.worktree))
+        .collect();
+    let mut seen_paths = HashSet::new();
+
+    for project_entry in std::fs::read_dir(&session_root).into_iter().flatten().flatten() {
+        let project_id = project_entry.file_name().to_string_lossy().to_string();
+
+        for entry in std::fs::read_dir(project_entry.path()).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if !path.extension().map(|e| e == "json").unwrap_or(false) {
+                continue;
+            }
+            seen_paths.insert(path.clone());
+            if let Some(session) = get_cached_history_entry(&storage_path, &path, projects.get(&project_id)) {
+                history.push(session);
+            }
+        }
+    }
+
+    // Drop cache entries for sessions that were deleted
+    HISTORY_CACHE.lock().unwrap().retain(|path, _| seen_paths.contains(path));
+
+    log::debug!("Found {} OpenCode history sessions", history.len());
+    history
+}
+
+/// Return the history entry for a session file, re-parsing only if it or its messages changed
+fn get_cached_history_entry(storage_path: &Path, path: &Path, worktree: Option<&String>) -> Option<HistorySession> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let session_modified = metadata.modified().ok()?;
+    let session_size = metadata.len();
+    let session_id = path.file_stem()?.to_string_lossy().to_string();
+    let messages_modified = std::fs::metadata(storage_path.join("message").join(&session_id))
+        .and_then(|m| m.modified())
+        .ok();
+
+    {
+        let cache = HISTORY_CACHE.lock().unwrap();
+        if let Some(cached) = cache.get(path) {
+            if cached.session_modified == session_modified
+                && cached.session_size == session_size
+                && cached.messages_modified == messages_modified
+            {
+                return cached.session.clone();
+            }
+        }
+    }
+
+    log::trace!("Parsing OpenCode history for {:?}", path);
+    let session = parse_history_session(storage_path, path, worktree);
+    HISTORY_CACHE.lock().unwrap().insert(path.to_path_buf(), CachedHistoryEntry {
+        session_modified,
+        session_size,
+        messages_modified,
+        session: session.clone(),
+    });
+    session
+}
+
+/// Build the history entry of a session file, None if it has no messages
+fn parse_history_session(storage_path: &Path, path: &Path, worktree: Option<&String>) -> Option<HistorySession> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let session = serde_json::from_str::<OpenCodeSession>(&content).ok()?;
+
+    let project_path = if !session.directory.is_empty() {
+        session.directory.clone()
+    } else {
+        worktree?.clone()
+    };
+
+    let (message_count, size_bytes, first_prompt) = get_message_summary(storage_path, &session.id);
+    if message_count == 0 {
+        return None;
+    }
+
+    Some(HistorySession {
+        id: session.id,
+        agent_type: AgentType::OpenCode,
+        project_path,
+        first_prompt,
+        title: session_title(&session.title),
+        last_activity_at: millis_to_rfc3339(session.time.updated).unwrap_or_else(|| "Unknown".to_string()),
+        message_count,
+        git_branch: None,
+        size_bytes,
+    })
+}
+
+/// Count a session's messages, their total size on disk, and find the first user prompt
+fn get_message_summary(storage_path: &Path, session_id: &str) -> (usize, u64, Option<String>) {
+    let message_dir = storage_path.join("message").join(session_id);
+
+    let mut size_bytes = 0;
+    let mut user_messages: Vec<(String, u64)> = Vec::new(); // (message_id, created)
+    let mut message_count = 0;
+
+    for entry in std::fs::read_dir(&message_dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if !path.extension().map(|e| e == "json").unwrap_or(false) {
+            continue;
+        }
+        size_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            if let Ok(msg) = serde_json::from_str::<OpenCodeMessage>(&content) {
+                message_count += 1;
+                if msg.role == "user" {
+                    user_messages.push((msg.id, msg.time.created));
+                }
+            }
+        }
+    }
+
+    // Oldest user message with displayable text is the first prompt
+    user_messages.sort_by_key(|(_, created)| *created);
+    let first_prompt = user_messages
+        .iter()
+        .find_map(|(message_id, _)| get_message_text(storage_path, message_id));
+
+    (message_count, size_bytes, first_prompt)
+}
+
+/// Get a display title for an OpenCode session.
+/// OpenCode names untitled sessions "New session - <timestamp>", which is not useful as a title.
+fn session_title(title: &str) -> Option<String> {
+    let title = title.trim();
+    if title.is_empty() || title.starts_with("New session") {
+        None
+    } else {
+        Some(title.to_string())
+    }
+}
+
 /// Load all project definitions
 fn load_projects(storage_path: &PathBuf) -> Vec<OpenCodeProject> {
     let project_dir = storage_path.join("project");
@@ -242,7 +480,7 @@ fn get_latest_session_for_project(
     let (session, _) = latest_session?;
 
     // Get the last message for status detection and display
-    let (last_role, last_message_text, _last_message_time) = get_last_message(storage_path, &session.id);
+    let (last_role, last_message_text, _last_message_time, model) = get_last_message(storage_path, &session.id);
 
     // Determine status
     let status = if process.cpu_usage > 5.0 {
@@ -280,38 +518,62 @@ fn get_latest_session_for_project(
         session.id, project_name, status, last_role, process.cpu_usage
     );
 
-    // Use message text if available, fall back to session title
-    let display_message = last_message_text
-        .or_else(|| Some(session.title.clone()).filter(|t| !t.is_empty()));
+    let title = session_title(&session.title);
 
     Some(Session {
         id: session.id,
         agent_type: AgentType::OpenCode,
         project_name,
         project_path: actual_path,
+        title,
         git_branch: None,
-        github_url: None,
+        repo_id: None,
+        worktree: None,
+        git_status: None,
+        remote: None,
+        model,
+        permission_mode: None,
+        cli_version: None,
+        context_tokens: None,
+        context_usage_percent: None,
+        compaction_count: 0,
+        total_input_tokens: 0,
+        total_output_tokens: 0,
         status,
-        last_message: display_message,
+        last_message: last_message_text,
         last_message_role: last_role,
+        status_since: last_activity_at.clone(),
         last_activity_at,
         pid: process.pid,
         cpu_usage: process.cpu_usage,
+        memory: 0,
+        process_started_at: None,
+        tree_cpu_usage: process.cpu_usage,
+        tree_memory: 0,
+        child_processes: Vec::new(),
+        container: None,
+        host: None,
+        origin: SessionOrigin::Terminal,
         active_subagent_count: 0,
+        edit_conflicts: Vec::new(),
+        flagged_rules: Vec::new(),
+        secret_finding_count: 0,
+        binding_ambiguous: false,
     })
 }
 
-/// Get the last message role, time, and text for a session
-fn get_last_message(storage_path: &PathBuf, session_id: &str) -> (Option<String>, Option<String>, u64) {
+/// Get the last message role, time, and text for a session, and the model of its latest assistant message
+fn get_last_message(storage_path: &PathBuf, session_id: &str) -> (Option<String>, Option<String>, u64, Option<String>) {
     let message_dir = storage_path.join("message").join(session_id);
 
     if !message_dir.exists() {
         log::debug!("Message dir does not exist: {:?}", message_dir);
-        return (None, None, 0);
+        return (None, None, 0, None);
     }
 
     // Collect all messages sorted by created time (descending)
     let mut messages: Vec<(String, String, u64)> = Vec::new(); // (role, message_id, created)
+    let mut latest_model: Option<(u64, String)> = None;
 
     if let Ok(entries) = std::fs::read_dir(&message_dir) {
         for entry in entries.flatten() {
@@ -319,6 +581,11 @@ fn get_last_message(storage_path: &PathBuf, session_id: &str) -> (Option<String>
             if path.extension().map(|e| e == "json").unwrap_or(false) {
                 if let Ok(content) = std::fs::read_to_string(&path) {
                     if let Ok(msg) = serde_json::from_str::<OpenCodeMessage>(&content) {
+                        if let Some(model) = msg.model_id.filter(|_| msg.role == "assistant") {
+                            if latest_model.as_ref().map(|(t, _)| msg.time.created >= *t).unwrap_or(true) {
+                                latest_model = Some((msg.time.created, model));
+                            }
+                        }
                         messages.push((msg.role, msg.id, msg.time.created));
                     }
                 }
@@ -330,6 +597,7 @@ fn get_last_message(storage_path: &PathBuf, session_id: &str) -> (Option<String>
     messages.sort_by(|a, b| b.2.cmp(&a.2));
 
     let message_count = messages.len();
+    let model = latest_model.map(|(_, model)| model);
 
     // Find the first message with displayable text (skip system prompts)
     for (role, message_id, time) in messages {
@@ -338,16 +606,16 @@ fn get_last_message(storage_path: &PathBuf, session_id: &str) -> (Option<String>
                 "Session {} has {} messages, showing: id={}, role={}, created={}, text={:?}",
                 session_id, message_count, message_id, role, time, &text[..text.len().min(50)]
             );
-            return (Some(role), Some(text), time);
+            return (Some(role), Some(text), time, model);
         }
     }
 
     log::debug!("Session {} has {} messages but no displayable text", session_id, message_count);
-    (None, None, 0)
+    (None, None, 0, model)
 }
 
 /// Get the text content from a message's parts
-fn get_message_text(storage_path: &PathBuf, message_id: &str) -> Option<String> {
+fn get_message_text(storage_path: &Path, message_id: &str) -> Option<String> {
     let part_dir = storage_path.join("part").join(message_id);
 
     if !part_dir.exists() {
@@ -435,7 +703,7 @@ fn get_global_session_for_directory(
     let (session, _) = latest_session?;
 
     // Get the last message for status detection and display
-    let (last_role, last_message_text, _last_message_time) = get_last_message(storage_path, &session.id);
+    let (last_role, last_message_text, _last_message_time, model) = get_last_message(storage_path, &session.id);
 
     // Determine status
     let status = if process.cpu_usage > 5.0 {
@@ -467,23 +735,134 @@ fn get_global_session_for_directory(
         session.id, session.directory, status, last_role, process.cpu_usage
     );
 
-    // Use message text if available, fall back to session title
-    let display_message = last_message_text
-        .or_else(|| Some(session.title.clone()).filter(|t| !t.is_empty()));
+    let title = session_title(&session.title);
 
     Some(Session {
         id: session.id,
         agent_type: AgentType::OpenCode,
         project_name,
         project_path: session.directory,
+        title,
         git_branch: None,
-        github_url: None,
+        repo_id: None,
+        worktree: None,
+        git_status: None,
+        remote: None,
+        model,
+        permission_mode: None,
+        cli_version: None,
+        context_tokens: None,
+        context_usage_percent: None,
+        compaction_count: 0,
+        total_input_tokens: 0,
+        total_output_tokens: 0,
         status,
-        last_message: display_message,
+        last_message: last_message_text,
         last_message_role: last_role,
+        status_since: last_activity_at.clone(),
         last_activity_at,
         pid: process.pid,
         cpu_usage: process.cpu_usage,
+        memory: 0,
+        process_started_at: None,
+        tree_cpu_usage: process.cpu_usage,
+        tree_memory: 0,
+        child_processes: Vec::new(),
+        container: None,
+        host: None,
+        origin: SessionOrigin::Terminal,
         active_subagent_count: 0,
+        edit_conflicts: Vec::new(),
+        flagged_rules: Vec::new(),
+        secret_finding_count: 0,
+        binding_ambiguous: false,
     })
 }
+
+fn millis_to_rfc3339(millis: u64) -> Option<String> {
+    chrono::DateTime::from_timestamp_millis(millis as i64)
+        .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+}
+
+/// Read all JSON files in a directory that parse as `T`
+fn read_json_dir<T: serde::de::DeserializeOwned>(dir: &Path) -> Vec<T> {
+    std::fs::read_dir(dir).into_iter().flatten().flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|e| e == "json").unwrap_or(false))
+        .filter_map(|path| serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok())
+        .collect()
+}
+
+/// Read a session's full transcript from OpenCode storage: messages in creation order,
+/// each with its parts in id order
+pub fn load_opencode_transcript(storage_path: &Path, session_id: &str) -> Result<Transcript, String> {
+    let session = std::fs::read_dir(storage_path.join("session")).into_iter().flatten().flatten()
+        .map(|project| project.path().join(format!("{}.json", session_id)))
+        .find_map(|path| serde_json::from_str::<OpenCodeSession>(&std::fs::read_to_string(path).ok()?).ok())
+        .ok_or_else(|| format!("No OpenCode session with id {}", session_id))?;
+
+    let mut messages: Vec<OpenCodeMessage> = read_json_dir(&storage_path.join("message").join(session_id));
+    messages.sort_by(|a, b| a.time.created.cmp(&b.time.created).then_with(|| a.id.cmp(&b.id)));
+
+    let mut transcript = Transcript {
+        session_id: session_id.to_string(),
+        agent_type: AgentType::OpenCode,
+        title: session_title(&session.title),
+        project_path: Some(session.directory.clone()).filter(|d| !d.is_empty()),
+        git_branch: None,
+        model: None,
+        started_at: messages.first().and_then(|m| millis_to_rfc3339(m.time.created)),
+        ended_at: messages.last().and_then(|m| millis_to_rfc3339(m.time.completed.max(m.time.created))),
+        input_tokens: 0,
+        output_tokens: 0,
+        messages: Vec::new(),
+    };
+
+    for message in messages {
+        let role = if message.role == "user" { MessageRole::User } else { MessageRole::Assistant };
+        if let Some(model) = message.model_id {
+            transcript.model = Some(model);
+        }
+        if let Some(tokens) = &message.tokens {
+            transcript.input_tokens += tokens.input + tokens.cache.read + tokens.cache.write;
+            transcript.output_tokens += tokens.output + tokens.reasoning;
+        }
+
+        let mut parts: Vec<OpenCodePart> = read_json_dir(&storage_path.join("part").join(&message.id));
+        parts.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut blocks = Vec::new();
+        for part in parts {
+            match part.part_type.as_str() {
+                "text" => blocks.extend(part.text.filter(|t| !t.trim().is_empty()).map(|text| TranscriptBlock::Text { text })),
+                "reasoning" => blocks.extend(part.text.filter(|t| !t.trim().is_empty()).map(|text| TranscriptBlock::Thinking { text })),
+                "tool" => {
+                    let id = part.call_id.unwrap_or(part.id);
+                    let Some(state) = part.state else {
+                        continue;
+                    };
+                    blocks.push(TranscriptBlock::ToolUse {
+                        id: id.clone(),
+                        name: part.tool.unwrap_or_default(),
+                        input: state.input,
+                    });
+                    if state.status == "completed" || state.status == "error" {
+                        blocks.push(TranscriptBlock::ToolResult {
+                            tool_use_id: id,
+                            output: state.output.or(state.error).unwrap_or_default(),
+                            is_error: state.status == "error",
+                            omitted_chars: 0,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !blocks.is_empty() {
+            transcript.messages.push(TranscriptMessage { role, timestamp: millis_to_rfc3339(message.time.created), blocks });
+        }
+    }
+
+    Ok(transcript)
+}
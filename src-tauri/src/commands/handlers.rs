@@ -2,7 +2,18 @@ use tauri::Manager;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 use std::sync::Mutex;
 
-use crate::session::{get_sessions, SessionsResponse};
+use crate::agent;
+use crate::export::{self, ExportOptions};
+use crate::policy::{self, PolicyActionRecord};
+use crate::process::{origin, SessionOrigin, TerminateOptions, TerminationResult};
+use crate::remote::{self, CollectorCommand, RemoteHost, RemoteHostStatus};
+use crate::session::{self, get_sessions, AgentType, ProjectHistory, SessionsResponse};
+use crate::session::activity::{ActivityReport, ProjectDayActivity, WeeklySessions};
+use crate::session::audit::AuditEntry;
+use crate::session::events::SessionEvent;
+use crate::session::files::TouchedFile;
+use crate::session::resources::ResourceSample;
+use crate::session::secrets::{RedactionResult, SecretFinding};
 use crate::terminal;
 
 // Store current shortcut for unregistration
@@ -14,18 +25,123 @@ pub fn get_all_sessions() -> SessionsResponse {
     get_sessions()
 }
 
-/// Focus the terminal containing a specific session
+/// Focus the terminal containing a specific session, on its remote host when `host` is set
 #[tauri::command]
-pub fn focus_session(pid: u32, project_path: String) -> Result<(), String> {
+pub fn focus_session(pid: u32, project_path: String, host: Option<String>) -> Result<(), String> {
+    if let Some(host) = host {
+        return remote::send_command(&host, CollectorCommand::Focus { pid, project_path }).map(|_| ());
+    }
     terminal::focus_terminal_for_pid(pid)
         .or_else(|_| terminal::focus_terminal_by_path(&project_path))
 }
 
+/// List past sessions per project, including ones whose agent has exited
+#[tauri::command]
+pub fn get_session_history() -> Vec<ProjectHistory> {
+    agent::get_session_history()
+}
+
+/// Resume a past session in a new terminal opened in its project directory
+#[tauri::command]
+pub fn resume_session(session_id: String, project_path: String, agent_type: AgentType) -> Result<(), String> {
+    let command = agent::get_resume_command(&agent_type, &session_id)?;
+
+    if !std::path::Path::new(&project_path).is_dir() {
+        return Err(format!("Project directory no longer exists: {}", project_path));
+    }
+
+    terminal::open_terminal_with_command(&project_path, &command)
+}
+
+/// Set how long a session waits for input before it is shown as idle (0 disables)
+#[tauri::command]
+pub fn set_idle_threshold(seconds: u64) {
+    session::tracker::set_idle_threshold(seconds);
+}
+
+/// Choose which launchers' sessions are listed (terminal, IDEs, SDK apps, headless runs)
+#[tauri::command]
+pub fn set_visible_origins(origins: Vec<SessionOrigin>) {
+    origin::set_visible_origins(origins);
+}
+
+/// Session lifecycle events recorded after sequence number `since` (all kept events if omitted)
+#[tauri::command]
+pub fn get_recent_events(since: Option<u64>) -> Vec<SessionEvent> {
+    session::events::recent_events(since)
+}
+
+/// CPU and memory of a live session's process tree over the last ten minutes
+#[tauri::command]
+pub fn get_resource_history(session_id: String) -> Result<Vec<ResourceSample>, String> {
+    session::resources::get_resource_history(&session_id)
+        .ok_or_else(|| format!("No active session with id {}", session_id))
+}
+
+/// Files a live session has edited, most recently touched first
+#[tauri::command]
+pub fn get_session_files(session_id: String) -> Result<Vec<TouchedFile>, String> {
+    session::files::get_touched_files(&session_id)
+        .ok_or_else(|| format!("No active session with id {}", session_id))
+}
+
+/// Commands, web requests and flagged edits of a live session, oldest first
+#[tauri::command]
+pub fn get_session_audit(session_id: String) -> Result<Vec<AuditEntry>, String> {
+    session::audit::get_audit_entries(&session_id)
+        .ok_or_else(|| format!("No active session with id {}", session_id))
+}
+
+/// Credentials found in a live session's transcript, in order of appearance
+#[tauri::command]
+pub fn get_session_secrets(session_id: String) -> Result<Vec<SecretFinding>, String> {
+    session::secrets::get_secret_findings(&session_id)
+        .ok_or_else(|| format!("No active session with id {}", session_id))
+}
+
+/// Replace the credentials in an ended session's transcript, keeping a backup of the original
+#[tauri::command]
+pub fn redact_session_secrets(session_id: String) -> Result<RedactionResult, String> {
+    session::secrets::redact_session_secrets(&session_id)
+}
+
+/// Time per status and tokens per project and day, over the last `days` days (default 30)
+#[tauri::command]
+pub fn get_project_time(days: Option<u32>) -> Result<Vec<ProjectDayActivity>, String> {
+    session::activity::project_time_report(days.unwrap_or(30))
+}
+
+/// Sessions started per week, over the last `weeks` weeks (default 12)
+#[tauri::command]
+pub fn get_weekly_sessions(weeks: Option<u32>) -> Result<Vec<WeeklySessions>, String> {
+    session::activity::weekly_sessions_report(weeks.unwrap_or(12))
+}
+
+/// Save a report as CSV in the downloads directory and return the file's path.
+/// `period` is in days for the project time report and in weeks for the weekly one.
+#[tauri::command]
+pub fn export_activity_csv(report: ActivityReport, period: Option<u32>) -> Result<String, String> {
+    let default_period = match report {
+        ActivityReport::ProjectTime => 30,
+        ActivityReport::WeeklySessions => 12,
+    };
+    session::activity::export_csv(report, period.unwrap_or(default_period))
+}
+
+/// Save a live or past session's transcript as Markdown, HTML or JSON in the
+/// downloads directory and return the file's path
+#[tauri::command]
+pub fn export_session(session_id: String, agent_type: AgentType, options: ExportOptions) -> Result<String, String> {
+    let transcript = agent::load_transcript(&agent_type, &session_id)?;
+    let path = export::write_export(transcript, &options)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
 /// Update the tray icon title with session counts
 #[tauri::command]
 pub fn update_tray_title(app: tauri::AppHandle, total: usize, waiting: usize) -> Result<(), String> {
     let title = if waiting > 0 {
-        format!("{} ({} idle)", total, waiting)
+        format!("{} ({} waiting)", total, waiting)
     } else if total > 0 {
         format!("{}", total)
     } else {
@@ -92,23 +208,69 @@ pub fn unregister_shortcut(app: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-/// Kill an agent process by PID
+/// Pause a session's agent and everything it started (SIGSTOP), e.g. while
+/// rebasing. Named apart from `resume_session`, which reopens past sessions.
 #[tauri::command]
-pub fn kill_session(pid: u32) -> Result<(), String> {
-    use std::process::Command;
+pub fn pause_session(session_id: String) -> Result<Vec<u32>, String> {
+    if let Some(host) = remote::host_of_session(&session_id) {
+        return send_remote(&host, CollectorCommand::Pause { session_id });
+    }
+    agent::pause_session(&session_id)
+}
 
-    // Use SIGKILL (-9) to forcefully terminate the process
-    // SIGTERM often doesn't work for agent processes with child processes
-    let output = Command::new("kill")
-        .arg("-9")
-        .arg(pid.to_string())
-        .output()
-        .map_err(|e| format!("Failed to execute kill command: {}", e))?;
+/// Let a paused session run again (SIGCONT)
+#[tauri::command]
+pub fn continue_session(session_id: String) -> Result<Vec<u32>, String> {
+    if let Some(host) = remote::host_of_session(&session_id) {
+        return send_remote(&host, CollectorCommand::Continue { session_id });
+    }
+    agent::continue_session(&session_id)
+}
 
-    if output.status.success() {
-        Ok(())
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Failed to kill process {}: {}", pid, stderr))
+/// Terminate a process started by an agent, e.g. a leftover dev server,
+/// leaving the agent itself running
+#[tauri::command]
+pub fn kill_child_process(session_id: String, child_pid: u32) -> Result<(), String> {
+    if let Some(host) = remote::host_of_session(&session_id) {
+        return remote::send_command(&host, CollectorCommand::KillChild { session_id, child_pid }).map(|_| ());
     }
+    agent::kill_child_process(&session_id, child_pid)
+}
+
+/// Terminate a session's agent and everything it started, escalating from
+/// SIGINT to SIGTERM to SIGKILL. Runs off the main thread since it waits for
+/// the processes to exit.
+#[tauri::command]
+pub async fn kill_session(session_id: String, options: Option<TerminateOptions>) -> Result<TerminationResult, String> {
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || match remote::host_of_session(&session_id) {
+        Some(host) => send_remote(&host, CollectorCommand::Kill { session_id, options: Some(options) }),
+        None => agent::terminate_session(&session_id, &options),
+    })
+        .await
+        .map_err(|e| format!("Termination task failed: {}", e))?
+}
+
+/// Actions taken by the rules in policies.json since the app started, including dry runs
+#[tauri::command]
+pub fn get_policy_actions() -> Vec<PolicyActionRecord> {
+    policy::get_policy_actions()
+}
+
+/// Send a command to a remote host's collector and read its result
+fn send_remote<T: serde::de::DeserializeOwned>(host: &str, command: CollectorCommand) -> Result<T, String> {
+    let value = remote::send_command(host, command)?;
+    serde_json::from_value(value).map_err(|e| format!("Unexpected reply from {}: {}", host, e))
+}
+
+/// Collect sessions from these machines over ssh, replacing the previous list
+#[tauri::command]
+pub fn set_remote_hosts(hosts: Vec<RemoteHost>) {
+    remote::set_remote_hosts(hosts);
+}
+
+/// Connection state of each remote host
+#[tauri::command]
+pub fn get_remote_host_status() -> Vec<RemoteHostStatus> {
+    remote::remote_host_status()
 }
@@ -0,0 +1,158 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use super::{MessageRole, Transcript, TranscriptBlock, TranscriptMessage};
+use crate::session::model::JsonlMessage;
+use crate::session::title::extract_title_from_jsonl;
+use crate::session::AgentType;
+
+/// Find a session's transcript in any project directory under ~/.claude/projects
+pub fn find_claude_transcript(session_id: &str) -> Option<PathBuf> {
+    let projects_dir = dirs::home_dir()?.join(".claude").join("projects");
+    let file_name = format!("{}.jsonl", session_id);
+    fs::read_dir(projects_dir).ok()?
+        .flatten()
+        .map(|entry| entry.path().join(&file_name))
+        .find(|path| path.is_file())
+}
+
+/// Read a Claude Code transcript. Subagent (sidechain) entries and meta entries
+/// injected by the CLI are left out; entries of one response are merged.
+pub fn load_claude_transcript(session_id: &str, jsonl_path: &Path) -> io::Result<Transcript> {
+    let reader = BufReader::new(File::open(jsonl_path)?);
+    let mut transcript = Transcript {
+        session_id: session_id.to_string(),
+        agent_type: AgentType::Claude,
+        title: extract_title_from_jsonl(jsonl_path),
+        project_path: None,
+        git_branch: None,
+        model: None,
+        started_at: None,
+        ended_at: None,
+        input_tokens: 0,
+        output_tokens: 0,
+        messages: Vec::new(),
+    };
+    let mut counted_message_ids: HashSet<String> = HashSet::new();
+    let mut last_message_id: Option<String> = None;
+
+    for line in reader.lines().map_while(Result::ok) {
+        let Ok(msg) = serde_json::from_str::<JsonlMessage>(&line) else {
+            continue;
+        };
+        if msg.is_sidechain == Some(true) || msg.is_meta == Some(true) {
+            continue;
+        }
+
+        if transcript.project_path.is_none() {
+            transcript.project_path = msg.cwd.clone();
+        }
+        if let Some(branch) = msg.git_branch.as_ref().filter(|b| !b.is_empty() && *b != "HEAD") {
+            transcript.git_branch = Some(branch.clone());
+        }
+
+        let (role, blocks, message_id) = match msg.msg_type.as_deref() {
+            Some("user") => {
+                let blocks = user_blocks(msg.message.as_ref().and_then(|m| m.content.as_ref()));
+                let role = if msg.is_compact_summary == Some(true) { MessageRole::System } else { MessageRole::User };
+                (role, blocks, None)
+            }
+            Some("assistant") => {
+                let Some(message) = &msg.message else {
+                    continue;
+                };
+                if let Some(model) = message.model.as_ref().filter(|m| *m != "<synthetic>") {
+                    transcript.model = Some(model.clone());
+                }
+                if let Some(usage) = &message.usage {
+                    let first_time = message.id.as_ref().map(|id| counted_message_ids.insert(id.clone())).unwrap_or(true);
+                    if first_time {
+                        transcript.input_tokens += usage.input_tokens.unwrap_or(0)
+                            + usage.cache_read_input_tokens.unwrap_or(0)
+                            + usage.cache_creation_input_tokens.unwrap_or(0);
+                        transcript.output_tokens += usage.output_tokens.unwrap_or(0);
+                    }
+                }
+                (MessageRole::Assistant, assistant_blocks(message.content.as_ref()), message.id.clone())
+            }
+            Some("system") if msg.subtype.as_deref() == Some("compact_boundary") => {
+                (MessageRole::System, vec![TranscriptBlock::Text { text: "Conversation compacted".to_string() }], None)
+            }
+            _ => continue,
+        };
+
+        if let Some(timestamp) = &msg.timestamp {
+            transcript.started_at.get_or_insert_with(|| timestamp.clone());
+            transcript.ended_at = Some(timestamp.clone());
+        }
+        if blocks.is_empty() {
+            continue;
+        }
+
+        // A response with several content blocks is written as one entry per block
+        let continues_response = message_id.is_some() && message_id == last_message_id;
+        match transcript.messages.last_mut() {
+            Some(last) if continues_response => last.blocks.extend(blocks),
+            _ => transcript.messages.push(TranscriptMessage { role, timestamp: msg.timestamp.clone(), blocks }),
+        }
+        last_message_id = message_id;
+    }
+
+    Ok(transcript)
+}
+
+fn user_blocks(content: Option<&serde_json::Value>) -> Vec<TranscriptBlock> {
+    match content {
+        Some(serde_json::Value::String(text)) if !text.trim().is_empty() => vec![TranscriptBlock::Text { text: text.clone() }],
+        Some(serde_json::Value::Array(items)) => items.iter()
+            .filter_map(|item| match item.get("type").and_then(|t| t.as_str())? {
+                "text" => Some(TranscriptBlock::Text { text: item.get("text")?.as_str()?.to_string() }),
+                "image" => Some(TranscriptBlock::Text { text: "[image]".to_string() }),
+                "tool_result" => Some(TranscriptBlock::ToolResult {
+                    tool_use_id: item.get("tool_use_id").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                    output: tool_result_text(item.get("content")),
+                    is_error: item.get("is_error").and_then(|v| v.as_bool()).unwrap_or(false),
+                    omitted_chars: 0,
+                }),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn assistant_blocks(content: Option<&serde_json::Value>) -> Vec<TranscriptBlock> {
+    let Some(serde_json::Value::Array(items)) = content else {
+        return Vec::new();
+    };
+    items.iter()
+        .filter_map(|item| match item.get("type").and_then(|t| t.as_str())? {
+            "text" => Some(TranscriptBlock::Text { text: item.get("text")?.as_str()?.to_string() }),
+            "thinking" => Some(TranscriptBlock::Thinking { text: item.get("thinking")?.as_str()?.to_string() }),
+            "tool_use" => Some(TranscriptBlock::ToolUse {
+                id: item.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                name: item.get("name").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                input: item.get("input").cloned().unwrap_or(serde_json::Value::Null),
+            }),
+            _ => None,
+        })
+        .filter(|block| !matches!(block, TranscriptBlock::Text { text } | TranscriptBlock::Thinking { text } if text.trim().is_empty()))
+        .collect()
+}
+
+/// Tool result content is a string or a list of text and image blocks
+fn tool_result_text(content: Option<&serde_json::Value>) -> String {
+    match content {
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(serde_json::Value::Array(items)) => items.iter()
+            .map(|item| match item.get("type").and_then(|t| t.as_str()) {
+                Some("image") => "[image]",
+                _ => item.get("text").and_then(|t| t.as_str()).unwrap_or_default(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
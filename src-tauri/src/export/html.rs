@@ -0,0 +1,110 @@
+use super::{
+    format_time, metadata_rows, omitted_note, pretty_input, role_name, title, tool_results, tool_summary,
+    tool_use_ids, visible_blocks, MessageRole, Transcript, TranscriptBlock,
+};
+
+const STYLE: &str = "\
+body { font: 14px/1.5 -apple-system, BlinkMacSystemFont, 'Segoe UI', sans-serif; max-width: 960px; margin: 2em auto; padding: 0 1em; color: #1f2328; }
+h1 { font-size: 1.6em; }
+table.meta td { padding: 2px 12px 2px 0; vertical-align: top; }
+table.meta td:first-child { color: #59636e; }
+.message { border-top: 1px solid #d1d9e0; padding: 0.5em 0; }
+.role { font-weight: 600; }
+.time { color: #59636e; font-size: 0.85em; margin-left: 0.5em; }
+.user .role { color: #0969da; }
+.system .role { color: #9a6700; }
+.text { white-space: pre-wrap; word-wrap: break-word; }
+details { margin: 0.5em 0; border: 1px solid #d1d9e0; border-radius: 6px; padding: 0.25em 0.75em; }
+summary { cursor: pointer; font-family: ui-monospace, SFMono-Regular, Menlo, monospace; font-size: 0.9em; }
+pre { background: #f6f8fa; padding: 0.75em; overflow-x: auto; white-space: pre-wrap; word-wrap: break-word; font-size: 0.85em; }
+.error { color: #cf222e; }
+.note { color: #59636e; font-style: italic; font-size: 0.85em; }
+";
+
+/// Escape text for HTML element content and attribute values
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Render a transcript as a single HTML page with inline styles and no scripts.
+/// Tool calls and thinking are collapsed in `<details>`.
+pub fn render(transcript: &Transcript) -> String {
+    let title = escape(&title(transcript));
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n<h1>{}</h1>\n<table class=\"meta\">\n",
+        title, STYLE, title
+    );
+    for (name, value) in metadata_rows(transcript) {
+        out.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", name, escape(&value)));
+    }
+    out.push_str("</table>\n");
+
+    let results = tool_results(transcript);
+    let call_ids = tool_use_ids(transcript);
+    for message in &transcript.messages {
+        let blocks = visible_blocks(message, &call_ids);
+        if blocks.is_empty() {
+            continue;
+        }
+
+        let class = match message.role {
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+            MessageRole::System => "system",
+        };
+        out.push_str(&format!("<section class=\"message {}\">\n<div><span class=\"role\">{}</span>", class, role_name(message.role)));
+        if let Some(timestamp) = &message.timestamp {
+            out.push_str(&format!("<span class=\"time\">{}</span>", escape(&format_time(timestamp))));
+        }
+        out.push_str("</div>\n");
+
+        for block in blocks {
+            match block {
+                TranscriptBlock::Text { text } => {
+                    out.push_str(&format!("<div class=\"text\">{}</div>\n", escape(text.trim_end())));
+                }
+                TranscriptBlock::Thinking { text } => {
+                    out.push_str(&format!("<details><summary>Thinking</summary><div class=\"text\">{}</div></details>\n", escape(text.trim_end())));
+                }
+                TranscriptBlock::ToolUse { id, name, input } => {
+                    out.push_str(&format!("<details><summary>{}</summary>\n<pre>{}</pre>\n", escape(&tool_summary(name, input)), escape(&pretty_input(input))));
+                    if let Some(result) = results.get(id.as_str()) {
+                        out.push_str(&render_result(result));
+                    }
+                    out.push_str("</details>\n");
+                }
+                TranscriptBlock::ToolResult { .. } => {
+                    out.push_str("<details><summary>Tool result</summary>\n");
+                    out.push_str(&render_result(block));
+                    out.push_str("</details>\n");
+                }
+            }
+        }
+        out.push_str("</section>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn render_result(block: &TranscriptBlock) -> String {
+    let TranscriptBlock::ToolResult { output, is_error, omitted_chars, .. } = block else {
+        return String::new();
+    };
+    let mut out = format!("<pre{}>{}</pre>\n", if *is_error { " class=\"error\"" } else { "" }, escape(output));
+    if let Some(note) = omitted_note(*omitted_chars) {
+        out.push_str(&format!("<div class=\"note\">{}</div>\n", escape(&note)));
+    }
+    out
+}
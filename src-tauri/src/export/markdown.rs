@@ -0,0 +1,85 @@
+use super::html::escape;
+use super::{
+    format_time, metadata_rows, omitted_note, pretty_input, role_name, title, tool_results, tool_summary,
+    tool_use_ids, visible_blocks, Transcript, TranscriptBlock,
+};
+
+/// A code fence longer than any backtick run in `text`, so the text can't close it
+fn fence(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn code_block(text: &str, language: &str) -> String {
+    let fence = fence(text);
+    format!("{}{}\n{}\n{}\n", fence, language, text.trim_end_matches('\n'), fence)
+}
+
+/// Render a transcript as Markdown. Tool calls and thinking are wrapped in
+/// `<details>`, which GitHub and most other renderers show collapsed.
+pub fn render(transcript: &Transcript) -> String {
+    let mut out = format!("# {}\n\n", title(transcript).replace('\n', " "));
+
+    out.push_str("| | |\n|---|---|\n");
+    for (name, value) in metadata_rows(transcript) {
+        out.push_str(&format!("| {} | {} |\n", name, value.replace('|', "\\|")));
+    }
+    out.push_str("\n---\n");
+
+    let results = tool_results(transcript);
+    let call_ids = tool_use_ids(transcript);
+    for message in &transcript.messages {
+        let blocks = visible_blocks(message, &call_ids);
+        if blocks.is_empty() {
+            continue;
+        }
+
+        out.push_str(&format!("\n## {}", role_name(message.role)));
+        if let Some(timestamp) = &message.timestamp {
+            out.push_str(&format!(" · {}", format_time(timestamp)));
+        }
+        out.push_str("\n\n");
+
+        for block in blocks {
+            match block {
+                TranscriptBlock::Text { text } => {
+                    out.push_str(text.trim_end());
+                    out.push_str("\n\n");
+                }
+                TranscriptBlock::Thinking { text } => {
+                    out.push_str("<details>\n<summary>Thinking</summary>\n\n");
+                    out.push_str(text.trim_end());
+                    out.push_str("\n\n</details>\n\n");
+                }
+                TranscriptBlock::ToolUse { id, name, input } => {
+                    out.push_str(&format!("<details>\n<summary>{}</summary>\n\n", escape(&tool_summary(name, input))));
+                    out.push_str(&code_block(&pretty_input(input), "json"));
+                    if let Some(result) = results.get(id.as_str()) {
+                        out.push('\n');
+                        out.push_str(&render_result(result));
+                    }
+                    out.push_str("\n</details>\n\n");
+                }
+                TranscriptBlock::ToolResult { .. } => {
+                    out.push_str("<details>\n<summary>Tool result</summary>\n\n");
+                    out.push_str(&render_result(block));
+                    out.push_str("\n</details>\n\n");
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn render_result(block: &TranscriptBlock) -> String {
+    let TranscriptBlock::ToolResult { output, is_error, omitted_chars, .. } = block else {
+        return String::new();
+    };
+    let mut out = String::from(if *is_error { "**Error**\n\n" } else { "**Output**\n\n" });
+    out.push_str(&code_block(output, ""));
+    if let Some(note) = omitted_note(*omitted_chars) {
+        out.push_str(&format!("\n_{}_\n", note));
+    }
+    out
+}
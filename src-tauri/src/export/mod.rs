@@ -0,0 +1,298 @@
+pub mod claude;
+mod html;
+mod markdown;
+
+use chrono::DateTime;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::session::AgentType;
+
+/// Tool output longer than this is cut off unless the caller asks otherwise
+pub const DEFAULT_MAX_TOOL_OUTPUT_CHARS: usize = 2000;
+
+/// A session's transcript in a form shared by all agents
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcript {
+    pub session_id: String,
+    pub agent_type: AgentType,
+    pub title: Option<String>,
+    pub project_path: Option<String>,
+    pub git_branch: Option<String>,
+    /// Model of the latest response
+    pub model: Option<String>,
+    /// Timestamps of the first and last message (RFC 3339)
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    /// Input tokens of all requests, including cached input
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub messages: Vec<TranscriptMessage>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageRole {
+    User,
+    Assistant,
+    /// Notes from the agent itself, e.g. that the conversation was compacted
+    System,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptMessage {
+    pub role: MessageRole,
+    pub timestamp: Option<String>,
+    pub blocks: Vec<TranscriptBlock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum TranscriptBlock {
+    Text { text: String },
+    Thinking { text: String },
+    ToolUse { id: String, name: String, input: serde_json::Value },
+    ToolResult {
+        tool_use_id: String,
+        output: String,
+        is_error: bool,
+        /// Characters cut from the end of `output`
+        #[serde(default, skip_serializing_if = "is_zero")]
+        omitted_chars: usize,
+    },
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub include_thinking: bool,
+    /// Longest tool output (and tool input string) kept, 0 keeps everything
+    #[serde(default = "default_max_tool_output_chars")]
+    pub max_tool_output_chars: usize,
+}
+
+fn default_max_tool_output_chars() -> usize {
+    DEFAULT_MAX_TOOL_OUTPUT_CHARS
+}
+
+/// Drop thinking if not wanted, shorten tool inputs and outputs and drop messages left empty
+pub fn apply_options(mut transcript: Transcript, options: &ExportOptions) -> Transcript {
+    let limit = options.max_tool_output_chars;
+    for message in &mut transcript.messages {
+        message.blocks.retain(|b| options.include_thinking || !matches!(b, TranscriptBlock::Thinking { .. }));
+        if limit == 0 {
+            continue;
+        }
+        for block in &mut message.blocks {
+            match block {
+                TranscriptBlock::ToolUse { input, .. } => truncate_strings(input, limit),
+                TranscriptBlock::ToolResult { output, omitted_chars, .. } => {
+                    *omitted_chars += truncate(output, limit);
+                }
+                _ => {}
+            }
+        }
+    }
+    transcript.messages.retain(|m| !m.blocks.is_empty());
+    transcript
+}
+
+/// Cut a string to `limit` characters, returning how many were removed
+fn truncate(text: &mut String, limit: usize) -> usize {
+    match text.char_indices().nth(limit) {
+        Some((cut, _)) => {
+            let omitted = text[cut..].chars().count();
+            text.truncate(cut);
+            omitted
+        }
+        None => 0,
+    }
+}
+
+/// Shorten long strings anywhere in a tool's input, e.g. the content of a Write
+fn truncate_strings(value: &mut serde_json::Value, limit: usize) {
+    match value {
+        serde_json::Value::String(s) => {
+            let omitted = truncate(s, limit);
+            if omitted > 0 {
+                s.push_str(&format!("… [{} more characters]", omitted));
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| truncate_strings(v, limit)),
+        serde_json::Value::Object(map) => map.values_mut().for_each(|v| truncate_strings(v, limit)),
+        _ => {}
+    }
+}
+
+/// Render a transcript in the requested format, after applying the options
+pub fn render(transcript: Transcript, options: &ExportOptions) -> Result<String, String> {
+    let transcript = apply_options(transcript, options);
+    match options.format {
+        ExportFormat::Markdown => Ok(markdown::render(&transcript)),
+        ExportFormat::Html => Ok(html::render(&transcript)),
+        ExportFormat::Json => serde_json::to_string_pretty(&transcript).map_err(|e| e.to_string()),
+    }
+}
+
+/// Render a transcript and save it in the downloads directory, returning the file's path
+pub fn write_export(transcript: Transcript, options: &ExportOptions) -> Result<PathBuf, String> {
+    let file_name = format!("session-{}.{}", transcript.session_id, options.format.extension());
+    let content = render(transcript, options)?;
+    let dir = dirs::download_dir().or_else(dirs::home_dir).ok_or("Could not determine downloads directory")?;
+    let path = dir.join(file_name);
+    fs::write(&path, content).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    info!("Exported session transcript to {:?}", path);
+    Ok(path)
+}
+
+// Helpers shared by the renderers
+
+fn agent_name(agent_type: &AgentType) -> &'static str {
+    match agent_type {
+        AgentType::Claude => "Claude Code",
+        AgentType::OpenCode => "OpenCode",
+    }
+}
+
+fn role_name(role: MessageRole) -> &'static str {
+    match role {
+        MessageRole::User => "User",
+        MessageRole::Assistant => "Assistant",
+        MessageRole::System => "System",
+    }
+}
+
+fn format_time(timestamp: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC%:z").to_string().replace("UTC+00:00", "UTC"))
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+/// "2024-01-01 10:00:00 UTC – 11:30:00 (1h 30m)"
+fn time_range(transcript: &Transcript) -> Option<String> {
+    let start = transcript.started_at.as_deref()?;
+    let Some(end) = transcript.ended_at.as_deref() else {
+        return Some(format_time(start));
+    };
+    let duration = DateTime::parse_from_rfc3339(end).ok()
+        .zip(DateTime::parse_from_rfc3339(start).ok())
+        .map(|(end, start)| (end - start).num_minutes())
+        .map(|minutes| if minutes >= 60 { format!(" ({}h {}m)", minutes / 60, minutes % 60) } else { format!(" ({}m)", minutes) })
+        .unwrap_or_default();
+    Some(format!("{} – {}{}", format_time(start), format_time(end), duration))
+}
+
+fn format_tokens(tokens: u64) -> String {
+    match tokens {
+        0..=999 => tokens.to_string(),
+        1_000..=999_999 => format!("{:.1}k", tokens as f64 / 1000.0),
+        _ => format!("{:.1}M", tokens as f64 / 1_000_000.0),
+    }
+}
+
+/// Header rows shared by the Markdown and HTML exports
+fn metadata_rows(transcript: &Transcript) -> Vec<(&'static str, String)> {
+    let mut rows = vec![("Agent", agent_name(&transcript.agent_type).to_string())];
+    if let Some(project) = &transcript.project_path {
+        rows.push(("Project", project.clone()));
+    }
+    if let Some(branch) = &transcript.git_branch {
+        rows.push(("Branch", branch.clone()));
+    }
+    if let Some(model) = &transcript.model {
+        rows.push(("Model", model.clone()));
+    }
+    if transcript.input_tokens > 0 || transcript.output_tokens > 0 {
+        rows.push(("Tokens", format!("{} in / {} out", format_tokens(transcript.input_tokens), format_tokens(transcript.output_tokens))));
+    }
+    if let Some(range) = time_range(transcript) {
+        rows.push(("Time", range));
+    }
+    rows.push(("Session", transcript.session_id.clone()));
+    rows
+}
+
+fn title(transcript: &Transcript) -> String {
+    transcript.title.clone().unwrap_or_else(|| format!("Session {}", transcript.session_id))
+}
+
+/// One line describing a tool call, e.g. the command of a Bash call
+fn tool_summary(name: &str, input: &serde_json::Value) -> String {
+    let field = ["command", "file_path", "notebook_path", "path", "url", "pattern", "query", "description", "filePath"]
+        .iter()
+        .find_map(|key| input.get(*key).and_then(|v| v.as_str()));
+    let Some(detail) = field else {
+        return name.to_string();
+    };
+    let line = detail.lines().next().unwrap_or_default();
+    let mut short: String = line.chars().take(100).collect();
+    if short.len() < detail.len() {
+        short.push('…');
+    }
+    format!("{}: {}", name, short)
+}
+
+fn pretty_input(input: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(input).unwrap_or_default()
+}
+
+fn omitted_note(omitted_chars: usize) -> Option<String> {
+    (omitted_chars > 0).then(|| format!("… {} more characters not shown", omitted_chars))
+}
+
+/// Tool results by the id of their tool call, so they can be shown with the call
+fn tool_results(transcript: &Transcript) -> std::collections::HashMap<&str, &TranscriptBlock> {
+    transcript.messages.iter()
+        .flat_map(|m| &m.blocks)
+        .filter_map(|b| match b {
+            TranscriptBlock::ToolResult { tool_use_id, .. } => Some((tool_use_id.as_str(), b)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Ids of all tool calls, to tell which results are shown with their call
+fn tool_use_ids(transcript: &Transcript) -> std::collections::HashSet<&str> {
+    transcript.messages.iter()
+        .flat_map(|m| &m.blocks)
+        .filter_map(|b| match b {
+            TranscriptBlock::ToolUse { id, .. } => Some(id.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Blocks shown for a message: results of known tool calls are shown with the call instead
+fn visible_blocks<'a>(message: &'a TranscriptMessage, call_ids: &std::collections::HashSet<&str>) -> Vec<&'a TranscriptBlock> {
+    message.blocks.iter()
+        .filter(|b| !matches!(b, TranscriptBlock::ToolResult { tool_use_id, .. } if call_ids.contains(tool_use_id.as_str())))
+        .collect()
+}
@@ -0,0 +1,7 @@
+pub mod remote;
+pub mod repo;
+pub mod status;
+
+pub use remote::{get_remote, GitRemote, RemoteProvider};
+pub use repo::{repo_name, resolve_repo, RepoIdentity};
+pub use status::{git_status, retain_git_status, GitOperation, GitStatus};
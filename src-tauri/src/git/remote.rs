@@ -0,0 +1,264 @@
+use log::{debug, trace};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+/// Hosting service of a remote, which decides how branch and compare URLs look
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteProvider {
+    GitHub,
+    GitLab,
+    Bitbucket,
+    Gitea,
+    Unknown,
+}
+
+/// Web links for a repository's `origin` remote
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRemote {
+    pub provider: RemoteProvider,
+    /// Web page of the repository, e.g. "https://gitlab.example.com/group/app"
+    pub repo_url: String,
+    /// Branch `origin/HEAD` points to
+    pub default_branch: Option<String>,
+    /// Web page of the session's branch, None if the branch or provider is unknown
+    pub branch_url: Option<String>,
+    /// Comparison of the session's branch against the default branch
+    pub compare_url: Option<String>,
+}
+
+/// The parts of a remote URL that identify a repository
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteUrl {
+    pub host: String,
+    /// Port of an http(s) remote; ssh ports don't carry over to the web UI
+    pub web_port: Option<u16>,
+    /// "http" for plain http remotes, "https" for everything else
+    pub web_scheme: &'static str,
+    /// Repository path without leading slash or `.git`, e.g. "group/subgroup/app"
+    pub path: String,
+    /// True for ssh and scp-style remotes, whose host may be an alias from ~/.ssh/config
+    pub is_ssh: bool,
+}
+
+/// Parse the remote URL formats git accepts:
+/// - scp-style: `git@host:group/app.git`
+/// - `ssh://[user@]host[:port]/group/app.git` (and `git+ssh://`, `git://`)
+/// - `https://[user[:password]@]host[:port]/group/app.git`, credentials are dropped
+pub fn parse_remote_url(url: &str) -> Option<RemoteUrl> {
+    let url = url.trim();
+
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme.to_ascii_lowercase()), rest),
+        None => (None, url),
+    };
+
+    let (authority, path, is_ssh, web_scheme) = match scheme.as_deref() {
+        Some("https") | Some("http") => {
+            let (authority, path) = rest.split_once('/')?;
+            let web_scheme = if scheme.as_deref() == Some("http") { "http" } else { "https" };
+            (authority, path, false, web_scheme)
+        }
+        Some("ssh") | Some("git+ssh") | Some("ssh+git") | Some("git") => {
+            let (authority, path) = rest.split_once('/')?;
+            (authority, path, true, "https")
+        }
+        Some(_) => return None,
+        None => {
+            // scp-style needs a colon before the first slash, otherwise it's a local path
+            let (authority, path) = rest.split_once(':')?;
+            if authority.contains('/') {
+                return None;
+            }
+            (authority, path, true, "https")
+        }
+    };
+
+    // Drop "user@" / "user:password@"
+    let host_port = authority.rsplit_once('@').map(|(_, h)| h).unwrap_or(authority);
+    let (host, port) = match host_port.rsplit_once(':').map(|(h, p)| (h, p.parse::<u16>())) {
+        Some((host, Ok(port))) => (host, Some(port)),
+        _ => (host_port, None),
+    };
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path).trim_end_matches('/');
+    // ssh://host/~user/app refers to a home directory, which has no web page
+    if host.is_empty() || path.is_empty() || path.starts_with('~') {
+        return None;
+    }
+
+    Some(RemoteUrl {
+        host: host.to_ascii_lowercase(),
+        web_port: if is_ssh { None } else { port },
+        web_scheme,
+        path: path.to_string(),
+        is_ssh,
+    })
+}
+
+/// Read host aliases (`Host` -> `HostName`) from an ssh config file.
+/// Wildcard patterns are skipped, they can't be resolved to one host.
+pub fn parse_ssh_config(content: &str) -> HashMap<String, String> {
+    let mut aliases = HashMap::new();
+    let mut current_hosts: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // "Keyword value" or "Keyword=value"
+        let (keyword, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+            Some((k, v)) => (k, v.trim_start_matches(|c: char| c.is_whitespace() || c == '=').trim()),
+            None => continue,
+        };
+
+        if keyword.eq_ignore_ascii_case("Host") {
+            current_hosts = value.split_whitespace()
+                .filter(|h| !h.contains(['*', '?', '!']))
+                .map(|h| h.to_ascii_lowercase())
+                .collect();
+        } else if keyword.eq_ignore_ascii_case("Match") {
+            current_hosts.clear();
+        } else if keyword.eq_ignore_ascii_case("HostName") {
+            // The first value for a host wins, as in ssh itself
+            for host in &current_hosts {
+                aliases.entry(host.clone()).or_insert_with(|| value.to_ascii_lowercase());
+            }
+        }
+    }
+
+    aliases
+}
+
+/// Guess the hosting service from a host name; self-hosted instances
+/// usually keep the product name somewhere in it (github.example.com, gitlab.corp)
+pub fn provider_for_host(host: &str) -> RemoteProvider {
+    if host.contains("github") {
+        RemoteProvider::GitHub
+    } else if host.contains("gitlab") {
+        RemoteProvider::GitLab
+    } else if host.contains("bitbucket") {
+        RemoteProvider::Bitbucket
+    } else if host.contains("gitea") || host.contains("codeberg") || host.contains("forgejo") {
+        RemoteProvider::Gitea
+    } else {
+        RemoteProvider::Unknown
+    }
+}
+
+impl GitRemote {
+    /// Build the repository link from a remote URL, resolving ssh host aliases
+    pub fn from_url(url: &str, ssh_aliases: &HashMap<String, String>, default_branch: Option<String>) -> Option<GitRemote> {
+        let remote = parse_remote_url(url)?;
+        let host = if remote.is_ssh {
+            ssh_aliases.get(&remote.host).cloned().unwrap_or(remote.host)
+        } else {
+            remote.host
+        };
+        let port = remote.web_port.map(|p| format!(":{}", p)).unwrap_or_default();
+
+        Some(GitRemote {
+            provider: provider_for_host(&host),
+            repo_url: format!("{}://{}{}/{}", remote.web_scheme, host, port, remote.path),
+            default_branch,
+            branch_url: None,
+            compare_url: None,
+        })
+    }
+
+    /// Fill in the branch and compare URLs for a session's branch
+    pub fn with_branch(mut self, branch: Option<&str>) -> GitRemote {
+        let Some(branch) = branch.filter(|b| !b.is_empty() && *b != "HEAD") else {
+            return self;
+        };
+        let repo = &self.repo_url;
+        let encoded = encode_branch(branch);
+
+        self.branch_url = match self.provider {
+            RemoteProvider::GitHub => Some(format!("{}/tree/{}", repo, encoded)),
+            RemoteProvider::GitLab => Some(format!("{}/-/tree/{}", repo, encoded)),
+            RemoteProvider::Bitbucket => Some(format!("{}/src/{}", repo, encoded)),
+            RemoteProvider::Gitea => Some(format!("{}/src/branch/{}", repo, encoded)),
+            RemoteProvider::Unknown => None,
+        };
+
+        if let Some(default) = self.default_branch.as_deref().filter(|d| *d != branch) {
+            let default = encode_branch(default);
+            self.compare_url = match self.provider {
+                RemoteProvider::GitHub | RemoteProvider::Gitea => Some(format!("{}/compare/{}...{}", repo, default, encoded)),
+                RemoteProvider::GitLab => Some(format!("{}/-/compare/{}...{}", repo, default, encoded)),
+                RemoteProvider::Bitbucket => Some(format!("{}/branches/compare/{}%0D{}", repo, encoded, default)),
+                RemoteProvider::Unknown => None,
+            };
+        }
+
+        self
+    }
+}
+
+/// Percent-encode a branch name for a URL path, keeping `/` separators readable
+fn encode_branch(branch: &str) -> String {
+    let mut encoded = String::with_capacity(branch.len());
+    for byte in branch.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Cache remotes by project path — the origin remote doesn't change during the app's lifetime
+static REMOTE_CACHE: Lazy<Mutex<HashMap<String, Option<GitRemote>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Host aliases from ~/.ssh/config, read once
+static SSH_ALIASES: Lazy<HashMap<String, String>> = Lazy::new(|| {
+    let aliases = dirs::home_dir()
+        .and_then(|home| fs::read_to_string(home.join(".ssh").join("config")).ok())
+        .map(|content| parse_ssh_config(&content))
+        .unwrap_or_default();
+    debug!("Loaded {} ssh host aliases", aliases.len());
+    aliases
+});
+
+/// Get the `origin` remote of a project directory (cached), with the given branch's links
+pub fn get_remote(project_path: &str, branch: Option<&str>) -> Option<GitRemote> {
+    let cached = REMOTE_CACHE.lock().unwrap().get(project_path).cloned();
+    let remote = match cached {
+        Some(remote) => remote,
+        None => {
+            let remote = get_remote_uncached(Path::new(project_path));
+            // Cache the result (including None) so we don't retry failed lookups
+            REMOTE_CACHE.lock().unwrap().insert(project_path.to_string(), remote.clone());
+            remote
+        }
+    };
+    remote.map(|r| r.with_branch(branch))
+}
+
+fn get_remote_uncached(project_path: &Path) -> Option<GitRemote> {
+    let url = run_git(project_path, &["remote", "get-url", "origin"])?;
+    // Set by clone; missing in repositories where origin was added by hand
+    let default_branch = run_git(project_path, &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"])
+        .map(|r| r.strip_prefix("origin/").map(String::from).unwrap_or(r));
+
+    let remote = GitRemote::from_url(&url, &SSH_ALIASES, default_branch);
+    trace!("Remote for {:?}: {} -> {:?}", project_path, url, remote);
+    remote
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|s| !s.is_empty())
+}
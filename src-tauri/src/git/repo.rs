@@ -0,0 +1,99 @@
+use log::{debug, trace};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+/// Which repository and working tree a directory belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoIdentity {
+    /// Canonical path of the common git dir, shared by all worktrees of a repository
+    pub repo_id: String,
+    /// Display name of the repository, from the main working tree's directory
+    pub repo_name: String,
+    /// Top-level directory of the working tree containing the path
+    pub worktree_root: PathBuf,
+    /// Directory name of the linked worktree, None for the main working tree
+    pub worktree: Option<String>,
+    /// Git dir of this working tree (`.git`, or `.git/worktrees/<name>` for linked worktrees)
+    pub git_dir: PathBuf,
+}
+
+/// Cache repository lookups by project path — a directory doesn't move between repositories
+static REPO_CACHE: Lazy<Mutex<HashMap<String, Option<RepoIdentity>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Resolve the repository of a project directory (cached)
+pub fn resolve_repo(project_path: &str) -> Option<RepoIdentity> {
+    {
+        let cache = REPO_CACHE.lock().unwrap();
+        if let Some(cached) = cache.get(project_path) {
+            return cached.clone();
+        }
+    }
+
+    let result = find_repo(Path::new(project_path));
+    debug!("Resolved repository for {}: {:?}", project_path, result.as_ref().map(|r| &r.repo_id));
+
+    REPO_CACHE.lock().unwrap().insert(project_path.to_string(), result.clone());
+    result
+}
+
+/// Find the repository containing `path` by reading `.git` entries from the file system.
+/// Doesn't spawn git, so it's cheap enough to run for every session.
+pub fn find_repo(path: &Path) -> Option<RepoIdentity> {
+    let (worktree_root, git_dir) = path.ancestors().find_map(|dir| {
+        let dot_git = dir.join(".git");
+        if dot_git.is_dir() {
+            Some((dir.to_path_buf(), dot_git))
+        } else if dot_git.is_file() {
+            // Linked worktrees and submodules have a `.git` file pointing to their git dir
+            read_gitdir_file(&dot_git).map(|git_dir| (dir.to_path_buf(), git_dir))
+        } else {
+            None
+        }
+    })?;
+    trace!("Found git dir {:?} for {:?}", git_dir, path);
+
+    // Linked worktrees record the shared git dir in `commondir`, relative to their own git dir
+    let common_dir = fs::read_to_string(git_dir.join("commondir"))
+        .ok()
+        .map(|common| git_dir.join(common.trim()))
+        .unwrap_or_else(|| git_dir.clone());
+    let common_dir = common_dir.canonicalize().unwrap_or(common_dir);
+    let canonical_git_dir = git_dir.canonicalize().unwrap_or_else(|_| git_dir.clone());
+
+    let worktree = (canonical_git_dir != common_dir)
+        .then(|| worktree_root.file_name())
+        .flatten()
+        .map(|name| name.to_string_lossy().to_string());
+
+    Some(RepoIdentity {
+        repo_id: common_dir.to_string_lossy().to_string(),
+        repo_name: repo_name(&common_dir),
+        worktree_root,
+        worktree,
+        git_dir: canonical_git_dir,
+    })
+}
+
+/// Read the target of a `gitdir: <path>` file, resolved relative to the file's directory
+fn read_gitdir_file(dot_git: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(dot_git).ok()?;
+    let target = content.lines().find_map(|l| l.strip_prefix("gitdir:"))?.trim();
+    Some(dot_git.parent()?.join(target))
+}
+
+/// Name a repository after its main working tree ("/src/app/.git" -> "app"),
+/// or after the directory of a bare repository ("/srv/app.git" -> "app")
+pub fn repo_name(common_dir: &Path) -> String {
+    let name = common_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    if name == ".git" {
+        common_dir.parent()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or(name)
+    } else {
+        name.strip_suffix(".git").map(String::from).unwrap_or(name)
+    }
+}
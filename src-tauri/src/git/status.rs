@@ -0,0 +1,246 @@
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+
+use super::repo::RepoIdentity;
+
+/// How long a working-tree status is reused before it is refreshed
+pub const STATUS_TTL: Duration = Duration::from_secs(10);
+
+/// Maximum number of refreshes waiting for the worker; further requests are dropped
+/// until the queue drains, and picked up again on a later poll
+const QUEUE_CAPACITY: usize = 32;
+
+/// Operation that stopped halfway and needs the user to continue or abort it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum GitOperation {
+    Merge,
+    Rebase,
+}
+
+/// State of a working tree as reported by `git status`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatus {
+    /// Tracked files with staged or unstaged changes
+    pub modified: usize,
+    pub untracked: usize,
+    /// Files with unresolved merge conflicts
+    pub conflicted: usize,
+    /// Lines added and removed versus HEAD, staged and unstaged
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Upstream branch such as "origin/main", None if the branch doesn't track one
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub last_commit_subject: Option<String>,
+    pub operation: Option<GitOperation>,
+}
+
+/// Parse the output of `git status --porcelain=v2 --branch`
+pub fn parse_porcelain_status(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+
+    for line in output.lines() {
+        if let Some(upstream) = line.strip_prefix("# branch.upstream ") {
+            status.upstream = Some(upstream.to_string());
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            // "# branch.ab +1 -2"
+            for part in ab.split_whitespace() {
+                if let Some(n) = part.strip_prefix('+') {
+                    status.ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = part.strip_prefix('-') {
+                    status.behind = n.parse().unwrap_or(0);
+                }
+            }
+        } else {
+            match line.split(' ').next() {
+                Some("1") | Some("2") => status.modified += 1,
+                Some("u") => status.conflicted += 1,
+                Some("?") => status.untracked += 1,
+                _ => {}
+            }
+        }
+    }
+
+    status
+}
+
+/// Parse the summary line of `git diff --shortstat` into (insertions, deletions)
+pub fn parse_shortstat(output: &str) -> (usize, usize) {
+    // " 3 files changed, 10 insertions(+), 2 deletions(-)"
+    let mut insertions = 0;
+    let mut deletions = 0;
+    for part in output.trim().split(", ") {
+        let mut words = part.split_whitespace();
+        let count = words.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+        match words.next() {
+            Some(w) if w.starts_with("insertion") => insertions = count,
+            Some(w) if w.starts_with("deletion") => deletions = count,
+            _ => {}
+        }
+    }
+    (insertions, deletions)
+}
+
+/// Find an interrupted merge or rebase from the marker files in a working tree's git dir
+pub fn detect_operation(git_dir: &Path) -> Option<GitOperation> {
+    if git_dir.join("rebase-merge").is_dir() || git_dir.join("rebase-apply").is_dir() {
+        Some(GitOperation::Rebase)
+    } else if git_dir.join("MERGE_HEAD").is_file() {
+        Some(GitOperation::Merge)
+    } else {
+        None
+    }
+}
+
+/// Run git in a working tree and return its stdout, None if it fails
+fn run_git(worktree_root: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(worktree_root)
+        // Don't take the index lock for the stat refresh, so we never block the agent's own git calls
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        trace!("git {:?} failed in {:?}: {}", args, worktree_root, String::from_utf8_lossy(&output.stderr).trim());
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Read the status of a working tree by spawning git. Slow in large repositories,
+/// so sessions get it through [`git_status`] instead.
+pub fn read_git_status(worktree_root: &Path, git_dir: &Path) -> Option<GitStatus> {
+    let porcelain = run_git(worktree_root, &["status", "--porcelain=v2", "--branch"])?;
+    let mut status = parse_porcelain_status(&porcelain);
+
+    // Both fail in a repository without commits, which has nothing to compare against
+    if let Some(shortstat) = run_git(worktree_root, &["diff", "--shortstat", "HEAD"]) {
+        (status.insertions, status.deletions) = parse_shortstat(&shortstat);
+    }
+    status.last_commit_subject = run_git(worktree_root, &["log", "-1", "--format=%s"])
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    status.operation = detect_operation(git_dir);
+
+    Some(status)
+}
+
+struct CacheEntry {
+    status: Option<GitStatus>,
+    fetched_at: Instant,
+}
+
+/// Working-tree statuses by worktree root, plus the refreshes that are queued
+pub struct GitStatusCache {
+    ttl: Duration,
+    entries: HashMap<PathBuf, CacheEntry>,
+    pending: HashSet<PathBuf>,
+}
+
+impl GitStatusCache {
+    pub fn new(ttl: Duration) -> Self {
+        GitStatusCache { ttl, entries: HashMap::new(), pending: HashSet::new() }
+    }
+
+    /// Get the cached status (possibly stale) and whether a refresh should be queued.
+    /// A refresh is only requested once until its result is stored or cancelled.
+    pub fn lookup(&mut self, worktree_root: &Path, now: Instant) -> (Option<GitStatus>, bool) {
+        let entry = self.entries.get(worktree_root);
+        let fresh = entry.map(|e| now.duration_since(e.fetched_at) < self.ttl).unwrap_or(false);
+        let status = entry.and_then(|e| e.status.clone());
+
+        if fresh || self.pending.contains(worktree_root) {
+            return (status, false);
+        }
+        self.pending.insert(worktree_root.to_path_buf());
+        (status, true)
+    }
+
+    /// Store the result of a refresh. Results for worktrees dropped by `retain` are discarded.
+    pub fn store(&mut self, worktree_root: &Path, status: Option<GitStatus>, now: Instant) {
+        if self.pending.remove(worktree_root) {
+            self.entries.insert(worktree_root.to_path_buf(), CacheEntry { status, fetched_at: now });
+        }
+    }
+
+    /// Forget a refresh that couldn't be queued, so the next lookup requests it again
+    pub fn cancel(&mut self, worktree_root: &Path) {
+        self.pending.remove(worktree_root);
+    }
+
+    /// Forget worktrees no session is using anymore
+    pub fn retain(&mut self, live: &HashSet<PathBuf>) {
+        self.entries.retain(|root, _| live.contains(root));
+        self.pending.retain(|root| live.contains(root));
+    }
+}
+
+/// A working tree whose status should be refreshed
+struct StatusJob {
+    worktree_root: PathBuf,
+    git_dir: PathBuf,
+}
+
+/// Status cache shared with a single background worker that runs git
+struct GitStatusService {
+    cache: Mutex<GitStatusCache>,
+    jobs: SyncSender<StatusJob>,
+}
+
+static GIT_STATUS_SERVICE: Lazy<GitStatusService> = Lazy::new(|| {
+    let (jobs, queue) = mpsc::sync_channel::<StatusJob>(QUEUE_CAPACITY);
+
+    let spawned = thread::Builder::new()
+        .name("git-status".to_string())
+        .spawn(move || {
+            for job in queue {
+                let started = Instant::now();
+                let status = read_git_status(&job.worktree_root, &job.git_dir);
+                debug!("Refreshed git status of {:?} in {:?}", job.worktree_root, started.elapsed());
+                GIT_STATUS_SERVICE.cache.lock().unwrap().store(&job.worktree_root, status, Instant::now());
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to start git status worker: {}", e);
+    }
+
+    GitStatusService { cache: Mutex::new(GitStatusCache::new(STATUS_TTL)), jobs }
+});
+
+/// Get the working-tree status of a repository without blocking on git.
+/// Returns the last known status and queues a refresh once it's older than [`STATUS_TTL`],
+/// so a working tree shows no status until its first refresh completes.
+pub fn git_status(repo: &RepoIdentity) -> Option<GitStatus> {
+    let service = &*GIT_STATUS_SERVICE;
+    let (status, needs_refresh) = service.cache.lock().unwrap().lookup(&repo.worktree_root, Instant::now());
+
+    if needs_refresh {
+        let job = StatusJob { worktree_root: repo.worktree_root.clone(), git_dir: repo.git_dir.clone() };
+        if let Err(e) = service.jobs.try_send(job) {
+            if let TrySendError::Full(_) = e {
+                debug!("Git status queue is full, skipping {:?} this poll", repo.worktree_root);
+            }
+            service.cache.lock().unwrap().cancel(&repo.worktree_root);
+        }
+    }
+
+    status
+}
+
+/// Drop cached statuses of worktrees that no session is using anymore
+pub fn retain_git_status(live_worktree_roots: &HashSet<PathBuf>) {
+    GIT_STATUS_SERVICE.cache.lock().unwrap().retain(live_worktree_roots);
+}
@@ -2,8 +2,12 @@
 
 pub mod agent;
 pub mod commands;
+pub mod export;
+pub mod git;
 pub mod logging;
+pub mod policy;
 pub mod process;
+pub mod remote;
 pub mod session;
 pub mod terminal;
 
@@ -11,13 +15,14 @@ pub mod terminal;
 mod tests;
 
 use tauri::{
+    Emitter,
     Manager,
     tray::TrayIconBuilder,
     menu::{MenuBuilder, MenuItemBuilder},
 };
 use std::sync::Mutex;
 
-use commands::{get_all_sessions, focus_session, update_tray_title, register_shortcut, unregister_shortcut, kill_session};
+use commands::{get_all_sessions, focus_session, update_tray_title, register_shortcut, unregister_shortcut, kill_session, kill_child_process, pause_session, continue_session, get_session_history, resume_session, set_idle_threshold, get_recent_events, get_resource_history, get_session_files, get_session_audit, get_session_secrets, redact_session_secrets, get_project_time, get_weekly_sessions, export_activity_csv, export_session, get_policy_actions, set_remote_hosts, get_remote_host_status, set_visible_origins};
 
 // Store tray icon ID for updates
 static TRAY_ID: Mutex<Option<String>> = Mutex::new(None);
@@ -30,8 +35,17 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
-        .invoke_handler(tauri::generate_handler![get_all_sessions, focus_session, update_tray_title, register_shortcut, unregister_shortcut, kill_session])
+        .invoke_handler(tauri::generate_handler![get_all_sessions, focus_session, update_tray_title, register_shortcut, unregister_shortcut, kill_session, kill_child_process, pause_session, continue_session, get_session_history, resume_session, set_idle_threshold, get_recent_events, get_resource_history, get_session_files, get_session_audit, get_session_secrets, redact_session_secrets, get_project_time, get_weekly_sessions, export_activity_csv, export_session, get_policy_actions, set_remote_hosts, get_remote_host_status, set_visible_origins])
         .setup(|app| {
+            // Forward session lifecycle events to the frontend
+            let app_handle = app.handle().clone();
+            session::events::add_listener(move |event| {
+                let _ = app_handle.emit("session-event", event);
+            });
+
+            // Keep a persistent log of session activity for the reports
+            session::activity::start();
+
             // Create menu for tray
             let show_item = MenuItemBuilder::with_id("show", "Show Window")
                 .build(app)?;
@@ -92,6 +106,10 @@ pub fn run() {
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|_app, _event| {
+            if let tauri::RunEvent::Exit = _event {
+                session::activity::stop();
+            }
+
             // Handle dock icon click when app is already running (macOS only)
             #[cfg(target_os = "macos")]
             if let tauri::RunEvent::Reopen { has_visible_windows, .. } = _event {
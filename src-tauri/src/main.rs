@@ -2,5 +2,10 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Headless mode for collecting sessions from remote hosts over ssh
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--collect") {
+        std::process::exit(tauri_temp_lib::remote::collector::run(&args));
+    }
     tauri_temp_lib::run()
 }
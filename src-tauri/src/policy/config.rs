@@ -0,0 +1,95 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Minimum time between two actions of a rule on the same session or process
+const DEFAULT_COOLDOWN_MINUTES: u64 = 60;
+
+/// Rules evaluated on every poll, read from `policies.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyConfig {
+    /// Log the actions the rules would take without taking them
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+/// A condition and what to do when a session or process meets it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyRule {
+    pub id: String,
+    pub when: PolicyCondition,
+    pub action: PolicyActionKind,
+    #[serde(default = "default_cooldown_minutes")]
+    pub cooldown_minutes: u64,
+    /// Dry run for this rule only
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_cooldown_minutes() -> u64 {
+    DEFAULT_COOLDOWN_MINUTES
+}
+
+/// What a rule checks on each poll
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum PolicyCondition {
+    /// Waiting, idle or paused with no transcript activity for this long
+    IdleFor { minutes: u64 },
+    /// The agent and the processes it started use more resident memory than this
+    TreeMemoryAbove { megabytes: u64 },
+    /// A command or web request has been running for this long
+    ToolRunningFor { minutes: u64 },
+    /// Processes a session started are still running after the session ended
+    OrphanedChildren,
+}
+
+/// What a rule does when its condition is met
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum PolicyActionKind {
+    /// Only record a policy event
+    Notify,
+    /// Stop the agent and its processes with SIGSTOP, like `pause_session`
+    Pause,
+    /// Terminate the session like `kill_session`, or send SIGTERM to an orphaned process
+    Terminate,
+}
+
+/// `~/.config/agent-sessions/policies.json` (platform config directory)
+pub fn config_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("agent-sessions").join("policies.json"))
+}
+
+/// Parse a policy file. Rules that can't be carried out are dropped with a warning
+/// so one bad rule doesn't disable the others.
+pub fn parse_config(text: &str) -> Result<PolicyConfig, String> {
+    let mut config: PolicyConfig = serde_json::from_str(text).map_err(|e| format!("Invalid policy file: {}", e))?;
+    let mut ids = HashSet::new();
+    config.rules.retain(|rule| {
+        if !ids.insert(rule.id.clone()) {
+            warn!("Ignoring policy rule with duplicate id {}", rule.id);
+            return false;
+        }
+        if rule.when == PolicyCondition::OrphanedChildren && rule.action == PolicyActionKind::Pause {
+            warn!("Ignoring policy rule {}: orphaned processes can only be reported or terminated", rule.id);
+            return false;
+        }
+        true
+    });
+    Ok(config)
+}
+
+/// Read a policy file; a missing file means no rules
+pub fn load_config(path: &Path) -> Result<PolicyConfig, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => parse_config(&text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(PolicyConfig::default()),
+        Err(e) => Err(format!("Failed to read {:?}: {}", path, e)),
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+
+use crate::process::ChildProcess;
+use crate::session::{AgentType, Session, SessionStatus};
+
+use super::config::{PolicyActionKind, PolicyCondition, PolicyRule};
+
+/// What a planned action applies to
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyTarget {
+    /// The session's agent and everything it started
+    Session,
+    /// A process left running by an ended session
+    Process(ChildProcess),
+}
+
+/// An action a rule decided on during one evaluation
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedAction {
+    pub rule_id: String,
+    pub action: PolicyActionKind,
+    pub session_id: String,
+    pub agent_type: AgentType,
+    pub project_name: String,
+    pub target: PolicyTarget,
+    /// Why the rule matched, e.g. "no activity for 725 minutes"
+    pub reason: String,
+    /// The rule itself is in dry run
+    pub dry_run: bool,
+}
+
+/// What one poll looks like to the rules
+pub struct PolicyInput<'a> {
+    pub sessions: &'a [Session],
+    pub now: DateTime<Utc>,
+    /// Oldest unfinished command or web request of each session: (command line or URL, start)
+    pub running_tools: &'a HashMap<String, (String, DateTime<Utc>)>,
+    /// Whether the process with this pid and start time is still running
+    pub is_running: &'a dyn Fn(u32, u64) -> bool,
+}
+
+/// Processes a session had started, remembered across polls
+#[derive(Debug, Clone)]
+struct SessionChildren {
+    agent_type: AgentType,
+    project_name: String,
+    /// The agent's pid and start time; its children are only orphaned once it exited
+    agent_pid: u32,
+    agent_start_time: u64,
+    processes: Vec<ChildProcess>,
+}
+
+/// Rule state kept between polls: cooldowns and the processes of ended sessions
+#[derive(Default)]
+pub struct PolicyEngine {
+    /// When a rule last acted on a target, keyed by (rule id, target key)
+    last_fired: HashMap<(String, String), DateTime<Utc>>,
+    /// Child processes of each live session as of the last evaluation
+    children: HashMap<String, SessionChildren>,
+    /// Children of ended sessions that were still running at the last evaluation
+    orphans: HashMap<String, SessionChildren>,
+}
+
+impl PolicyEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check every rule against the poll and return the actions due, skipping
+    /// targets still in a rule's cooldown. Call once per poll, even without
+    /// rules, so processes of ending sessions are remembered.
+    pub fn evaluate(&mut self, rules: &[PolicyRule], input: &PolicyInput) -> Vec<PlannedAction> {
+        self.track_orphans(input);
+
+        let mut planned = Vec::new();
+        for rule in rules {
+            for (target_key, action) in self.matches(rule, input) {
+                if self.in_cooldown(rule, &target_key, input.now) {
+                    continue;
+                }
+                self.last_fired.insert((rule.id.clone(), target_key), input.now);
+                planned.push(action);
+            }
+        }
+
+        // Forget cooldowns that have long run out
+        let rules_by_id: HashMap<&str, &PolicyRule> = rules.iter().map(|r| (r.id.as_str(), r)).collect();
+        self.last_fired.retain(|(rule_id, _), at| {
+            rules_by_id.get(rule_id.as_str())
+                .is_some_and(|rule| input.now - *at < Duration::minutes(rule.cooldown_minutes as i64))
+        });
+        planned
+    }
+
+    fn in_cooldown(&self, rule: &PolicyRule, target_key: &str, now: DateTime<Utc>) -> bool {
+        self.last_fired
+            .get(&(rule.id.clone(), target_key.to_string()))
+            .is_some_and(|at| now - *at < Duration::minutes(rule.cooldown_minutes as i64))
+    }
+
+    /// Move the children of sessions whose agent exited into `orphans`, and
+    /// drop orphans that have exited since.
+    ///
+    /// A session can be missing from a poll while its agent still runs, e.g.
+    /// when its transcript couldn't be bound or its origin is hidden. Its
+    /// children are kept as they were until the agent itself is gone.
+    fn track_orphans(&mut self, input: &PolicyInput) {
+        let live_ids: HashSet<&str> = input.sessions.iter().map(|s| s.id.as_str()).collect();
+        let mut vanished = HashMap::new();
+        for (id, children) in std::mem::take(&mut self.children) {
+            if live_ids.contains(id.as_str()) {
+                continue;
+            }
+            if (input.is_running)(children.agent_pid, children.agent_start_time) {
+                vanished.insert(id, children);
+                continue;
+            }
+            self.orphans.entry(id)
+                .or_insert_with(|| SessionChildren { processes: Vec::new(), ..children.clone() })
+                .processes
+                .extend(children.processes);
+        }
+
+        self.children = input.sessions.iter()
+            .filter(|s| !s.child_processes.is_empty())
+            .map(|s| (s.id.clone(), SessionChildren {
+                agent_type: s.agent_type.clone(),
+                project_name: s.project_name.clone(),
+                agent_pid: s.pid,
+                agent_start_time: s.process_started_at.as_deref()
+                    .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+                    .map_or(0, |at| at.timestamp() as u64),
+                processes: s.child_processes.clone(),
+            }))
+            .collect();
+        self.children.extend(vanished);
+
+        // A process that shows up under a live agent again isn't orphaned
+        let live_children: HashSet<u32> = input.sessions.iter().flat_map(|s| &s.child_processes).map(|c| c.pid).collect();
+        for orphans in self.orphans.values_mut() {
+            orphans.processes.retain(|p| !live_children.contains(&p.pid) && (input.is_running)(p.pid, p.start_time));
+        }
+        self.orphans.retain(|_, orphans| !orphans.processes.is_empty());
+    }
+
+    /// Targets the rule's condition holds for, with their cooldown keys
+    fn matches(&self, rule: &PolicyRule, input: &PolicyInput) -> Vec<(String, PlannedAction)> {
+        let session_action = |session: &Session, reason: String| PlannedAction {
+            rule_id: rule.id.clone(),
+            action: rule.action,
+            session_id: session.id.clone(),
+            agent_type: session.agent_type.clone(),
+            project_name: session.project_name.clone(),
+            target: PolicyTarget::Session,
+            reason,
+            dry_run: rule.dry_run,
+        };
+        // Pausing a paused session would only repeat itself
+        let sessions = input.sessions.iter()
+            .filter(|s| !(rule.action == PolicyActionKind::Pause && s.status == SessionStatus::Paused));
+
+        match &rule.when {
+            PolicyCondition::IdleFor { minutes } => sessions
+                .filter(|s| matches!(s.status, SessionStatus::Waiting | SessionStatus::Idle | SessionStatus::Paused))
+                .filter_map(|s| {
+                    let last_activity = DateTime::parse_from_rfc3339(&s.last_activity_at).ok()?.with_timezone(&Utc);
+                    let idle = (input.now - last_activity).num_minutes();
+                    (idle >= *minutes as i64).then(|| (s.id.clone(), session_action(s, format!("no activity for {} minutes", idle))))
+                })
+                .collect(),
+            PolicyCondition::TreeMemoryAbove { megabytes } => sessions
+                .filter(|s| s.tree_memory > megabytes * 1024 * 1024)
+                .map(|s| {
+                    let reason = format!("agent and {} processes use {} MB", s.child_processes.len(), s.tree_memory / (1024 * 1024));
+                    (s.id.clone(), session_action(s, reason))
+                })
+                .collect(),
+            PolicyCondition::ToolRunningFor { minutes } => sessions
+                .filter_map(|s| {
+                    let (detail, started) = input.running_tools.get(&s.id)?;
+                    let running = (input.now - *started).num_minutes();
+                    // Keyed by the tool's start so the next long tool is reported on its own
+                    (running >= *minutes as i64).then(|| (
+                        format!("{}@{}", s.id, started.timestamp()),
+                        session_action(s, format!("running for {} minutes: {}", running, detail)),
+                    ))
+                })
+                .collect(),
+            PolicyCondition::OrphanedChildren => {
+                let mut actions: Vec<(String, PlannedAction)> = self.orphans.iter()
+                    .flat_map(|(session_id, orphans)| orphans.processes.iter().map(move |p| (session_id, orphans, p)))
+                    .map(|(session_id, orphans, process)| (
+                        format!("{}@{}", process.pid, process.start_time),
+                        PlannedAction {
+                            rule_id: rule.id.clone(),
+                            action: rule.action,
+                            session_id: session_id.clone(),
+                            agent_type: orphans.agent_type.clone(),
+                            project_name: orphans.project_name.clone(),
+                            target: PolicyTarget::Process(process.clone()),
+                            reason: format!("process {} ({}) outlived its session", process.pid, process.name),
+                            dry_run: rule.dry_run,
+                        },
+                    ))
+                    .collect();
+                actions.sort_by(|a, b| a.0.cmp(&b.0));
+                actions
+            }
+        }
+    }
+}
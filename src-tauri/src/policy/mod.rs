@@ -0,0 +1,208 @@
+pub mod config;
+pub mod engine;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::process::TerminateOptions;
+use crate::session::audit::{get_audit_entries, AuditEntry, AuditKind};
+use crate::session::events::{record_events, SessionEvent, SessionEventKind};
+use crate::session::{Session, SessionStatus};
+
+pub use config::{config_path, load_config, parse_config, PolicyActionKind, PolicyCondition, PolicyConfig, PolicyRule};
+pub use engine::{PlannedAction, PolicyEngine, PolicyInput, PolicyTarget};
+
+/// Records kept in memory for `get_policy_actions`
+const MAX_ACTION_RECORDS: usize = 500;
+
+/// An action a policy rule took, or would have taken in dry run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyActionRecord {
+    /// When the rule matched (RFC 3339)
+    pub at: String,
+    pub rule: String,
+    pub action: PolicyActionKind,
+    pub session_id: String,
+    pub project_name: String,
+    /// Orphaned process the action was taken on, None for the session itself
+    pub pid: Option<u32>,
+    pub reason: String,
+    pub dry_run: bool,
+    /// Why the action failed, None if it succeeded or was a dry run
+    pub error: Option<String>,
+}
+
+/// Policy file as of its last modification time, so it is only parsed when it changes
+struct LoadedConfig {
+    modified: Option<SystemTime>,
+    config: PolicyConfig,
+}
+
+static LOADED_CONFIG: Lazy<Mutex<Option<LoadedConfig>>> = Lazy::new(|| Mutex::new(None));
+static ENGINE: Lazy<Mutex<PolicyEngine>> = Lazy::new(|| Mutex::new(PolicyEngine::new()));
+static ACTION_LOG: Lazy<Mutex<VecDeque<PolicyActionRecord>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+/// The policy file's rules, re-read when the file changes. An invalid file
+/// disables all rules until it is fixed.
+fn current_config() -> PolicyConfig {
+    let Some(path) = config_path() else {
+        return PolicyConfig::default();
+    };
+    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+    let mut loaded = LOADED_CONFIG.lock().unwrap();
+    if let Some(loaded) = loaded.as_ref().filter(|l| l.modified == modified) {
+        return loaded.config.clone();
+    }
+
+    let config = match load_config(&path) {
+        Ok(config) => {
+            info!("Loaded {} policy rules from {:?}{}", config.rules.len(), path, if config.dry_run { " (dry run)" } else { "" });
+            config
+        }
+        Err(e) => {
+            warn!("{}; policies are disabled", e);
+            PolicyConfig::default()
+        }
+    };
+    *loaded = Some(LoadedConfig { modified, config: config.clone() });
+    config
+}
+
+/// Oldest unfinished command or web request of each busy session, from the audit log
+fn running_tools(sessions: &[Session]) -> HashMap<String, (String, DateTime<Utc>)> {
+    sessions.iter()
+        .filter(|s| matches!(s.status, SessionStatus::Processing | SessionStatus::Thinking | SessionStatus::Paused))
+        .filter_map(|s| Some((s.id.clone(), oldest_running_tool(get_audit_entries(&s.id)?)?)))
+        .collect()
+}
+
+/// Oldest command or web request still waiting for its result: (detail, start).
+///
+/// A tool call that never got a result (interrupted, or its result was
+/// compacted away) would otherwise stay "running" forever, so only calls
+/// made no earlier than the latest call with a result count. Calls made in
+/// parallel share that time and stay in.
+pub fn oldest_running_tool(entries: Vec<AuditEntry>) -> Option<(String, DateTime<Utc>)> {
+    let parse = |at: &str| DateTime::parse_from_rfc3339(at).ok().map(|t| t.with_timezone(&Utc));
+    let latest_finished = entries.iter()
+        .filter(|e| e.outcome.is_some())
+        .filter_map(|e| parse(&e.at))
+        .max();
+    entries.into_iter()
+        .filter(|e| e.outcome.is_none() && e.kind != AuditKind::FileEdit)
+        .filter_map(|e| Some((parse(&e.at)?, e.detail)))
+        .filter(|(at, _)| latest_finished.is_none_or(|finished| *at >= finished))
+        .min_by_key(|(at, _)| *at)
+        .map(|(at, detail)| (detail, at))
+}
+
+/// Evaluate the policy file against this poll and carry out the actions due.
+/// Sessions are only signalled from a background thread, so polling never waits on them.
+pub fn run_policies(sessions: &[Session], is_running: &dyn Fn(u32, u64) -> bool) {
+    let config = current_config();
+    let needs_tools = config.rules.iter().any(|r| matches!(r.when, PolicyCondition::ToolRunningFor { .. }));
+    let running_tools = if needs_tools { running_tools(sessions) } else { HashMap::new() };
+    let input = PolicyInput { sessions, now: Utc::now(), running_tools: &running_tools, is_running };
+    let planned = ENGINE.lock().unwrap().evaluate(&config.rules, &input);
+    apply_actions(planned, config.dry_run, input.now);
+}
+
+/// Record and log the planned actions, then carry out those not in dry run
+pub fn apply_actions(planned: Vec<PlannedAction>, dry_run: bool, now: DateTime<Utc>) {
+    let at = now.to_rfc3339_opts(SecondsFormat::Millis, true);
+    let mut events = Vec::new();
+    let mut pending = Vec::new();
+    for action in planned {
+        let dry_run = dry_run || action.dry_run;
+        let pid = match &action.target {
+            PolicyTarget::Session => None,
+            PolicyTarget::Process(process) => Some(process.pid),
+        };
+        info!("Policy {}{}: {:?} session {} ({}){}: {}",
+            action.rule_id, if dry_run { " (dry run)" } else { "" }, action.action, action.session_id,
+            action.project_name, pid.map(|p| format!(" process {}", p)).unwrap_or_default(), action.reason);
+        events.push(SessionEvent {
+            seq: 0,
+            at: at.clone(),
+            session_id: action.session_id.clone(),
+            agent_type: action.agent_type.clone(),
+            project_name: action.project_name.clone(),
+            kind: SessionEventKind::PolicyAction {
+                rule: action.rule_id.clone(),
+                action: action.action,
+                detail: action.reason.clone(),
+                dry_run,
+            },
+        });
+
+        let record = PolicyActionRecord {
+            at: at.clone(),
+            rule: action.rule_id.clone(),
+            action: action.action,
+            session_id: action.session_id.clone(),
+            project_name: action.project_name.clone(),
+            pid,
+            reason: action.reason.clone(),
+            dry_run,
+            error: None,
+        };
+        // Notifying is done by the event itself
+        if dry_run || action.action == PolicyActionKind::Notify {
+            push_record(record);
+        } else {
+            pending.push((action, record));
+        }
+    }
+    record_events(events);
+
+    if !pending.is_empty() {
+        std::thread::spawn(move || {
+            for (action, mut record) in pending {
+                if let Err(e) = execute(&action) {
+                    warn!("Policy {} failed on session {}: {}", action.rule_id, action.session_id, e);
+                    record.error = Some(e);
+                }
+                push_record(record);
+            }
+        });
+    }
+}
+
+/// Pause or terminate through the same paths as the session card actions
+fn execute(action: &PlannedAction) -> Result<(), String> {
+    match (&action.target, action.action) {
+        (_, PolicyActionKind::Notify) => Ok(()),
+        (PolicyTarget::Session, PolicyActionKind::Pause) => crate::agent::pause_session(&action.session_id).map(|_| ()),
+        (PolicyTarget::Session, PolicyActionKind::Terminate) => {
+            let result = crate::agent::terminate_session(&action.session_id, &TerminateOptions::default())?;
+            if result.exited {
+                Ok(())
+            } else {
+                Err(format!("Processes still running: {:?}", result.remaining_pids))
+            }
+        }
+        (PolicyTarget::Process(process), PolicyActionKind::Terminate) => {
+            crate::agent::terminate_orphaned_process(process.pid, process.start_time)
+        }
+        (PolicyTarget::Process(_), PolicyActionKind::Pause) => Err("Orphaned processes can't be paused".to_string()),
+    }
+}
+
+fn push_record(record: PolicyActionRecord) {
+    let mut log = ACTION_LOG.lock().unwrap();
+    if log.len() == MAX_ACTION_RECORDS {
+        log.pop_front();
+    }
+    log.push_back(record);
+}
+
+/// Actions taken by policy rules since the app started, oldest first
+pub fn get_policy_actions() -> Vec<PolicyActionRecord> {
+    ACTION_LOG.lock().unwrap().iter().cloned().collect()
+}
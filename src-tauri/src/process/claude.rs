@@ -3,22 +3,101 @@ use serde::{Deserialize, Serialize};
 use sysinfo::System;
 use std::path::PathBuf;
 
+use super::container::{detect_container, ContainerInfo};
+use super::origin::{classify_origin, is_claude_command, is_origin_visible, read_entrypoint, SessionOrigin};
+#[cfg(target_os = "linux")]
+use super::orphan;
+
 /// Represents a running Claude Code process
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct ClaudeProcess {
     pub pid: u32,
     pub cwd: Option<PathBuf>,
     pub cpu_usage: f32,
     pub memory: u64,
+    pub cmd: Vec<String>,
+    /// Start time in seconds since the epoch
+    pub start_time: u64,
+    /// Stopped by SIGSTOP or job control
+    pub stopped: bool,
+    /// Set when the process runs in a container; `cwd` is then a path inside it
+    pub container: Option<ContainerInfo>,
+    /// Terminal, IDE or SDK that launched it
+    pub origin: SessionOrigin,
+}
+
+/// Session settings passed to Claude Code on its command line
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClaudeCliArgs {
+    pub model: Option<String>,
+    pub permission_mode: Option<String>,
+    /// Session id from `--resume <id>` or `--session-id <id>`
+    pub session_id: Option<String>,
+}
+
+/// Parse the flags we care about from a Claude Code command line.
+/// Handles both `--flag value` and `--flag=value` forms.
+/// `--dangerously-skip-permissions` maps to the `bypassPermissions` mode used in transcripts.
+pub fn parse_claude_args(cmd: &[String]) -> ClaudeCliArgs {
+    let mut args = ClaudeCliArgs::default();
+    let mut iter = cmd.iter().skip(1).peekable();
+
+    while let Some(arg) = iter.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+
+        match flag {
+            "--dangerously-skip-permissions" => {
+                args.permission_mode = Some("bypassPermissions".to_string());
+            }
+            "--permission-mode" => {
+                let value = inline_value.or_else(|| iter.next().cloned());
+                // An explicit skip flag wins over any mode given alongside it
+                if args.permission_mode.as_deref() != Some("bypassPermissions") {
+                    args.permission_mode = value;
+                }
+            }
+            "--model" => {
+                args.model = inline_value.or_else(|| iter.next().cloned());
+            }
+            "--resume" | "-r" | "--session-id" => {
+                // A bare --resume opens the session picker, so the next flag is not its value
+                let value = inline_value.or_else(|| iter.next_if(|v| !v.starts_with('-')).cloned());
+                if value.is_some() {
+                    args.session_id = value;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    args
+}
+
+/// Check if a process is orphaned, i.e. the terminal it ran in was closed.
+/// On Linux this looks at the controlling terminal and signal state in /proc
+/// (see `orphan::is_orphaned`), elsewhere at the parent chain.
+pub fn is_orphaned_process(system: &System, process: &sysinfo::Process) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        let pid = process.pid().as_u32();
+        let table = orphan::read_proc_table(pid);
+        if table.contains_key(&pid) {
+            return orphan::is_orphaned(&table, pid, |tty_nr| orphan::tty_device_exists(pid, tty_nr));
+        }
+    }
+
+    is_orphaned_by_parent_chain(system, process)
 }
 
-/// Check if a process is orphaned by examining its parent chain.
 /// A process is considered orphaned if its parent shell has been reparented
 /// to launchd/init (PID 1), indicating the original terminal was closed.
 ///
 /// Parent chain for healthy sessions: claude → shell → terminal_emulator
 /// Parent chain for orphaned sessions: claude → shell (PPID=1) → launchd
-pub fn is_orphaned_process(system: &System, process: &sysinfo::Process) -> bool {
+fn is_orphaned_by_parent_chain(system: &System, process: &sysinfo::Process) -> bool {
     let parent_pid = match process.parent() {
         Some(pid) => pid,
         None => return true, // No parent at all - definitely orphaned
@@ -46,9 +125,20 @@ pub fn is_orphaned_process(system: &System, process: &sysinfo::Process) -> bool
 }
 
 /// Find all running Claude Code processes using the shared system snapshot.
-/// Filters out sub-agent processes (whose parent is also a Claude process)
-/// and orphaned processes (whose terminal has been closed).
+/// Filters out sub-agent processes (whose parent is also a Claude process),
+/// orphaned processes (whose terminal has been closed) and processes whose
+/// launcher isn't among the visible origins.
 pub fn find_claude_processes(system: &System) -> Vec<ClaudeProcess> {
+    discover_claude_processes(system, true)
+}
+
+/// Every running Claude Code process except sub-agents, including those the
+/// session list leaves out because of their origin or because they're orphaned
+pub fn find_all_claude_processes(system: &System) -> Vec<ClaudeProcess> {
+    discover_claude_processes(system, false)
+}
+
+fn discover_claude_processes(system: &System, listed_only: bool) -> Vec<ClaudeProcess> {
     use std::collections::HashSet;
     use sysinfo::Pid;
 
@@ -60,14 +150,7 @@ pub fn find_claude_processes(system: &System) -> Vec<ClaudeProcess> {
     // First pass: collect all Claude process PIDs
     let mut claude_pids: HashSet<Pid> = HashSet::new();
     for (pid, process) in system.processes() {
-        let cmd = process.cmd();
-        let is_claude = if let Some(first_arg) = cmd.first() {
-            let first_arg_str = first_arg.to_string_lossy().to_lowercase();
-            first_arg_str == "claude" || first_arg_str.ends_with("/claude")
-        } else {
-            false
-        };
-        if is_claude {
+        if is_claude_command(&command_line(process)) {
             claude_pids.insert(*pid);
         }
     }
@@ -76,15 +159,8 @@ pub fn find_claude_processes(system: &System) -> Vec<ClaudeProcess> {
 
     // Second pass: collect Claude processes, excluding sub-agents and orphans
     for (pid, process) in system.processes() {
-        let cmd = process.cmd();
         let process_name = process.name().to_string_lossy();
-
-        let is_claude = if let Some(first_arg) = cmd.first() {
-            let first_arg_str = first_arg.to_string_lossy().to_lowercase();
-            first_arg_str == "claude" || first_arg_str.ends_with("/claude")
-        } else {
-            false
-        };
+        let is_claude = claude_pids.contains(pid);
 
         // Exclude our own app
         let is_our_app = process_name.contains("claude-sessions")
@@ -110,31 +186,23 @@ pub fn find_claude_processes(system: &System) -> Vec<ClaudeProcess> {
                     );
                     continue;
                 }
+            }
 
-                // Check if parent is Zed's external agent (claude-code-acp)
-                // These are auto-spawned by Zed and not user-initiated terminal sessions
-                if let Some(parent_process) = system.process(parent_pid) {
-                    let parent_cmd: String = parent_process.cmd()
-                        .iter()
-                        .map(|s| s.to_string_lossy())
-                        .collect::<Vec<_>>()
-                        .join(" ");
-                    if parent_cmd.contains("claude-code-acp") {
-                        debug!(
-                            "Skipping Zed external agent: pid={}, parent_pid={}, cwd={:?}",
-                            pid.as_u32(),
-                            parent_pid.as_u32(),
-                            cwd
-                        );
-                        continue;
-                    }
-                }
+            let cmd = command_line(process);
+            let parent_cmd = process.parent()
+                .and_then(|parent_pid| system.process(parent_pid))
+                .map(command_line)
+                .unwrap_or_default();
+            let origin = classify_origin(&cmd, read_entrypoint(pid.as_u32()).as_deref(), &parent_cmd);
+            if listed_only && !is_origin_visible(origin) {
+                debug!("Skipping process launched by {:?}: pid={}, cwd={:?}", origin, pid.as_u32(), cwd);
+                continue;
             }
 
             // Check if process is orphaned (terminal was closed)
-            if is_orphaned_process(system, process) {
+            if listed_only && is_orphaned_process(system, process) {
                 warn!(
-                    "Skipping orphaned process: pid={}, cwd={:?}, cpu={:.1}% (parent shell reparented to launchd)",
+                    "Skipping orphaned process: pid={}, cwd={:?}, cpu={:.1}% (its terminal was closed)",
                     pid.as_u32(),
                     cwd,
                     process.cpu_usage()
@@ -143,8 +211,9 @@ pub fn find_claude_processes(system: &System) -> Vec<ClaudeProcess> {
             }
 
             debug!(
-                "Found Claude process: pid={}, cwd={:?}, cpu={:.1}%, mem={}MB",
+                "Found Claude process: pid={}, origin={:?}, cwd={:?}, cpu={:.1}%, mem={}MB",
                 pid.as_u32(),
+                origin,
                 cwd,
                 process.cpu_usage(),
                 process.memory() / 1024 / 1024
@@ -155,6 +224,11 @@ pub fn find_claude_processes(system: &System) -> Vec<ClaudeProcess> {
                 cwd,
                 cpu_usage: process.cpu_usage(),
                 memory: process.memory(),
+                cmd,
+                start_time: process.start_time(),
+                stopped: process.status() == sysinfo::ProcessStatus::Stop,
+                container: detect_container(pid.as_u32()),
+                origin,
             });
         }
     }
@@ -162,3 +236,7 @@ pub fn find_claude_processes(system: &System) -> Vec<ClaudeProcess> {
     debug!("Process discovery complete: found {} Claude processes (excluding sub-agents and orphans)", processes.len());
     processes
 }
+
+fn command_line(process: &sysinfo::Process) -> Vec<String> {
+    process.cmd().iter().map(|s| s.to_string_lossy().to_string()).collect()
+}
@@ -0,0 +1,263 @@
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+/// Cgroup path prefixes of container runtimes, with the runtime they belong to
+const SCOPE_PREFIXES: &[(&str, &str)] = &[
+    ("docker-", "docker"),
+    ("libpod-", "podman"),
+    ("cri-containerd-", "containerd"),
+    ("crio-", "cri-o"),
+];
+
+/// The container an agent session runs in, as shown on its card
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerRef {
+    /// Container id from the cgroup, or `mntns-<inode>` for other mount namespaces
+    pub id: String,
+    /// "docker", "podman", "containerd" or "cri-o", None if not known
+    pub runtime: Option<String>,
+    /// Name reported by the runtime's CLI, None if it couldn't be asked
+    pub name: Option<String>,
+    /// The project is bind-mounted from the host and shown at its host path.
+    /// Otherwise it only exists inside the container.
+    #[serde(default)]
+    pub project_on_host: bool,
+}
+
+/// One line of `/proc/<pid>/mountinfo`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MountEntry {
+    /// `major:minor` of the filesystem
+    pub device: String,
+    /// Directory of the filesystem that is mounted, e.g. the source of a bind mount
+    pub root: PathBuf,
+    /// Where it is mounted, as the process sees it
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+}
+
+/// A process running in another mount namespace than this app
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ContainerInfo {
+    pub id: String,
+    pub runtime: Option<String>,
+    /// The process's filesystem as seen from here: `/proc/<pid>/root`
+    pub root: PathBuf,
+    /// Home directory inside the container
+    pub home: Option<PathBuf>,
+    /// The container's mounts, for translating its paths to host paths
+    pub mounts: Vec<MountEntry>,
+}
+
+impl ContainerInfo {
+    /// A path inside the container, readable from the host through `root`
+    pub fn host_view(&self, path: &Path) -> PathBuf {
+        self.root.join(path.strip_prefix("/").unwrap_or(path))
+    }
+}
+
+fn is_container_id(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Find a container id and runtime in `/proc/<pid>/cgroup`, e.g.
+/// `0::/system.slice/docker-<id>.scope` or `12:pids:/docker/<id>`
+pub fn parse_cgroup_container(cgroup: &str) -> Option<(Option<String>, String)> {
+    for line in cgroup.lines() {
+        let Some(path) = line.splitn(3, ':').nth(2) else {
+            continue;
+        };
+        let segments: Vec<&str> = path.split('/').collect();
+        for (i, segment) in segments.iter().enumerate() {
+            let name = segment.strip_suffix(".scope").unwrap_or(segment);
+            for (prefix, runtime) in SCOPE_PREFIXES {
+                if let Some(id) = name.strip_prefix(prefix).filter(|id| is_container_id(id)) {
+                    return Some((Some(runtime.to_string()), id.to_string()));
+                }
+            }
+            if is_container_id(name) {
+                let runtime = match i.checked_sub(1).map(|p| segments[p]) {
+                    Some("docker") | Some("actions_job") => Some("docker".to_string()),
+                    _ => None,
+                };
+                return Some((runtime, name.to_string()));
+            }
+        }
+    }
+    None
+}
+
+/// Undo the octal escapes mountinfo uses for spaces, tabs, newlines and backslashes
+fn unescape_mount_path(s: &str) -> PathBuf {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4)
+            .filter(|_| bytes[i] == b'\\')
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match octal {
+            Some(byte) => {
+                out.push(byte);
+                i += 4;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    PathBuf::from(String::from_utf8_lossy(&out).into_owned())
+}
+
+/// Parse `/proc/<pid>/mountinfo`:
+/// `id parent major:minor root mount_point options [optional...] - fs_type source super_options`
+pub fn parse_mountinfo(text: &str) -> Vec<MountEntry> {
+    text.lines()
+        .filter_map(|line| {
+            let (fields, rest) = line.split_once(" - ")?;
+            let fields: Vec<&str> = fields.split(' ').collect();
+            Some(MountEntry {
+                device: fields.get(2)?.to_string(),
+                root: unescape_mount_path(fields.get(3)?),
+                mount_point: unescape_mount_path(fields.get(4)?),
+                fs_type: rest.split(' ').next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Translate a path inside a container to the host path it is bind-mounted
+/// from. Paths in the container's own root filesystem have no host path.
+///
+/// The container mount gives the directory within its filesystem; the host
+/// mount of the same filesystem whose root contains that directory gives
+/// where it is on the host.
+pub fn translate_path(path: &Path, container_mounts: &[MountEntry], host_mounts: &[MountEntry]) -> Option<PathBuf> {
+    // max_by_key picks the last of equals, and later mounts hide earlier ones at the same mount point
+    let mount = container_mounts.iter()
+        .filter(|m| path.starts_with(&m.mount_point))
+        .max_by_key(|m| m.mount_point.components().count())?;
+    if mount.mount_point == Path::new("/") {
+        return None;
+    }
+    let in_filesystem = mount.root.join(path.strip_prefix(&mount.mount_point).ok()?);
+
+    let host = host_mounts.iter()
+        .filter(|m| m.device == mount.device && in_filesystem.starts_with(&m.root))
+        .max_by_key(|m| m.root.components().count())?;
+    Some(host.mount_point.join(in_filesystem.strip_prefix(&host.root).ok()?))
+}
+
+/// `HOME` from the NUL-separated `/proc/<pid>/environ`
+pub fn parse_environ_home(environ: &[u8]) -> Option<PathBuf> {
+    environ.split(|b| *b == 0)
+        .find_map(|var| var.strip_prefix(b"HOME="))
+        .filter(|home| home.starts_with(b"/"))
+        .map(|home| PathBuf::from(String::from_utf8_lossy(home).into_owned()))
+}
+
+/// Home directory of `uid` in an `/etc/passwd` file
+pub fn passwd_home(passwd: &str, uid: u32) -> Option<PathBuf> {
+    passwd.lines()
+        .map(|line| line.split(':').collect::<Vec<_>>())
+        .find(|fields| fields.get(2).and_then(|u| u.parse::<u32>().ok()) == Some(uid))
+        .and_then(|fields| fields.get(5).map(PathBuf::from))
+}
+
+/// Mount namespace inode of a process, None if it can't be read (e.g. another user's process)
+#[cfg(target_os = "linux")]
+fn mount_namespace(pid: &str) -> Option<String> {
+    let link = std::fs::read_link(format!("/proc/{}/ns/mnt", pid)).ok()?;
+    let link = link.to_string_lossy();
+    link.strip_prefix("mnt:[")?.strip_suffix(']').map(String::from)
+}
+
+/// Mounts of this app's namespace
+#[cfg(target_os = "linux")]
+pub fn host_mounts() -> Vec<MountEntry> {
+    std::fs::read_to_string("/proc/self/mountinfo")
+        .map(|text| parse_mountinfo(&text))
+        .unwrap_or_default()
+}
+
+/// Check whether a process runs in a container, i.e. in another mount
+/// namespace than this app. The cgroup tells which container.
+#[cfg(target_os = "linux")]
+pub fn detect_container(pid: u32) -> Option<ContainerInfo> {
+    let namespace = mount_namespace(&pid.to_string())?;
+    if Some(&namespace) == mount_namespace("self").as_ref() {
+        return None;
+    }
+
+    let (runtime, id) = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()
+        .and_then(|cgroup| parse_cgroup_container(&cgroup))
+        .unwrap_or_else(|| (None, format!("mntns-{}", namespace)));
+    let root = PathBuf::from(format!("/proc/{}/root", pid));
+    let home = std::fs::read(format!("/proc/{}/environ", pid)).ok()
+        .and_then(|environ| parse_environ_home(&environ))
+        .or_else(|| {
+            let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+            let uid = status.lines().find_map(|l| l.strip_prefix("Uid:"))?.split_whitespace().next()?.parse().ok()?;
+            passwd_home(&std::fs::read_to_string(root.join("etc/passwd")).ok()?, uid)
+        });
+    let mounts = std::fs::read_to_string(format!("/proc/{}/mountinfo", pid))
+        .map(|text| parse_mountinfo(&text))
+        .unwrap_or_default();
+
+    debug!("Process pid={} runs in container {} ({:?}), home={:?}", pid, id, runtime, home);
+    Some(ContainerInfo { id, runtime, root, home, mounts })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn host_mounts() -> Vec<MountEntry> {
+    Vec::new()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn detect_container(_pid: u32) -> Option<ContainerInfo> {
+    None
+}
+
+// Container names by id; containers can't be renamed while an agent runs in
+// them often enough to justify asking the runtime again
+static CONTAINER_NAMES: Lazy<Mutex<HashMap<String, Option<String>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Name of a container from `docker inspect` (or `podman inspect`), cached per id
+pub fn container_name(id: &str, runtime: Option<&str>) -> Option<String> {
+    if let Some(name) = CONTAINER_NAMES.lock().unwrap().get(id) {
+        return name.clone();
+    }
+    let cli = match runtime {
+        Some("podman") => Some("podman"),
+        Some("docker") | None => Some("docker"),
+        // Kubernetes runtimes name pods, not containers
+        _ => None,
+    };
+    let name = cli.filter(|_| is_container_id(id)).and_then(|cli| {
+        let output = Command::new(cli).args(["inspect", "--format", "{{.Name}}", id]).output();
+        match output {
+            Ok(output) if output.status.success() => {
+                let name = String::from_utf8_lossy(&output.stdout).trim().trim_start_matches('/').to_string();
+                (!name.is_empty()).then_some(name)
+            }
+            Ok(output) => {
+                debug!("{} inspect {} failed: {}", cli, id, String::from_utf8_lossy(&output.stderr).trim());
+                None
+            }
+            Err(e) => {
+                warn!("Failed to run {} inspect: {}", cli, e);
+                None
+            }
+        }
+    });
+    CONTAINER_NAMES.lock().unwrap().insert(id.to_string(), name.clone());
+    name
+}
@@ -1,3 +1,13 @@
 mod claude;
+pub mod container;
+pub mod orphan;
+pub mod origin;
+pub mod suspend;
+pub mod terminate;
+pub mod tree;
 
-pub use claude::{ClaudeProcess, find_claude_processes, is_orphaned_process};
+pub use claude::{ClaudeCliArgs, ClaudeProcess, find_all_claude_processes, find_claude_processes, is_orphaned_process, parse_claude_args};
+pub use terminate::{ProcessIdentity, TerminateOptions, TerminationResult};
+pub use container::{ContainerInfo, ContainerRef};
+pub use origin::SessionOrigin;
+pub use tree::ChildProcess;
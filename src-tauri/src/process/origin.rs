@@ -0,0 +1,206 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// What launched an agent process
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionOrigin {
+    /// Started by hand in a terminal, including IDE integrated terminals
+    #[default]
+    Terminal,
+    /// Zed's external agent, through the claude-code-acp adapter
+    Zed,
+    /// The VS Code extension, or one of its forks (Cursor, Windsurf)
+    VsCode,
+    JetBrains,
+    /// An app built on the Agent SDK, or a script driving the CLI's stream-json mode
+    Sdk,
+    /// A one-off `claude -p` run
+    Headless,
+}
+
+/// Origins shown before the user picks any; this is what was detected
+/// before origins were classified
+pub const DEFAULT_VISIBLE_ORIGINS: [SessionOrigin; 2] = [SessionOrigin::Terminal, SessionOrigin::Headless];
+
+static VISIBLE_ORIGINS: Lazy<Mutex<Vec<SessionOrigin>>> = Lazy::new(|| Mutex::new(DEFAULT_VISIBLE_ORIGINS.to_vec()));
+
+/// Set which launchers' sessions are listed, the others are left out of discovery
+pub fn set_visible_origins(origins: Vec<SessionOrigin>) {
+    *VISIBLE_ORIGINS.lock().unwrap() = origins;
+}
+
+pub fn is_origin_visible(origin: SessionOrigin) -> bool {
+    VISIBLE_ORIGINS.lock().unwrap().contains(&origin)
+}
+
+/// JavaScript runtimes Claude Code's npm package runs under
+const RUNTIMES: [&str; 3] = ["node", "nodejs", "bun"];
+
+/// Path fragments of the extension directories VS Code and its forks install into
+const VSCODE_EXTENSION_DIRS: [&str; 5] = [
+    "/.vscode/extensions/",
+    "/.vscode-server/extensions/",
+    "/.vscode-insiders/extensions/",
+    "/.cursor/extensions/",
+    "/.windsurf/extensions/",
+];
+
+/// Executable names of JetBrains IDEs, matched as a prefix (`idea64`, `pycharm.sh`)
+const JETBRAINS_IDES: [&str; 10] = [
+    "idea", "pycharm", "webstorm", "goland", "clion", "rider", "rubymine", "phpstorm", "datagrip", "rustrover",
+];
+
+/// File name of a command line argument, lowercased and without `.exe`
+fn program_name(arg: &str) -> String {
+    let name = arg.rsplit(['/', '\\']).next().unwrap_or(arg).to_lowercase();
+    name.strip_suffix(".exe").map(str::to_string).unwrap_or(name)
+}
+
+/// A script that is Claude Code's CLI: the npm package's `cli.js`, or the
+/// `claude` bin link that points to it
+fn is_claude_script(arg: &str) -> bool {
+    let path = arg.replace('\\', "/");
+    let is_js = path.ends_with(".js") || path.ends_with(".mjs");
+    program_name(&path) == "claude"
+        || (is_js && path.contains("@anthropic-ai/claude-code/"))
+        || (is_js && VSCODE_EXTENSION_DIRS.iter().any(|dir| path.contains(dir)) && path.contains("anthropic.claude-code"))
+}
+
+/// Index of the argument that is Claude Code's executable or script, None if
+/// the command line isn't Claude Code. Arguments after it are Claude's own.
+///
+/// Besides `claude` itself this accepts the npm package run by a runtime, as
+/// in `node .../@anthropic-ai/claude-code/cli.js` or `bun run .../claude`.
+/// Wrappers like `npx` aren't matched; the runtime they start is.
+pub fn claude_arg_index(cmd: &[String]) -> Option<usize> {
+    let first = cmd.first()?;
+    if program_name(first) == "claude" {
+        return Some(0);
+    }
+    if !RUNTIMES.contains(&program_name(first).as_str()) {
+        return None;
+    }
+    // The script is the first argument that isn't a runtime flag
+    let (index, script) = cmd.iter()
+        .enumerate()
+        .skip(1)
+        .find(|(_, arg)| !arg.starts_with('-') && arg.as_str() != "run")?;
+    is_claude_script(script).then_some(index)
+}
+
+/// Whether a process is Claude Code, see `claude_arg_index`
+pub fn is_claude_command(cmd: &[String]) -> bool {
+    claude_arg_index(cmd).is_some()
+}
+
+/// Whether a program name (or `comm`) is an interactive shell
+pub fn is_shell(name: &str) -> bool {
+    matches!(name, "sh" | "bash" | "zsh" | "fish" | "dash" | "ksh" | "tcsh" | "csh" | "nu" | "pwsh" | "powershell" | "cmd")
+}
+
+/// Launcher named by `CLAUDE_CODE_ENTRYPOINT`, which the SDKs and IDE
+/// extensions set for the Claude Code they start
+fn origin_from_entrypoint(entrypoint: &str) -> Option<SessionOrigin> {
+    let entrypoint = entrypoint.to_lowercase();
+    if entrypoint.starts_with("sdk-") && entrypoint != "sdk-cli" {
+        Some(SessionOrigin::Sdk)
+    } else if entrypoint.contains("vscode") {
+        Some(SessionOrigin::VsCode)
+    } else if entrypoint.contains("jetbrains") {
+        Some(SessionOrigin::JetBrains)
+    } else if entrypoint.contains("zed") {
+        Some(SessionOrigin::Zed)
+    } else {
+        None
+    }
+}
+
+/// IDE that started the agent directly. A shell in between means the agent
+/// was typed into an integrated terminal, which counts as a terminal session.
+fn origin_from_parent(parent_cmd: &[String]) -> Option<SessionOrigin> {
+    let joined = parent_cmd.join(" ");
+    if joined.contains("claude-code-acp") {
+        return Some(SessionOrigin::Zed);
+    }
+    let name = program_name(parent_cmd.first()?);
+    if is_shell(&name) {
+        return None;
+    }
+    if name == "zed" || name == "zed-editor" {
+        Some(SessionOrigin::Zed)
+    } else if ["code", "cursor", "windsurf"].iter().any(|ide| name == *ide || name.starts_with(&format!("{} helper", ide)))
+        || joined.contains("--type=extensionHost")
+        || joined.contains("/.vscode-server/")
+    {
+        Some(SessionOrigin::VsCode)
+    } else if JETBRAINS_IDES.iter().any(|ide| name.starts_with(ide)) || joined.contains("com.intellij") {
+        Some(SessionOrigin::JetBrains)
+    } else {
+        None
+    }
+}
+
+/// Classify what launched a Claude Code process from its command line, its
+/// `CLAUDE_CODE_ENTRYPOINT` and its parent's command line.
+///
+/// IDEs are recognised by the entrypoint, by the agent being the bundled copy
+/// inside an extension, or by the IDE being the direct parent. Otherwise
+/// stream-json input or output means something is driving the agent
+/// programmatically, and `-p`/`--print` alone is a headless run.
+pub fn classify_origin(cmd: &[String], entrypoint: Option<&str>, parent_cmd: &[String]) -> SessionOrigin {
+    if let Some(origin) = entrypoint.and_then(origin_from_entrypoint) {
+        return origin;
+    }
+
+    let index = claude_arg_index(cmd).unwrap_or(0);
+    let executable = cmd.get(index).map(|arg| arg.replace('\\', "/")).unwrap_or_default();
+    if VSCODE_EXTENSION_DIRS.iter().any(|dir| executable.contains(dir)) {
+        return SessionOrigin::VsCode;
+    }
+    if Path::new(&executable).components().any(|c| c.as_os_str().to_string_lossy().to_lowercase() == "jetbrains") {
+        return SessionOrigin::JetBrains;
+    }
+    if let Some(origin) = origin_from_parent(parent_cmd) {
+        return origin;
+    }
+
+    let args = cmd.get(index + 1..).unwrap_or_default();
+    if has_stream_json(args, "--input-format") || has_stream_json(args, "--output-format") {
+        SessionOrigin::Sdk
+    } else if args.iter().any(|arg| arg == "-p" || arg == "--print") {
+        SessionOrigin::Headless
+    } else {
+        SessionOrigin::Terminal
+    }
+}
+
+/// Whether `flag` is given as `stream-json`, the format the SDKs talk to the CLI in
+fn has_stream_json(args: &[String], flag: &str) -> bool {
+    args.iter().enumerate().any(|(i, arg)| {
+        arg.strip_prefix(flag).and_then(|rest| rest.strip_prefix('=')) == Some("stream-json")
+            || (arg == flag && args.get(i + 1).is_some_and(|v| v == "stream-json"))
+    })
+}
+
+/// `CLAUDE_CODE_ENTRYPOINT` from the NUL-separated `/proc/<pid>/environ`
+pub fn parse_environ_entrypoint(environ: &[u8]) -> Option<String> {
+    environ.split(|b| *b == 0)
+        .find_map(|var| var.strip_prefix(b"CLAUDE_CODE_ENTRYPOINT="))
+        .map(|value| String::from_utf8_lossy(value).into_owned())
+}
+
+/// Entrypoint a process was started with, None where the environment of
+/// other processes can't be read
+#[cfg(target_os = "linux")]
+pub fn read_entrypoint(pid: u32) -> Option<String> {
+    std::fs::read(format!("/proc/{}/environ", pid)).ok()
+        .and_then(|environ| parse_environ_entrypoint(&environ))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_entrypoint(_pid: u32) -> Option<String> {
+    None
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use super::origin::is_shell;
+
+/// Processes that adopt orphans: init systems, systemd user managers and
+/// container init shims that set `PR_SET_CHILD_SUBREAPER`
+const KNOWN_SUBREAPERS: &[&str] = &["systemd", "init", "launchd", "tini", "dumb-init", "catatonit", "s6-svscan"];
+
+/// The parts of `/proc/<pid>/stat` orphan detection needs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcEntry {
+    pub pid: u32,
+    pub ppid: u32,
+    /// Executable name (`comm`)
+    pub name: String,
+    /// Session id, the pid of the session leader
+    pub session: u32,
+    /// Device number of the controlling terminal, 0 if there is none
+    pub tty_nr: u32,
+}
+
+/// Process table used by the detection, keyed by pid
+pub type ProcTable = HashMap<u32, ProcEntry>;
+
+fn is_subreaper(entry: &ProcEntry) -> bool {
+    entry.pid == 1 || KNOWN_SUBREAPERS.contains(&entry.name.as_str())
+}
+
+/// Decide whether `pid` belongs to a closed terminal.
+///
+/// Closing a terminal on a Linux desktop usually doesn't reparent its shell
+/// to PID 1, since `systemd --user` or another subreaper adopts it. What
+/// reliably changes is the terminal: the kernel detaches it from the session
+/// on hangup, or the shell that owned it exits.
+///
+/// With a controlling terminal, the process is orphaned if the terminal
+/// device is gone or its session leader (usually the login shell) exited.
+/// Without one, it is orphaned if it or its shell was adopted by a subreaper.
+/// Ignoring SIGHUP alone doesn't count: `nohup claude -p` and agents started
+/// by an IDE or SDK app have no terminal either, but a live parent.
+pub fn is_orphaned(table: &ProcTable, pid: u32, tty_exists: impl Fn(u32) -> bool) -> bool {
+    let Some(entry) = table.get(&pid) else {
+        return false;
+    };
+
+    if entry.tty_nr != 0 {
+        return !tty_exists(entry.tty_nr) || !table.contains_key(&entry.session);
+    }
+
+    let Some(parent) = table.get(&entry.ppid) else {
+        return true;
+    };
+    if is_subreaper(parent) {
+        return true;
+    }
+    // Only a shell is left behind by a closed terminal; any other parent is
+    // what started the agent, and it is still running
+    if !is_shell(&parent.name) {
+        return false;
+    }
+    match table.get(&parent.ppid) {
+        Some(grandparent) => is_subreaper(grandparent),
+        None => parent.ppid != 0,
+    }
+}
+
+/// Parse `/proc/<pid>/stat`. The name is in parentheses and may itself
+/// contain spaces and parentheses, so fields are counted from the last `)`.
+pub fn parse_stat(stat: &str) -> Option<ProcEntry> {
+    let (pid, rest) = stat.split_once(" (")?;
+    let (name, fields) = rest.rsplit_once(") ")?;
+    // state ppid pgrp session tty_nr ...
+    let fields: Vec<&str> = fields.split_whitespace().collect();
+    Some(ProcEntry {
+        pid: pid.trim().parse().ok()?,
+        ppid: fields.get(1)?.parse().ok()?,
+        name: name.to_string(),
+        session: fields.get(3)?.parse().ok()?,
+        tty_nr: fields.get(4)?.parse::<i64>().ok()? as u32,
+    })
+}
+
+#[cfg(target_os = "linux")]
+pub fn read_proc_entry(pid: u32) -> Option<ProcEntry> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    parse_stat(&stat)
+}
+
+/// Majors of Unix98 pseudo-terminal slaves (`/dev/pts/N`)
+const PTS_MAJORS: std::ops::RangeInclusive<u32> = 136..=143;
+
+/// Where to look for the device of a `tty_nr`. Pseudo-terminals have no
+/// entry under /sys/dev/char, only a node in the devpts mount, which goes
+/// away when the terminal is closed; this path is relative to the
+/// process's root since containers have their own devpts.
+pub fn tty_device_path(tty_nr: u32) -> String {
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+    if PTS_MAJORS.contains(&major) {
+        format!("/dev/pts/{}", (major - PTS_MAJORS.start()) * 256 + minor)
+    } else {
+        format!("/sys/dev/char/{}:{}", major, minor)
+    }
+}
+
+/// Whether the terminal device `pid` has as `tty_nr` still exists
+#[cfg(target_os = "linux")]
+pub fn tty_device_exists(pid: u32, tty_nr: u32) -> bool {
+    let path = tty_device_path(tty_nr);
+    if path.starts_with("/dev/pts/") {
+        std::path::Path::new(&format!("/proc/{}/root{}", pid, path)).exists()
+    } else {
+        std::path::Path::new(&path).exists()
+    }
+}
+
+/// Read the entries `is_orphaned` looks at for `pid`: the process, its
+/// parent and grandparent, and its session leader
+#[cfg(target_os = "linux")]
+pub fn read_proc_table(pid: u32) -> ProcTable {
+    let mut table = ProcTable::new();
+    let mut next = Some(pid);
+    for _ in 0..3 {
+        let Some(entry) = next.filter(|p| *p != 0).and_then(read_proc_entry) else {
+            break;
+        };
+        next = Some(entry.ppid);
+        table.insert(entry.pid, entry);
+    }
+    if let Some(session) = table.get(&pid).map(|e| e.session).filter(|s| *s != 0 && !table.contains_key(s)) {
+        if let Some(leader) = read_proc_entry(session) {
+            table.insert(session, leader);
+        }
+    }
+    table
+}
@@ -0,0 +1,50 @@
+use log::{info, warn};
+use sysinfo::{Pid, Signal, System};
+
+use super::terminate::{process_parents, process_table, verify_identity, ProcessIdentity};
+use super::tree::descendant_pids;
+
+/// Send `signal` to each pid in order, returning the pids it reached
+fn signal_all(system: &System, pids: &[u32], signal: Signal) -> Result<Vec<u32>, String> {
+    let mut signalled = Vec::new();
+    for &pid in pids {
+        let Some(process) = system.process(Pid::from_u32(pid)) else {
+            continue;
+        };
+        match process.kill_with(signal) {
+            Some(true) => signalled.push(pid),
+            Some(false) => warn!("Failed to send {:?} to process {}", signal, pid),
+            None => return Err("Pausing processes is not supported on this platform".to_string()),
+        }
+    }
+    Ok(signalled)
+}
+
+/// The agent first, then its descendants parents before children
+fn tree_pids(system: &System, identity: &ProcessIdentity) -> Vec<u32> {
+    std::iter::once(identity.pid)
+        .chain(descendant_pids(&process_parents(system), identity.pid))
+        .collect()
+}
+
+/// Stop an agent and everything it started with SIGSTOP. The agent goes
+/// first so it can't start new processes in the meantime.
+pub fn stop_process_tree(identity: &ProcessIdentity) -> Result<Vec<u32>, String> {
+    let system = process_table();
+    verify_identity(&system, identity)?;
+    let signalled = signal_all(&system, &tree_pids(&system, identity), Signal::Stop)?;
+    info!("Paused process {} and {} descendants", identity.pid, signalled.len().saturating_sub(1));
+    Ok(signalled)
+}
+
+/// Continue a stopped agent and its descendants with SIGCONT. Descendants go
+/// first so the agent doesn't find its tools still stopped when it resumes.
+pub fn continue_process_tree(identity: &ProcessIdentity) -> Result<Vec<u32>, String> {
+    let system = process_table();
+    verify_identity(&system, identity)?;
+    let mut pids = tree_pids(&system, identity);
+    pids.reverse();
+    let signalled = signal_all(&system, &pids, Signal::Continue)?;
+    info!("Continued process {} and {} descendants", identity.pid, signalled.len().saturating_sub(1));
+    Ok(signalled)
+}
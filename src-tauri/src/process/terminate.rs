@@ -0,0 +1,223 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, Signal, System};
+
+use super::tree::descendant_pids;
+
+/// How long to wait for processes to exit after the final SIGKILL
+const KILL_WAIT: Duration = Duration::from_secs(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The process an agent session was reported with. Pid, start time and
+/// command line together tell the agent apart from a process reusing its pid.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessIdentity {
+    pub pid: u32,
+    /// Start time in seconds since the epoch
+    pub start_time: u64,
+    pub cmd: Vec<String>,
+}
+
+/// How long each step waits for the processes to exit before escalating
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminateOptions {
+    /// Wait after SIGINT, which lets the agent shut down like on Ctrl-C (0 skips SIGINT)
+    #[serde(default = "default_interrupt_timeout_ms")]
+    pub interrupt_timeout_ms: u64,
+    /// Wait after SIGTERM before sending SIGKILL
+    #[serde(default = "default_term_timeout_ms")]
+    pub term_timeout_ms: u64,
+}
+
+fn default_interrupt_timeout_ms() -> u64 {
+    2000
+}
+
+fn default_term_timeout_ms() -> u64 {
+    3000
+}
+
+impl Default for TerminateOptions {
+    fn default() -> Self {
+        Self {
+            interrupt_timeout_ms: default_interrupt_timeout_ms(),
+            term_timeout_ms: default_term_timeout_ms(),
+        }
+    }
+}
+
+/// One signal sent during termination
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminationStep {
+    /// "SIGINT", "SIGTERM" or "SIGKILL"
+    pub signal: String,
+    pub signalled_pids: Vec<u32>,
+    pub waited_ms: u64,
+    /// Whether the agent and all its descendants had exited at the end of the wait
+    pub all_exited: bool,
+}
+
+/// What happened when a session was terminated
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminationResult {
+    pub pid: u32,
+    /// Whether the agent process itself exited
+    pub exited: bool,
+    pub steps: Vec<TerminationStep>,
+    /// Agent or descendant processes still running at the end
+    pub remaining_pids: Vec<u32>,
+}
+
+fn signal_name(signal: Signal) -> &'static str {
+    match signal {
+        Signal::Interrupt => "SIGINT",
+        Signal::Term => "SIGTERM",
+        Signal::Kill => "SIGKILL",
+        _ => "signal",
+    }
+}
+
+pub(super) fn process_table() -> System {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::All,
+        ProcessRefreshKind::new().with_cmd(sysinfo::UpdateKind::Always),
+    );
+    system
+}
+
+/// Whether the process with this pid and start time is still running (zombies count as exited)
+fn is_running(system: &System, pid: u32, start_time: u64) -> bool {
+    system.process(Pid::from_u32(pid))
+        .is_some_and(|p| p.start_time() == start_time && p.status() != ProcessStatus::Zombie)
+}
+
+/// Check that the pid still belongs to the process the session was reported
+/// with, so a reused pid is never signalled
+pub fn verify_identity(system: &System, identity: &ProcessIdentity) -> Result<(), String> {
+    let process = system.process(Pid::from_u32(identity.pid))
+        .ok_or_else(|| format!("Process {} is no longer running", identity.pid))?;
+    let cmd: Vec<String> = process.cmd().iter().map(|s| s.to_string_lossy().to_string()).collect();
+    if process.start_time() != identity.start_time || cmd != identity.cmd {
+        return Err(format!("Process {} is no longer the agent it was reported as (pid reused)", identity.pid));
+    }
+    Ok(())
+}
+
+/// (pid, parent pid) of every process in the table, threads left out
+pub(super) fn process_parents(system: &System) -> Vec<(u32, u32)> {
+    system.processes()
+        .iter()
+        .filter(|(_, p)| p.thread_kind().is_none())
+        .filter_map(|(pid, p)| Some((pid.as_u32(), p.parent()?.as_u32())))
+        .collect()
+}
+
+/// The agent and its descendants, with the start time each was seen with
+pub(super) fn subtree(system: &System, pid: u32) -> HashMap<u32, u64> {
+    std::iter::once(pid)
+        .chain(descendant_pids(&process_parents(system), pid))
+        .filter_map(|pid| Some((pid, system.process(Pid::from_u32(pid))?.start_time())))
+        .collect()
+}
+
+/// Send `signal` to the given processes, then wait up to `timeout` for them to exit
+fn signal_and_wait(targets: &HashMap<u32, u64>, tracked: &HashMap<u32, u64>, signal: Signal, timeout: Duration) -> TerminationStep {
+    let system = process_table();
+    let mut signalled_pids: Vec<u32> = targets.iter()
+        .filter(|(pid, start_time)| is_running(&system, **pid, **start_time))
+        .filter_map(|(pid, _)| {
+            let process = system.process(Pid::from_u32(*pid))?;
+            let sent = process.kill_with(signal)?;
+            // A stopped (paused) process only acts on SIGINT and SIGTERM once continued
+            if process.status() == ProcessStatus::Stop && signal != Signal::Kill {
+                process.kill_with(Signal::Continue);
+            }
+            sent.then_some(*pid)
+        })
+        .collect();
+    signalled_pids.sort_unstable();
+
+    let started = Instant::now();
+    let mut all_exited = false;
+    while started.elapsed() < timeout {
+        std::thread::sleep(POLL_INTERVAL);
+        let system = process_table();
+        if !tracked.iter().any(|(pid, start_time)| is_running(&system, *pid, *start_time)) {
+            all_exited = true;
+            break;
+        }
+    }
+
+    TerminationStep {
+        signal: signal_name(signal).to_string(),
+        signalled_pids,
+        waited_ms: started.elapsed().as_millis() as u64,
+        all_exited,
+    }
+}
+
+/// Terminate an agent and everything it started: SIGINT to the agent so it
+/// can clean up, then SIGTERM and finally SIGKILL to the whole subtree, each
+/// after the previous step's timeout. Blocks until done.
+pub fn terminate_process_tree(identity: &ProcessIdentity, options: &TerminateOptions) -> Result<TerminationResult, String> {
+    let system = process_table();
+    verify_identity(&system, identity)?;
+
+    let mut tracked = subtree(&system, identity.pid);
+    let agent_only: HashMap<u32, u64> = HashMap::from([(identity.pid, identity.start_time)]);
+    let mut steps = Vec::new();
+
+    let stages = [
+        (Signal::Interrupt, options.interrupt_timeout_ms),
+        (Signal::Term, options.term_timeout_ms),
+    ];
+    for (signal, timeout_ms) in stages {
+        if signal == Signal::Interrupt && timeout_ms == 0 {
+            continue;
+        }
+        // Pick up processes the agent started since the last step
+        let system = process_table();
+        if is_running(&system, identity.pid, identity.start_time) {
+            tracked.extend(subtree(&system, identity.pid));
+        }
+        let targets = if signal == Signal::Interrupt { &agent_only } else { &tracked };
+        let step = signal_and_wait(targets, &tracked, signal, Duration::from_millis(timeout_ms));
+        let done = step.all_exited;
+        steps.push(step);
+        if done {
+            break;
+        }
+    }
+
+    if !steps.last().is_some_and(|s| s.all_exited) {
+        steps.push(signal_and_wait(&tracked, &tracked, Signal::Kill, KILL_WAIT));
+    }
+
+    let system = process_table();
+    let mut remaining_pids: Vec<u32> = tracked.iter()
+        .filter(|(pid, start_time)| is_running(&system, **pid, **start_time))
+        .map(|(pid, _)| *pid)
+        .collect();
+    remaining_pids.sort_unstable();
+    let exited = !remaining_pids.contains(&identity.pid);
+
+    let signals: Vec<&str> = steps.iter().map(|s| s.signal.as_str()).collect();
+    if remaining_pids.is_empty() {
+        info!("Terminated process {} and {} descendants ({})", identity.pid, tracked.len() - 1, signals.join(" → "));
+    } else {
+        warn!("Processes still running after terminating {}: {:?}", identity.pid, remaining_pids);
+    }
+
+    Ok(TerminationResult {
+        pid: identity.pid,
+        exited,
+        steps,
+        remaining_pids,
+    })
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use sysinfo::{Pid, Signal, System};
+
+/// A process started by an agent, directly or through a shell
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildProcess {
+    pub pid: u32,
+    pub parent_pid: u32,
+    pub name: String,
+    /// Full command line joined with spaces
+    pub command: String,
+    pub cpu_usage: f32,
+    /// Resident memory in bytes
+    pub memory: u64,
+    /// Start time in seconds since the epoch
+    pub start_time: u64,
+    pub runtime_secs: u64,
+}
+
+/// Pids below `root` in a (pid, parent pid) table, parents before their children
+pub fn descendant_pids(parents: &[(u32, u32)], root: u32) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for &(pid, parent) in parents {
+        if pid != parent {
+            children.entry(parent).or_default().push(pid);
+        }
+    }
+    for list in children.values_mut() {
+        list.sort_unstable();
+    }
+
+    let mut result = Vec::new();
+    let mut queue = std::collections::VecDeque::from([root]);
+    while let Some(pid) = queue.pop_front() {
+        for &child in children.get(&pid).into_iter().flatten() {
+            // A cycle can only come from a pid reused between reads, stop there
+            if child != root && !result.contains(&child) {
+                result.push(child);
+                queue.push_back(child);
+            }
+        }
+    }
+    result
+}
+
+/// Whether `pid` is below `ancestor` in the parent chain
+pub fn is_descendant(system: &System, ancestor: u32, pid: u32) -> bool {
+    let mut current = system.process(Pid::from_u32(pid)).and_then(|p| p.parent());
+    // Bounded so a parent loop from a stale snapshot can't spin forever
+    for _ in 0..64 {
+        let Some(parent) = current else {
+            return false;
+        };
+        if parent.as_u32() == ancestor {
+            return true;
+        }
+        current = system.process(parent).and_then(|p| p.parent());
+    }
+    false
+}
+
+/// Descendants of every agent pid, from one pass over the process table
+pub fn child_processes(system: &System, agent_pids: &[u32]) -> HashMap<u32, Vec<ChildProcess>> {
+    let parents: Vec<(u32, u32)> = system.processes()
+        .iter()
+        // Threads are listed as processes on Linux; they aren't separate work
+        .filter(|(_, p)| p.thread_kind().is_none())
+        .filter_map(|(pid, p)| Some((pid.as_u32(), p.parent()?.as_u32())))
+        .collect();
+
+    agent_pids.iter()
+        .map(|&agent_pid| {
+            let children = descendant_pids(&parents, agent_pid)
+                .into_iter()
+                .filter_map(|pid| system.process(Pid::from_u32(pid)))
+                .map(|p| ChildProcess {
+                    pid: p.pid().as_u32(),
+                    parent_pid: p.parent().map(|pp| pp.as_u32()).unwrap_or(0),
+                    name: p.name().to_string_lossy().to_string(),
+                    command: p.cmd().iter().map(|s| s.to_string_lossy()).collect::<Vec<_>>().join(" "),
+                    cpu_usage: p.cpu_usage(),
+                    memory: p.memory(),
+                    start_time: p.start_time(),
+                    runtime_secs: p.run_time(),
+                })
+                .collect();
+            (agent_pid, children)
+        })
+        .collect()
+}
+
+/// Send SIGTERM to a process left running by an ended session. Refuses pids
+/// that now belong to a process with a different start time.
+pub fn terminate_orphan(system: &System, pid: u32, start_time: u64) -> Result<(), String> {
+    let process = system.process(Pid::from_u32(pid))
+        .filter(|p| p.start_time() == start_time)
+        .ok_or_else(|| format!("Process {} is no longer running", pid))?;
+    match process.kill_with(Signal::Term) {
+        Some(true) => Ok(()),
+        Some(false) => Err(format!("Failed to terminate process {}", pid)),
+        None => Err("Terminating processes is not supported on this platform".to_string()),
+    }
+}
+
+/// Send SIGTERM to a process the agent started, leaving the agent running.
+/// Refuses pids that are not currently below the agent in the process tree.
+pub fn terminate_child(system: &System, agent_pid: u32, child_pid: u32) -> Result<(), String> {
+    if !is_descendant(system, agent_pid, child_pid) {
+        return Err(format!("Process {} is not a child of agent process {}", child_pid, agent_pid));
+    }
+    let process = system.process(Pid::from_u32(child_pid))
+        .ok_or_else(|| format!("Process {} is not running", child_pid))?;
+    match process.kill_with(Signal::Term) {
+        Some(true) => Ok(()),
+        Some(false) => Err(format!("Failed to terminate process {}", child_pid)),
+        None => Err("Terminating processes is not supported on this platform".to_string()),
+    }
+}
@@ -0,0 +1,88 @@
+use std::io::{BufRead, Write};
+use std::time::Duration;
+
+use crate::agent;
+use crate::session::get_sessions;
+use crate::terminal;
+
+use super::protocol::{CollectorCommand, CollectorMessage, CollectorReply, CollectorRequest};
+
+/// Time between two polls in `--stream` mode, the same as the app's refresh
+const STREAM_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Run the detection without a window: `--collect` prints one `SessionsResponse`
+/// as JSON, `--collect --stream` keeps printing them and takes commands on stdin.
+/// Returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    if !args.iter().any(|a| a == "--stream") {
+        // CPU usage is only known from the second refresh on
+        get_sessions();
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        return match serde_json::to_string(&get_sessions()) {
+            Ok(json) => {
+                println!("{}", json);
+                0
+            }
+            Err(e) => {
+                eprintln!("Failed to serialize sessions: {}", e);
+                1
+            }
+        };
+    }
+
+    // Stdin closes when the app or the ssh connection goes away
+    std::thread::spawn(|| {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            match serde_json::from_str::<CollectorRequest>(&line) {
+                Ok(request) => {
+                    // Termination waits for processes to exit; keep the stream going meanwhile
+                    std::thread::spawn(move || {
+                        emit(&CollectorMessage::Reply(CollectorReply::new(request.id, handle_command(request.command))));
+                    });
+                }
+                Err(e) => eprintln!("Ignoring invalid request {:?}: {}", line, e),
+            }
+        }
+        std::process::exit(0);
+    });
+
+    loop {
+        emit(&CollectorMessage::Sessions(get_sessions()));
+        std::thread::sleep(STREAM_INTERVAL);
+    }
+}
+
+/// Write one message as a line; exits once stdout is gone
+fn emit(message: &CollectorMessage) {
+    let Ok(json) = serde_json::to_string(message) else {
+        return;
+    };
+    let mut stdout = std::io::stdout().lock();
+    if writeln!(stdout, "{}", json).and_then(|_| stdout.flush()).is_err() {
+        std::process::exit(0);
+    }
+}
+
+/// Carry out a command from the app on this machine, like the matching Tauri command would
+pub fn handle_command(command: CollectorCommand) -> Result<serde_json::Value, String> {
+    match command {
+        CollectorCommand::Focus { pid, project_path } => to_json(
+            terminal::focus_terminal_for_pid(pid).or_else(|_| terminal::focus_terminal_by_path(&project_path)),
+        ),
+        CollectorCommand::Kill { session_id, options } => {
+            to_json(agent::terminate_session(&session_id, &options.unwrap_or_default()))
+        }
+        CollectorCommand::KillChild { session_id, child_pid } => {
+            to_json(agent::kill_child_process(&session_id, child_pid))
+        }
+        CollectorCommand::Pause { session_id } => to_json(agent::pause_session(&session_id)),
+        CollectorCommand::Continue { session_id } => to_json(agent::continue_session(&session_id)),
+    }
+}
+
+fn to_json<T: serde::Serialize>(result: Result<T, String>) -> Result<serde_json::Value, String> {
+    result.and_then(|v| serde_json::to_value(v).map_err(|e| e.to_string()))
+}
@@ -0,0 +1,230 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::session::Session;
+
+use super::protocol::{CollectorCommand, CollectorMessage, CollectorRequest};
+use super::transport::Transport;
+
+/// Wait before starting the collector again after it exited or failed to start
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Long enough for a remote kill to escalate all the way to SIGKILL
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Sessions are dropped when the collector hasn't sent any for this long,
+/// e.g. when the network is gone but ssh hasn't noticed yet
+const STALE_AFTER: Duration = Duration::from_secs(30);
+
+/// How a remote host's collector is doing, for the settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteHostStatus {
+    pub host: String,
+    pub connected: bool,
+    pub session_count: usize,
+    /// When sessions were last received (RFC 3339)
+    pub last_update: Option<String>,
+    /// Last error from the transport or the collector's stderr
+    pub error: Option<String>,
+}
+
+type PendingReplies = HashMap<u64, mpsc::Sender<Result<serde_json::Value, String>>>;
+
+/// State shared between a connection and its reader thread
+#[derive(Default)]
+struct Shared {
+    sessions: Vec<Session>,
+    received_at: Option<Instant>,
+    last_update: Option<DateTime<Utc>>,
+    connected: bool,
+    error: Option<String>,
+    stdin: Option<ChildStdin>,
+    child: Option<Child>,
+    pending: PendingReplies,
+}
+
+/// A collector running on a remote host, restarted whenever it exits.
+/// Dropping the connection stops the collector.
+pub struct RemoteConnection {
+    host: String,
+    shared: Arc<Mutex<Shared>>,
+    stop: Arc<AtomicBool>,
+    next_id: AtomicU64,
+}
+
+impl RemoteConnection {
+    /// Start the collector through `transport` in the background. Its sessions
+    /// are reported with `host` in their `host` field.
+    pub fn start(host: &str, transport: Box<dyn Transport>) -> Self {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        {
+            let host = host.to_string();
+            let shared = Arc::clone(&shared);
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || supervise(&host, transport.as_ref(), &shared, &stop));
+        }
+        RemoteConnection { host: host.to_string(), shared, stop, next_id: AtomicU64::new(1) }
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Latest sessions of the host, none while disconnected or stale
+    pub fn sessions(&self) -> Vec<Session> {
+        let shared = self.shared.lock().unwrap();
+        if shared.received_at.is_some_and(|at| at.elapsed() < STALE_AFTER) {
+            shared.sessions.clone()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn status(&self) -> RemoteHostStatus {
+        let session_count = self.sessions().len();
+        let shared = self.shared.lock().unwrap();
+        RemoteHostStatus {
+            host: self.host.clone(),
+            connected: shared.connected,
+            session_count,
+            last_update: shared.last_update.map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            error: shared.error.clone(),
+        }
+    }
+
+    /// Send a command to the collector and wait for its reply
+    pub fn request(&self, command: CollectorCommand) -> Result<serde_json::Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let line = serde_json::to_string(&CollectorRequest { id, command }).map_err(|e| e.to_string())?;
+        let (sender, receiver) = mpsc::channel();
+        {
+            let mut shared = self.shared.lock().unwrap();
+            let stdin = shared.stdin.as_mut().ok_or_else(|| format!("Not connected to {}", self.host))?;
+            if let Err(e) = writeln!(stdin, "{}", line).and_then(|_| stdin.flush()) {
+                return Err(format!("Failed to send command to {}: {}", self.host, e));
+            }
+            shared.pending.insert(id, sender);
+        }
+
+        let reply = receiver.recv_timeout(REQUEST_TIMEOUT);
+        self.shared.lock().unwrap().pending.remove(&id);
+        match reply {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(format!("{} did not reply in time", self.host)),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(format!("Connection to {} closed", self.host)),
+        }
+    }
+}
+
+impl Drop for RemoteConnection {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(mut child) = self.shared.lock().unwrap().child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Run the collector until the connection is dropped, restarting it when it exits
+fn supervise(host: &str, transport: &dyn Transport, shared: &Mutex<Shared>, stop: &AtomicBool) {
+    while !stop.load(Ordering::Relaxed) {
+        match transport.start() {
+            Ok(mut child) => {
+                info!("Started collector for remote host {}", host);
+                let stdout = child.stdout.take();
+                let stderr = child.stderr.take();
+                {
+                    let mut shared = shared.lock().unwrap();
+                    // Dropped while starting: Drop has already looked for a child to kill.
+                    // Checked under the lock Drop takes, so one of the two always sees the other.
+                    if stop.load(Ordering::Relaxed) {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        break;
+                    }
+                    shared.stdin = child.stdin.take();
+                    shared.child = Some(child);
+                    shared.connected = true;
+                    shared.error = None;
+                }
+                let stderr_reader = stderr.map(|stderr| std::thread::spawn(move || last_line(stderr)));
+                if let Some(stdout) = stdout {
+                    for line in BufReader::new(stdout).lines() {
+                        let Ok(line) = line else {
+                            break;
+                        };
+                        handle_line(host, &line, shared);
+                    }
+                }
+
+                let status = shared.lock().unwrap().child.take().and_then(|mut c| c.wait().ok());
+                let stderr_line = stderr_reader.and_then(|r| r.join().ok()).flatten();
+                let mut shared = shared.lock().unwrap();
+                shared.connected = false;
+                shared.stdin = None;
+                shared.sessions.clear();
+                // Dropping the senders fails the waiting requests
+                shared.pending.clear();
+                if !stop.load(Ordering::Relaxed) {
+                    let error = stderr_line.unwrap_or_else(|| match status {
+                        Some(status) => format!("Collector exited with {}", status),
+                        None => "Collector exited".to_string(),
+                    });
+                    warn!("Lost remote host {}: {}", host, error);
+                    shared.error = Some(error);
+                }
+            }
+            Err(e) => {
+                warn!("Failed to start collector for remote host {}: {}", host, e);
+                shared.lock().unwrap().error = Some(e.to_string());
+            }
+        }
+
+        let started = Instant::now();
+        while started.elapsed() < RECONNECT_DELAY && !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+fn handle_line(host: &str, line: &str, shared: &Mutex<Shared>) {
+    match serde_json::from_str::<CollectorMessage>(line) {
+        Ok(CollectorMessage::Sessions(response)) => {
+            let mut shared = shared.lock().unwrap();
+            shared.sessions = response.sessions
+                .into_iter()
+                .map(|mut s| {
+                    s.host = Some(host.to_string());
+                    s
+                })
+                .collect();
+            shared.received_at = Some(Instant::now());
+            shared.last_update = Some(Utc::now());
+        }
+        Ok(CollectorMessage::Reply(reply)) => {
+            if let Some(sender) = shared.lock().unwrap().pending.remove(&reply.id) {
+                let _ = sender.send(reply.into_result());
+            }
+        }
+        Err(e) => debug!("Ignoring output of {}'s collector: {} ({})", host, line, e),
+    }
+}
+
+/// Read a stream to its end and return its last non-empty line
+fn last_line(stream: impl Read) -> Option<String> {
+    BufReader::new(stream)
+        .lines()
+        .map_while(Result::ok)
+        .filter(|l| !l.trim().is_empty())
+        .last()
+}
@@ -0,0 +1,82 @@
+pub mod collector;
+pub mod connection;
+pub mod protocol;
+pub mod transport;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+use crate::session::Session;
+
+pub use connection::{RemoteConnection, RemoteHostStatus};
+pub use protocol::{CollectorCommand, CollectorMessage, CollectorReply, CollectorRequest};
+pub use transport::{LocalTransport, SshTransport, Transport};
+
+/// Run on the remote host when no other command is configured
+pub const DEFAULT_COLLECTOR_COMMAND: &str = "agent-sessions --collect --stream";
+
+/// A machine whose sessions are shown next to the local ones
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteHost {
+    /// ssh destination, e.g. `devbox` or `me@10.0.0.5`
+    pub host: String,
+    /// Collector command line, for when agent-sessions isn't on the remote PATH
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+struct ConfiguredHost {
+    config: RemoteHost,
+    /// Shared with requests in flight, which keep it open after the host is removed
+    connection: Arc<RemoteConnection>,
+}
+
+static REMOTE_HOSTS: Lazy<Mutex<Vec<ConfiguredHost>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Connect to the given hosts over ssh, keeping connections to hosts that
+/// are still configured and closing the others
+pub fn set_remote_hosts(hosts: Vec<RemoteHost>) {
+    let mut configured = REMOTE_HOSTS.lock().unwrap();
+    configured.retain(|c| hosts.contains(&c.config));
+    for host in hosts {
+        if configured.iter().any(|c| c.config == host) {
+            continue;
+        }
+        let transport = SshTransport {
+            destination: host.host.clone(),
+            command: host.command.clone().unwrap_or_else(|| DEFAULT_COLLECTOR_COMMAND.to_string()),
+        };
+        let connection = Arc::new(RemoteConnection::start(&host.host, Box::new(transport)));
+        configured.push(ConfiguredHost { config: host, connection });
+    }
+}
+
+/// Latest sessions of all remote hosts, with `host` set
+pub fn remote_sessions() -> Vec<Session> {
+    REMOTE_HOSTS.lock().unwrap().iter().flat_map(|c| c.connection.sessions()).collect()
+}
+
+pub fn remote_host_status() -> Vec<RemoteHostStatus> {
+    REMOTE_HOSTS.lock().unwrap().iter().map(|c| c.connection.status()).collect()
+}
+
+/// Host that reported a live session, None for local sessions
+pub fn host_of_session(session_id: &str) -> Option<String> {
+    REMOTE_HOSTS.lock().unwrap()
+        .iter()
+        .find(|c| c.connection.sessions().iter().any(|s| s.id == session_id))
+        .map(|c| c.connection.host().to_string())
+}
+
+/// Send a command to a host's collector and wait for the reply
+pub fn send_command(host: &str, command: CollectorCommand) -> Result<serde_json::Value, String> {
+    // The request waits for the reply, so it mustn't hold the host list meanwhile
+    let connection = REMOTE_HOSTS.lock().unwrap()
+        .iter()
+        .find(|c| c.connection.host() == host)
+        .map(|c| Arc::clone(&c.connection))
+        .ok_or_else(|| format!("Unknown remote host {}", host))?;
+    connection.request(command)
+}
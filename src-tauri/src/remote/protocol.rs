@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use crate::process::TerminateOptions;
+use crate::session::SessionsResponse;
+
+/// An action on a remote host's session, sent to its collector as one JSON line on stdin
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "command", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum CollectorCommand {
+    Focus { pid: u32, project_path: String },
+    Kill { session_id: String, options: Option<TerminateOptions> },
+    KillChild { session_id: String, child_pid: u32 },
+    Pause { session_id: String },
+    Continue { session_id: String },
+}
+
+/// A command with the id its reply will carry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectorRequest {
+    pub id: u64,
+    #[serde(flatten)]
+    pub command: CollectorCommand,
+}
+
+/// Outcome of a request: the command's JSON result, or why it failed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectorReply {
+    pub id: u64,
+    pub ok: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+impl CollectorReply {
+    pub fn new(id: u64, result: Result<serde_json::Value, String>) -> Self {
+        match result {
+            Ok(value) => CollectorReply { id, ok: Some(value), error: None },
+            Err(e) => CollectorReply { id, ok: None, error: Some(e) },
+        }
+    }
+
+    pub fn into_result(self) -> Result<serde_json::Value, String> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.ok.unwrap_or(serde_json::Value::Null)),
+        }
+    }
+}
+
+/// One JSON line a streaming collector writes to stdout. Sessions lines are
+/// a `SessionsResponse` with an added `"type": "sessions"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CollectorMessage {
+    Sessions(SessionsResponse),
+    Reply(CollectorReply),
+}
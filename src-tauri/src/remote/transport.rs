@@ -0,0 +1,46 @@
+use std::io;
+use std::process::{Child, Command, Stdio};
+
+/// Starts a streaming collector whose stdin and stdout carry the protocol
+pub trait Transport: Send + Sync {
+    /// Spawn the collector with stdin, stdout and stderr piped
+    fn start(&self) -> io::Result<Child>;
+}
+
+/// `ssh <destination> <command>`. Runs non-interactively, so hosts need key
+/// or agent authentication; a password prompt fails instead of hanging.
+pub struct SshTransport {
+    /// Anything ssh accepts: `host`, `user@host` or an alias from ~/.ssh/config
+    pub destination: String,
+    pub command: String,
+}
+
+impl Transport for SshTransport {
+    fn start(&self) -> io::Result<Child> {
+        Command::new("ssh")
+            .args(["-T", "-o", "BatchMode=yes", "-o", "ServerAliveInterval=15", "--"])
+            .arg(&self.destination)
+            .arg(&self.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+    }
+}
+
+/// Runs the collector as a subprocess on this machine, e.g. in tests
+pub struct LocalTransport {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl Transport for LocalTransport {
+    fn start(&self) -> io::Result<Child> {
+        Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+    }
+}
@@ -0,0 +1,493 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, SecondsFormat, TimeZone, Utc};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use super::events::{add_listener, SessionEvent, SessionEventKind};
+use super::model::{AgentType, Session, SessionStatus};
+
+/// Token totals of a running session are written at most this often
+const TOKEN_RECORD_INTERVAL_SECS: i64 = 60;
+
+/// What a line of the activity log records
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ActivityKind {
+    /// The app started; sessions still open from a previous run end with its last record
+    AppStarted,
+    AppStopped,
+    SessionStarted {
+        /// Project name, for display
+        project: String,
+        /// Missing in logs written before projects were told apart by path
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        project_path: Option<String>,
+        agent_type: AgentType,
+        status: SessionStatus,
+    },
+    StatusChanged { status: SessionStatus },
+    SessionEnded,
+    /// Token totals of a session so far
+    Tokens { input_tokens: u64, output_tokens: u64 },
+}
+
+/// One line of the activity log
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityRecord {
+    /// When it happened (RFC 3339)
+    pub at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(flatten)]
+    pub kind: ActivityKind,
+}
+
+impl ActivityRecord {
+    pub fn new(at: DateTime<Utc>, session_id: Option<&str>, kind: ActivityKind) -> Self {
+        ActivityRecord {
+            at: at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            session_id: session_id.map(String::from),
+            kind,
+        }
+    }
+}
+
+/// Time and tokens a project's sessions spent on one day
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDayActivity {
+    /// Local date, e.g. "2024-01-31"
+    pub day: String,
+    /// Project name, for display; projects in different directories may share it
+    pub project: String,
+    /// What the project is told apart by; its name in logs that predate paths
+    pub project_path: String,
+    /// Seconds spent Processing, Thinking or Compacting
+    pub processing_secs: i64,
+    pub waiting_secs: i64,
+    pub idle_secs: i64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// Sessions started in one week
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklySessions {
+    /// Local date of the week's Monday
+    pub week_start: String,
+    pub sessions_started: usize,
+    /// Number of distinct projects those sessions ran in
+    pub project_count: usize,
+}
+
+/// Which report to export
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ActivityReport {
+    ProjectTime,
+    WeeklySessions,
+}
+
+/// Append-only activity log, one JSON record per line
+pub struct ActivityStore {
+    path: PathBuf,
+    file: File,
+}
+
+impl ActivityStore {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(ActivityStore { path: path.to_path_buf(), file })
+    }
+
+    pub fn append(&mut self, records: &[ActivityRecord]) -> io::Result<()> {
+        let mut buf = String::new();
+        for record in records {
+            buf.push_str(&serde_json::to_string(record).map_err(io::Error::other)?);
+            buf.push('\n');
+        }
+        // One write per batch, so a crash can only cut off the last line
+        self.file.write_all(buf.as_bytes())
+    }
+
+    /// All records, skipping lines that don't parse (e.g. cut off by a crash)
+    pub fn read_all(&self) -> io::Result<Vec<ActivityRecord>> {
+        read_records(&self.path)
+    }
+}
+
+/// Read an activity log, skipping lines that don't parse
+pub fn read_records(path: &Path) -> io::Result<Vec<ActivityRecord>> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(reader.lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect())
+}
+
+/// Token totals of a live session, so unchanged totals aren't written every poll
+struct TokenTotals {
+    recorded_at: DateTime<Utc>,
+    recorded: (u64, u64),
+    latest: (u64, u64),
+}
+
+/// The log the app writes to, None until `start` succeeds
+static ACTIVITY_STORE: Lazy<Mutex<Option<ActivityStore>>> = Lazy::new(|| Mutex::new(None));
+
+static TOKEN_TOTALS: Lazy<Mutex<HashMap<String, TokenTotals>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Location of the activity log in the app's data directory
+pub fn default_store_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("agent-sessions").join("activity.jsonl"))
+}
+
+/// Open the activity log and record session events to it from now on
+pub fn start() {
+    let Some(path) = default_store_path() else {
+        warn!("No data directory, session activity won't be recorded");
+        return;
+    };
+    let mut store = match ActivityStore::open(&path) {
+        Ok(store) => store,
+        Err(e) => {
+            warn!("Failed to open activity log {:?}: {}", path, e);
+            return;
+        }
+    };
+    if let Err(e) = store.append(&[ActivityRecord::new(Utc::now(), None, ActivityKind::AppStarted)]) {
+        warn!("Failed to write activity log: {}", e);
+    }
+    info!("Recording session activity to {:?}", path);
+    *ACTIVITY_STORE.lock().unwrap() = Some(store);
+    add_listener(record_event);
+}
+
+/// Record that the app is quitting, which ends the intervals of all open sessions
+pub fn stop() {
+    let mut records = flush_token_totals(None);
+    records.push(ActivityRecord::new(Utc::now(), None, ActivityKind::AppStopped));
+    append(&records);
+}
+
+fn append(records: &[ActivityRecord]) {
+    if records.is_empty() {
+        return;
+    }
+    if let Some(store) = ACTIVITY_STORE.lock().unwrap().as_mut() {
+        if let Err(e) = store.append(records) {
+            warn!("Failed to write activity log: {}", e);
+        }
+    }
+}
+
+/// Lifecycle and status events become records; subagent and audit events aren't kept
+fn record_event(event: &SessionEvent) {
+    let Some(at) = parse_time(&event.at) else {
+        return;
+    };
+    let session_id = Some(event.session_id.as_str());
+    let records = match &event.kind {
+        SessionEventKind::SessionStarted { status, project_path } => vec![ActivityRecord::new(at, session_id, ActivityKind::SessionStarted {
+            project: event.project_name.clone(),
+            project_path: Some(project_path.clone()),
+            agent_type: event.agent_type.clone(),
+            status: status.clone(),
+        })],
+        SessionEventKind::StatusChanged { to, .. } => {
+            vec![ActivityRecord::new(at, session_id, ActivityKind::StatusChanged { status: to.clone() })]
+        }
+        SessionEventKind::SessionEnded => {
+            let mut records = flush_token_totals(Some(&event.session_id));
+            records.push(ActivityRecord::new(at, session_id, ActivityKind::SessionEnded));
+            records
+        }
+        _ => Vec::new(),
+    };
+    append(&records);
+}
+
+/// Record the token totals of sessions whose totals changed, at most once a minute per session
+pub fn record_token_totals(sessions: &[Session]) {
+    if ACTIVITY_STORE.lock().unwrap().is_none() {
+        return;
+    }
+    let now = Utc::now();
+    let mut records = Vec::new();
+    {
+        let mut totals = TOKEN_TOTALS.lock().unwrap();
+        for session in sessions {
+            let latest = (session.total_input_tokens, session.total_output_tokens);
+            let entry = totals.entry(session.id.clone()).or_insert(TokenTotals {
+                recorded_at: now - Duration::seconds(TOKEN_RECORD_INTERVAL_SECS),
+                recorded: (0, 0),
+                latest,
+            });
+            entry.latest = latest;
+            if entry.latest != entry.recorded && now - entry.recorded_at >= Duration::seconds(TOKEN_RECORD_INTERVAL_SECS) {
+                entry.recorded_at = now;
+                entry.recorded = latest;
+                records.push(token_record(now, &session.id, latest));
+            }
+        }
+    }
+    append(&records);
+}
+
+/// Records for totals not yet written, of one session (which is then forgotten) or all
+fn flush_token_totals(session_id: Option<&str>) -> Vec<ActivityRecord> {
+    let now = Utc::now();
+    let mut totals = TOKEN_TOTALS.lock().unwrap();
+    let pending = |id: &str, t: &TokenTotals| (t.latest != t.recorded).then(|| token_record(now, id, t.latest));
+    match session_id {
+        Some(id) => totals.remove(id).and_then(|t| pending(id, &t)).into_iter().collect(),
+        None => totals.iter().filter_map(|(id, t)| pending(id, t)).collect(),
+    }
+}
+
+fn token_record(at: DateTime<Utc>, session_id: &str, (input_tokens, output_tokens): (u64, u64)) -> ActivityRecord {
+    ActivityRecord::new(at, Some(session_id), ActivityKind::Tokens { input_tokens, output_tokens })
+}
+
+/// A session with a status interval in progress while replaying the log
+struct OpenSession {
+    status: SessionStatus,
+    since: DateTime<Utc>,
+}
+
+/// A session's project while replaying the log, as (path, name)
+type Project<'a> = (&'a str, &'a str);
+
+/// Per-day buckets by local date and project path
+type Buckets = BTreeMap<(NaiveDate, String), ProjectDayActivity>;
+
+/// Add a status interval to the per-day buckets, split at local midnight
+fn add_interval<Tz: TimeZone>(
+    buckets: &mut Buckets,
+    tz: &Tz,
+    project: Project,
+    status: &SessionStatus,
+    mut start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) {
+    while start < end {
+        let day = start.with_timezone(tz).date_naive();
+        let next_midnight = day.succ_opt()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .and_then(|t| tz.from_local_datetime(&t).earliest())
+            .map(|t| t.with_timezone(&Utc))
+            .filter(|t| *t > start)
+            .unwrap_or(end);
+        let segment_end = end.min(next_midnight);
+        let secs = (segment_end - start).num_seconds();
+
+        let bucket = bucket(buckets, day, project);
+        match status {
+            SessionStatus::Processing | SessionStatus::Thinking | SessionStatus::Compacting => bucket.processing_secs += secs,
+            SessionStatus::Waiting => bucket.waiting_secs += secs,
+            SessionStatus::Idle | SessionStatus::Paused => bucket.idle_secs += secs,
+        }
+        start = segment_end;
+    }
+}
+
+fn bucket<'a>(buckets: &'a mut Buckets, day: NaiveDate, (path, name): Project) -> &'a mut ProjectDayActivity {
+    buckets.entry((day, path.to_string())).or_insert_with(|| ProjectDayActivity {
+        day: day.format("%Y-%m-%d").to_string(),
+        project: name.to_string(),
+        project_path: path.to_string(),
+        ..Default::default()
+    })
+}
+
+/// The project a session started in; logs that predate paths only have the name
+fn project_of<'a>(name: &'a str, path: &'a Option<String>) -> Project<'a> {
+    (path.as_deref().unwrap_or(name), name)
+}
+
+fn parse_time(at: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(at).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Replay the log into time per status and tokens per project and local day, oldest day first.
+/// Intervals still open at the end of the log run until `now`.
+pub fn time_by_project_day<Tz: TimeZone>(records: &[ActivityRecord], tz: &Tz, now: DateTime<Utc>) -> Vec<ProjectDayActivity> {
+    let mut buckets: Buckets = BTreeMap::new();
+    let mut open: HashMap<&str, OpenSession> = HashMap::new();
+    // Kept across app restarts, which start sessions again
+    let mut projects: HashMap<&str, Project> = HashMap::new();
+    let mut tokens: HashMap<&str, (u64, u64)> = HashMap::new();
+    let mut last_at: Option<DateTime<Utc>> = None;
+
+    for record in records {
+        let Some(at) = parse_time(&record.at) else {
+            continue;
+        };
+        let session_id = record.session_id.as_deref().unwrap_or_default();
+        let project = projects.get(session_id).copied().unwrap_or_default();
+
+        match &record.kind {
+            ActivityKind::AppStarted | ActivityKind::AppStopped => {
+                // After a crash, the last record of the previous run is the best guess for its end
+                let end = if record.kind == ActivityKind::AppStarted { last_at.unwrap_or(at) } else { at };
+                for (id, session) in open.drain() {
+                    add_interval(&mut buckets, tz, projects.get(id).copied().unwrap_or_default(), &session.status, session.since, end);
+                }
+            }
+            ActivityKind::SessionStarted { project, project_path, status, .. } => {
+                if let Some(session) = open.remove(session_id) {
+                    add_interval(&mut buckets, tz, projects.get(session_id).copied().unwrap_or_default(), &session.status, session.since, at);
+                }
+                projects.insert(session_id, project_of(project, project_path));
+                open.insert(session_id, OpenSession { status: status.clone(), since: at });
+            }
+            ActivityKind::StatusChanged { status } => {
+                if let Some(session) = open.get_mut(session_id) {
+                    add_interval(&mut buckets, tz, project, &session.status, session.since, at);
+                    session.status = status.clone();
+                    session.since = session.since.max(at);
+                }
+            }
+            ActivityKind::SessionEnded => {
+                if let Some(session) = open.remove(session_id) {
+                    add_interval(&mut buckets, tz, project, &session.status, session.since, at);
+                }
+            }
+            ActivityKind::Tokens { input_tokens, output_tokens } => {
+                let previous = tokens.insert(session_id, (*input_tokens, *output_tokens)).unwrap_or((0, 0));
+                let bucket = bucket(&mut buckets, at.with_timezone(tz).date_naive(), project);
+                bucket.input_tokens += input_tokens.saturating_sub(previous.0);
+                bucket.output_tokens += output_tokens.saturating_sub(previous.1);
+            }
+        }
+        last_at = last_at.max(Some(at));
+    }
+
+    for (id, session) in open {
+        add_interval(&mut buckets, tz, projects.get(id).copied().unwrap_or_default(), &session.status, session.since, now);
+    }
+    buckets.into_values().collect()
+}
+
+/// Count the sessions first started in each week (Monday to Sunday, local time), oldest week first
+pub fn sessions_per_week<Tz: TimeZone>(records: &[ActivityRecord], tz: &Tz) -> Vec<WeeklySessions> {
+    let mut seen: BTreeSet<&str> = BTreeSet::new();
+    let mut weeks: BTreeMap<NaiveDate, (usize, BTreeSet<&str>)> = BTreeMap::new();
+
+    for record in records {
+        let ActivityKind::SessionStarted { project, project_path, .. } = &record.kind else {
+            continue;
+        };
+        let (Some(session_id), Some(at)) = (record.session_id.as_deref(), parse_time(&record.at)) else {
+            continue;
+        };
+        if !seen.insert(session_id) {
+            continue;
+        }
+        let day = at.with_timezone(tz).date_naive();
+        let week = weeks.entry(monday_of(day)).or_default();
+        week.0 += 1;
+        week.1.insert(project_of(project, project_path).0);
+    }
+
+    weeks.into_iter()
+        .map(|(monday, (sessions_started, projects))| WeeklySessions {
+            week_start: monday.format("%Y-%m-%d").to_string(),
+            sessions_started,
+            project_count: projects.len(),
+        })
+        .collect()
+}
+
+fn read_store() -> Result<Vec<ActivityRecord>, String> {
+    let path = default_store_path().ok_or("Could not determine data directory")?;
+    match read_records(&path) {
+        Ok(records) => Ok(records),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read activity log: {}", e)),
+    }
+}
+
+/// Time per project and day over the last `days` days (including today), from the app's log
+pub fn project_time_report(days: u32) -> Result<Vec<ProjectDayActivity>, String> {
+    let records = read_store()?;
+    let first_day = (Local::now() - Duration::days(days.saturating_sub(1) as i64)).format("%Y-%m-%d").to_string();
+    let mut report = time_by_project_day(&records, &Local, Utc::now());
+    report.retain(|r| r.day >= first_day);
+    debug!("Project time report: {} rows from {} records", report.len(), records.len());
+    Ok(report)
+}
+
+/// Sessions started per week over the last `weeks` calendar weeks (including this one), from the app's log
+pub fn weekly_sessions_report(weeks: u32) -> Result<Vec<WeeklySessions>, String> {
+    let records = read_store()?;
+    Ok(last_weeks(sessions_per_week(&records, &Local), Local::now().date_naive(), weeks))
+}
+
+/// Keep the rows of the `weeks` calendar weeks up to the one containing `today`.
+/// Weeks without sessions have no row, so this is by date rather than by count.
+pub fn last_weeks(mut rows: Vec<WeeklySessions>, today: NaiveDate, weeks: u32) -> Vec<WeeklySessions> {
+    let first_week = (monday_of(today) - Duration::weeks(weeks.saturating_sub(1) as i64)).format("%Y-%m-%d").to_string();
+    rows.retain(|r| r.week_start >= first_week);
+    rows
+}
+
+fn monday_of(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_monday() as i64)
+}
+
+/// Quote a CSV field if needed
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn hours(secs: i64) -> String {
+    format!("{:.2}", secs as f64 / 3600.0)
+}
+
+pub fn project_time_csv(rows: &[ProjectDayActivity]) -> String {
+    let mut csv = String::from("day,project,project_path,processing_hours,waiting_hours,idle_hours,input_tokens,output_tokens\n");
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            row.day, csv_field(&row.project), csv_field(&row.project_path), hours(row.processing_secs), hours(row.waiting_secs), hours(row.idle_secs),
+            row.input_tokens, row.output_tokens,
+        ));
+    }
+    csv
+}
+
+pub fn weekly_sessions_csv(rows: &[WeeklySessions]) -> String {
+    let mut csv = String::from("week_start,sessions_started,project_count\n");
+    for row in rows {
+        csv.push_str(&format!("{},{},{}\n", row.week_start, row.sessions_started, row.project_count));
+    }
+    csv
+}
+
+/// Write a report as CSV to the downloads directory, returning the file's path
+pub fn export_csv(report: ActivityReport, period: u32) -> Result<String, String> {
+    let (name, csv) = match report {
+        ActivityReport::ProjectTime => ("project-time", project_time_csv(&project_time_report(period)?)),
+        ActivityReport::WeeklySessions => ("weekly-sessions", weekly_sessions_csv(&weekly_sessions_report(period)?)),
+    };
+    let dir = dirs::download_dir().or_else(dirs::home_dir).ok_or("Could not determine downloads directory")?;
+    let path = dir.join(format!("agent-sessions-{}-{}.csv", name, Local::now().format("%Y-%m-%d")));
+    fs::write(&path, csv).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    info!("Exported {:?} report to {:?}", report, path);
+    Ok(path.to_string_lossy().to_string())
+}
@@ -0,0 +1,379 @@
+use chrono::{SecondsFormat, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use super::events::{record_events, SessionEvent, SessionEventKind};
+use super::files::edited_path;
+use super::incremental::TranscriptUpdate;
+use super::model::{JsonlMessage, Session};
+use super::parser::host_file_path;
+use super::shell::{parse_command_line, Pipeline, SimpleCommand};
+
+/// Entries kept per session; the oldest are dropped first
+const MAX_AUDIT_ENTRIES: usize = 1000;
+
+/// Length at which command lines and error messages are cut off
+const MAX_DETAIL_CHARS: usize = 2000;
+
+/// What a session did
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditKind {
+    /// A Bash tool call
+    Command,
+    WebFetch,
+    WebSearch,
+    /// A file edit that matched a rule; all edits are listed by `get_session_files`
+    FileEdit,
+}
+
+/// How a tool call ended, from its tool_result
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditOutcome {
+    pub is_error: bool,
+    /// Exit status of a command, None if it didn't run to completion (e.g. rejected)
+    pub exit_code: Option<i32>,
+    /// Start of the error output
+    pub error: Option<String>,
+}
+
+/// A command, web request or out-of-project edit found in a transcript
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub tool_use_id: String,
+    pub kind: AuditKind,
+    /// Command line, URL, search query or file path
+    pub detail: String,
+    /// When the tool was called (RFC 3339)
+    pub at: String,
+    /// Line of the tool call in the transcript
+    pub line_number: usize,
+    /// None while the tool is still running
+    pub outcome: Option<AuditOutcome>,
+    /// Ids of the rules the entry matched
+    pub matched_rules: Vec<String>,
+}
+
+/// Where an audited action happened, for rules that depend on it
+pub struct AuditContext<'a> {
+    pub project_path: &'a str,
+    pub home_dir: Option<&'a Path>,
+}
+
+/// A check run on every audited action
+pub struct AuditRule {
+    pub id: &'static str,
+    pub description: &'static str,
+    matches: fn(&AuditEntry, &[Pipeline], &AuditContext) -> bool,
+}
+
+/// The rules every session's actions are checked against
+pub fn default_rules() -> Vec<AuditRule> {
+    vec![
+        AuditRule { id: "rm-rf", description: "Recursive forced delete", matches: matches_rm_rf },
+        AuditRule { id: "git-force-push", description: "Force push", matches: matches_force_push },
+        AuditRule { id: "pipe-to-shell", description: "Downloaded script piped into a shell", matches: matches_pipe_to_shell },
+        AuditRule { id: "sudo", description: "Command run as root", matches: matches_sudo },
+        AuditRule { id: "write-outside-project", description: "Write outside the project directory", matches: matches_write_outside_project },
+    ]
+}
+
+/// Ids of the rules an entry matches
+pub fn check_rules(entry: &AuditEntry, rules: &[AuditRule], context: &AuditContext) -> Vec<String> {
+    let pipelines = match entry.kind {
+        AuditKind::Command => parse_command_line(&entry.detail),
+        _ => Vec::new(),
+    };
+    rules.iter()
+        .filter(|rule| (rule.matches)(entry, &pipelines, context))
+        .map(|rule| rule.id.to_string())
+        .collect()
+}
+
+fn commands(pipelines: &[Pipeline]) -> impl Iterator<Item = &SimpleCommand> {
+    pipelines.iter().flatten()
+}
+
+/// Short options may be combined, e.g. `-rf` or `-fR`
+fn has_flag(words: &[String], short: &[char], long: &[&str]) -> bool {
+    words.iter().skip(1).any(|w| {
+        if let Some(name) = w.strip_prefix("--") {
+            long.contains(&name)
+        } else if let Some(flags) = w.strip_prefix('-') {
+            flags.chars().any(|c| short.contains(&c))
+        } else {
+            false
+        }
+    })
+}
+
+fn matches_rm_rf(_: &AuditEntry, pipelines: &[Pipeline], _: &AuditContext) -> bool {
+    commands(pipelines).any(|c| {
+        let words = c.effective_words();
+        c.program() == Some("rm")
+            && has_flag(words, &['r', 'R'], &["recursive"])
+            && has_flag(words, &['f'], &["force"])
+    })
+}
+
+fn matches_force_push(_: &AuditEntry, pipelines: &[Pipeline], _: &AuditContext) -> bool {
+    commands(pipelines).any(|c| {
+        let words = c.effective_words();
+        c.program() == Some("git")
+            && words.iter().any(|w| w == "push")
+            // --force-with-lease refuses to overwrite work it hasn't seen, so it isn't flagged
+            && (has_flag(words, &['f'], &["force"]) || words.iter().skip(2).any(|w| w.starts_with('+')))
+    })
+}
+
+fn matches_pipe_to_shell(_: &AuditEntry, pipelines: &[Pipeline], _: &AuditContext) -> bool {
+    const DOWNLOADERS: &[&str] = &["curl", "wget"];
+    const INTERPRETERS: &[&str] = &["sh", "bash", "zsh", "dash", "ksh", "fish", "python", "python3", "perl", "ruby", "node"];
+
+    pipelines.iter().any(|pipeline| {
+        let download = pipeline.iter().position(|c| c.program().map(|p| DOWNLOADERS.contains(&p)).unwrap_or(false));
+        download.map(|start| {
+            pipeline[start + 1..].iter().any(|c| c.program().map(|p| INTERPRETERS.contains(&p)).unwrap_or(false))
+        }).unwrap_or(false)
+    })
+}
+
+fn matches_sudo(_: &AuditEntry, pipelines: &[Pipeline], _: &AuditContext) -> bool {
+    commands(pipelines).any(|c| c.is_privileged())
+}
+
+fn matches_write_outside_project(entry: &AuditEntry, pipelines: &[Pipeline], context: &AuditContext) -> bool {
+    match entry.kind {
+        AuditKind::FileEdit => is_outside_project(&entry.detail, context),
+        AuditKind::Command => commands(pipelines).any(|c| {
+            let tee_targets = (c.program() == Some("tee"))
+                .then(|| c.effective_words().iter().skip(1).filter(|w| !w.starts_with('-')))
+                .into_iter()
+                .flatten();
+            c.redirects.iter().chain(tee_targets).any(|target| is_outside_project(target, context))
+        }),
+        _ => false,
+    }
+}
+
+/// True for absolute (or `~`) paths outside the project, other than temp and device files.
+/// Relative paths depend on the shell's current directory and are assumed to stay inside.
+pub fn is_outside_project(path: &str, context: &AuditContext) -> bool {
+    let path = match (path.strip_prefix("~/"), context.home_dir) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ if path.starts_with('/') => PathBuf::from(path),
+        _ => return false,
+    };
+    let path = normalize(&path);
+
+    let mut allowed = vec![
+        PathBuf::from(context.project_path),
+        PathBuf::from("/dev"),
+        PathBuf::from("/tmp"),
+        PathBuf::from("/private/tmp"),
+        PathBuf::from("/var/folders"),
+        std::env::temp_dir(),
+    ];
+    // Plans, todos and memory the agent keeps for itself
+    if let Some(home) = context.home_dir {
+        allowed.push(home.join(".claude"));
+    }
+    !allowed.iter().any(|dir| path.starts_with(dir))
+}
+
+/// Resolve `.` and `..` without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Truncate on a char boundary
+fn truncate(text: &str) -> String {
+    if text.chars().count() > MAX_DETAIL_CHARS {
+        format!("{}...", text.chars().take(MAX_DETAIL_CHARS).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Audited tool calls in a transcript entry
+fn extract_actions(msg: &JsonlMessage, line_number: usize) -> Vec<AuditEntry> {
+    let Some(serde_json::Value::Array(blocks)) = msg.message.as_ref().and_then(|m| m.content.as_ref()) else {
+        return Vec::new();
+    };
+    let at = msg.timestamp.clone().unwrap_or_default();
+    let entry = |tool_use_id: &str, kind, detail: &str| AuditEntry {
+        tool_use_id: tool_use_id.to_string(),
+        kind,
+        detail: truncate(detail),
+        at: at.clone(),
+        line_number,
+        outcome: None,
+        matched_rules: Vec::new(),
+    };
+
+    blocks.iter()
+        .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+        .filter_map(|b| {
+            let id = b.get("id").and_then(|i| i.as_str()).unwrap_or_default();
+            let input = b.get("input")?;
+            let field = |name: &str| input.get(name).and_then(|v| v.as_str());
+            match b.get("name").and_then(|n| n.as_str())? {
+                "Bash" => Some(entry(id, AuditKind::Command, field("command")?)),
+                "WebFetch" => Some(entry(id, AuditKind::WebFetch, field("url")?)),
+                "WebSearch" => Some(entry(id, AuditKind::WebSearch, field("query")?)),
+                _ => edited_path(b, msg.cwd.as_deref()).map(|(_, path)| entry(id, AuditKind::FileEdit, &path)),
+            }
+        })
+        .collect()
+}
+
+/// Outcomes of tool calls in a transcript entry, by tool_use id
+fn extract_outcomes(msg: &JsonlMessage) -> Vec<(String, AuditOutcome)> {
+    let Some(serde_json::Value::Array(blocks)) = msg.message.as_ref().and_then(|m| m.content.as_ref()) else {
+        return Vec::new();
+    };
+
+    blocks.iter()
+        .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_result"))
+        .filter_map(|b| {
+            let id = b.get("tool_use_id").and_then(|i| i.as_str())?;
+            let is_error = b.get("is_error").and_then(|e| e.as_bool()).unwrap_or(false);
+            let text = match b.get("content") {
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(serde_json::Value::Array(parts)) => parts.iter()
+                    .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                _ => String::new(),
+            };
+            Some((id.to_string(), parse_outcome(is_error, &text)))
+        })
+        .collect()
+}
+
+/// Failed commands start their result with "Exit code <n>"
+pub fn parse_outcome(is_error: bool, text: &str) -> AuditOutcome {
+    if !is_error {
+        return AuditOutcome { is_error, exit_code: Some(0), error: None };
+    }
+    let exit_code = text.strip_prefix("Exit code ")
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|code| code.parse().ok());
+    let error = text.lines()
+        .skip(usize::from(exit_code.is_some()))
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(truncate);
+    AuditOutcome { is_error, exit_code, error }
+}
+
+/// Incremental audit state for one session's transcript
+#[derive(Default)]
+struct AuditState {
+    entries: VecDeque<AuditEntry>,
+    flagged_rules: BTreeSet<String>,
+}
+
+/// Audit logs by session id, updated from the lines appended since the last poll
+static AUDIT_CACHE: Lazy<Mutex<HashMap<String, AuditState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+static DEFAULT_RULES: Lazy<Vec<AuditRule>> = Lazy::new(default_rules);
+
+/// Audit what was appended to a session's transcript since the previous poll and return
+/// the ids of all rules the session has matched. Matches in lines appended after the
+/// first scan are recorded as alert events; older ones only flag the session.
+pub fn update_audit(session: &Session, update: &TranscriptUpdate) -> Vec<String> {
+    let home_dir = dirs::home_dir();
+    let context = AuditContext { project_path: &session.project_path, home_dir: home_dir.as_deref() };
+
+    let mut cache = AUDIT_CACHE.lock().unwrap();
+    let state = cache.entry(session.id.clone()).or_default();
+    if update.from_start {
+        state.entries.clear();
+        state.flagged_rules.clear();
+    }
+
+    let mut alerts = Vec::new();
+    for line in &update.lines {
+        let Some(msg) = &line.msg else {
+            continue;
+        };
+
+        for (id, outcome) in extract_outcomes(msg) {
+            if let Some(entry) = state.entries.iter_mut().rev().find(|e| e.tool_use_id == id) {
+                entry.outcome = Some(outcome);
+            }
+        }
+
+        for mut entry in extract_actions(msg, line.line_number) {
+            entry.matched_rules = check_rules(&entry, &DEFAULT_RULES, &context);
+            // Edits inside the project are listed by get_session_files instead
+            if entry.kind == AuditKind::FileEdit && entry.matched_rules.is_empty() {
+                continue;
+            }
+            for rule in &entry.matched_rules {
+                state.flagged_rules.insert(rule.clone());
+                if !update.from_start {
+                    warn!("Audit rule {} matched in session {}: {}", rule, session.id, entry.detail);
+                    alerts.push(SessionEvent {
+                        seq: 0,
+                        at: if entry.at.is_empty() { Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true) } else { entry.at.clone() },
+                        session_id: session.id.clone(),
+                        agent_type: session.agent_type.clone(),
+                        project_name: session.project_name.clone(),
+                        kind: SessionEventKind::AuditAlert { rule: rule.clone(), detail: entry.detail.clone() },
+                    });
+                }
+            }
+
+            if state.entries.len() == MAX_AUDIT_ENTRIES {
+                state.entries.pop_front();
+            }
+            state.entries.push_back(entry);
+        }
+    }
+
+    let flagged = state.flagged_rules.iter().cloned().collect();
+    drop(cache);
+    record_events(alerts);
+    flagged
+}
+
+/// Audit log of a live session, oldest first
+pub fn get_audit_entries(session_id: &str) -> Option<Vec<AuditEntry>> {
+    let cache = AUDIT_CACHE.lock().unwrap();
+    let entries = cache.get(session_id)?.entries.iter()
+        .cloned()
+        .map(|mut entry| {
+            // Edited files of container sessions at their host paths, like the touched files
+            if entry.kind == AuditKind::FileEdit {
+                if let Some(path) = host_file_path(session_id, &entry.detail) {
+                    entry.detail = path;
+                }
+            }
+            entry
+        })
+        .collect();
+    Some(entries)
+}
+
+/// Drop audit logs of sessions that no longer exist
+pub fn cleanup_stale_audit_entries(active_session_ids: &HashSet<String>) {
+    AUDIT_CACHE.lock().unwrap().retain(|id, _| active_session_ids.contains(id));
+}
@@ -0,0 +1,323 @@
+use log::{debug, trace, warn};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use once_cell::sync::Lazy;
+
+use crate::agent::AgentProcess;
+use crate::process::parse_claude_args;
+use super::model::JsonlMessage;
+
+/// Transcripts whose first entry is this close to a competing signal are reported as ambiguous
+const AMBIGUITY_WINDOW_SECS: i64 = 2;
+
+/// Slack for comparing a process start time (whole seconds) with transcript timestamps
+const START_TIME_SLACK_SECS: i64 = 2;
+
+/// What a process is known to be writing, gathered from the OS
+#[derive(Debug, Clone, Default)]
+pub struct ProcessSignals {
+    pub pid: u32,
+    /// Start time in seconds since the epoch
+    pub start_time: u64,
+    /// Session id from `--resume <id>` or `--session-id <id>`
+    pub cli_session_id: Option<String>,
+    /// Transcript files the process currently has open
+    pub open_files: Vec<PathBuf>,
+}
+
+impl ProcessSignals {
+    /// Gather binding signals for a running agent process
+    pub fn from_process(process: &AgentProcess) -> Self {
+        ProcessSignals {
+            pid: process.pid,
+            start_time: process.start_time,
+            cli_session_id: parse_claude_args(&process.cmd).session_id,
+            open_files: open_jsonl_files(process.pid),
+        }
+    }
+}
+
+/// What is known about a transcript without parsing all of it
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptInfo {
+    pub path: PathBuf,
+    /// Timestamp of the first entry, in seconds since the epoch
+    pub started_at: Option<i64>,
+    /// Last modification time, in seconds since the epoch
+    pub modified_at: Option<i64>,
+    /// True if the transcript was started by /clear in an already running process
+    pub starts_with_clear: bool,
+}
+
+/// The transcript a process is bound to
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptBinding {
+    pub path: PathBuf,
+    /// True if other transcripts matched about as well and this one may be wrong
+    pub ambiguous: bool,
+}
+
+/// A running process identified across pid reuse
+type ProcessKey = (u32, u64);
+
+/// A proposed binding, ranked by how far apart its timestamps are
+struct Claim {
+    pid: u32,
+    file: usize,
+    gap_secs: i64,
+}
+
+/// Binds running processes to the transcripts they write.
+///
+/// Signals, strongest first:
+/// 1. The transcript is among the process's open files
+/// 2. The transcript is named after the `--resume`/`--session-id` argument
+/// 3. An existing binding for the same pid and start time (bindings are sticky)
+/// 4. The transcript's first entry follows the process start, or follows the last
+///    write to the process's previous transcript and begins with /clear
+/// 5. Otherwise, the most recently modified transcript nobody else has claimed
+pub struct TranscriptBinder {
+    bindings: HashMap<ProcessKey, TranscriptBinding>,
+}
+
+impl Default for TranscriptBinder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TranscriptBinder {
+    pub fn new() -> Self {
+        TranscriptBinder { bindings: HashMap::new() }
+    }
+
+    /// Bind each process to one of `files`, which all share the processes' cwd and
+    /// are sorted newest first. Processes without any candidate are left out.
+    pub fn bind(&mut self, processes: &[ProcessSignals], files: &[TranscriptInfo]) -> HashMap<u32, TranscriptBinding> {
+        let mut result: HashMap<u32, TranscriptBinding> = HashMap::new();
+        let mut claimed: HashSet<usize> = HashSet::new();
+        let file_index = |path: &Path| files.iter().position(|f| f.path == path);
+
+        // 1 + 2: open files and command line session ids
+        for process in processes {
+            let mut strong: Vec<usize> = process.open_files.iter().filter_map(|p| file_index(p)).collect();
+            if strong.is_empty() {
+                if let Some(id) = &process.cli_session_id {
+                    strong.extend(files.iter().position(|f| file_stem(&f.path) == Some(id.as_str())));
+                }
+            }
+            strong.sort_unstable();
+            strong.dedup();
+
+            let Some(&index) = strong.iter().find(|i| !claimed.contains(i)) else {
+                if !strong.is_empty() {
+                    warn!("Transcript {:?} for pid={} is already bound to another process", files[strong[0]].path, process.pid);
+                }
+                continue;
+            };
+            let ambiguous = strong.len() > 1 || strong[0] != index;
+            if ambiguous {
+                warn!("pid={} matches {} transcripts directly, using {:?}", process.pid, strong.len(), files[index].path);
+            }
+            claimed.insert(index);
+            result.insert(process.pid, TranscriptBinding { path: files[index].path.clone(), ambiguous });
+        }
+        let directly_bound: HashSet<u32> = result.keys().copied().collect();
+
+        // 3: sticky bindings from earlier polls
+        for process in processes.iter().filter(|p| !directly_bound.contains(&p.pid)) {
+            let Some(previous) = self.bindings.get(&(process.pid, process.start_time)) else {
+                continue;
+            };
+            match file_index(&previous.path) {
+                Some(index) if !claimed.contains(&index) => {
+                    claimed.insert(index);
+                    result.insert(process.pid, previous.clone());
+                }
+                _ => trace!("Previous transcript of pid={} is gone or taken, rebinding", process.pid),
+            }
+        }
+
+        // 4: new processes claim transcripts started after them, bound processes claim
+        // transcripts that /clear started after their previous transcript went quiet
+        let mut claims = Vec::new();
+        for process in processes.iter().filter(|p| !directly_bound.contains(&p.pid)) {
+            let bound_modified_at = result.get(&process.pid)
+                .and_then(|b| file_index(&b.path))
+                .map(|i| files[i].modified_at);
+
+            for (index, file) in files.iter().enumerate() {
+                let Some(started_at) = file.started_at else { continue };
+                if claimed.contains(&index) {
+                    continue;
+                }
+                let since = match bound_modified_at {
+                    None => Some(process.start_time as i64 - START_TIME_SLACK_SECS),
+                    Some(modified_at) if file.starts_with_clear => modified_at.map(|m| m - START_TIME_SLACK_SECS),
+                    Some(_) => None,
+                };
+                if let Some(since) = since.filter(|since| started_at >= *since) {
+                    claims.push(Claim { pid: process.pid, file: index, gap_secs: started_at - since });
+                }
+            }
+        }
+        claims.sort_by_key(|c| c.gap_secs);
+
+        let mut rebound: HashSet<u32> = HashSet::new();
+        for claim in &claims {
+            if claimed.contains(&claim.file) || rebound.contains(&claim.pid) {
+                continue;
+            }
+            let is_rollover = result.contains_key(&claim.pid);
+            let ambiguous = claims.iter().any(|other| {
+                !std::ptr::eq(other, claim)
+                    && (other.file == claim.file || other.pid == claim.pid)
+                    && !rebound.contains(&other.pid)
+                    && !claimed.contains(&other.file)
+                    && other.gap_secs - claim.gap_secs <= AMBIGUITY_WINDOW_SECS
+            });
+            if ambiguous {
+                warn!("Transcript {:?} matches several processes by start time, binding pid={}", files[claim.file].path, claim.pid);
+            }
+            if is_rollover {
+                debug!("pid={} rolled over to {:?} after /clear", claim.pid, files[claim.file].path);
+            }
+
+            claimed.insert(claim.file);
+            rebound.insert(claim.pid);
+            result.insert(claim.pid, TranscriptBinding { path: files[claim.file].path.clone(), ambiguous });
+        }
+
+        // 5: whatever is left, newest transcript first
+        let unbound: Vec<&ProcessSignals> = processes.iter().filter(|p| !result.contains_key(&p.pid)).collect();
+        let mut remaining = (0..files.len()).filter(|i| !claimed.contains(i));
+        let forced = unbound.len() == 1 && files.len() - claimed.len() == 1;
+        for process in unbound {
+            let Some(index) = remaining.next() else {
+                debug!("No transcript left for pid={}", process.pid);
+                continue;
+            };
+            if !forced {
+                warn!("No signal identifies the transcript of pid={}, guessing {:?}", process.pid, files[index].path);
+            }
+            result.insert(process.pid, TranscriptBinding { path: files[index].path.clone(), ambiguous: !forced });
+        }
+
+        for process in processes {
+            if let Some(binding) = result.get(&process.pid) {
+                self.bindings.insert((process.pid, process.start_time), binding.clone());
+            }
+        }
+
+        result
+    }
+
+    /// Forget bindings of processes that are no longer running
+    pub fn retain_live(&mut self, live: &HashSet<(u32, u64)>) {
+        self.bindings.retain(|key, _| live.contains(key));
+    }
+}
+
+/// Bindings for all running Claude processes, kept across polls
+pub static TRANSCRIPT_BINDER: Lazy<Mutex<TranscriptBinder>> = Lazy::new(|| Mutex::new(TranscriptBinder::new()));
+
+/// First-entry details by transcript path. The start of a transcript never changes,
+/// so it is only read until a timestamp is found.
+#[derive(Default)]
+struct TranscriptStarts {
+    starts: HashMap<PathBuf, (i64, bool)>,
+    /// Transcripts looked at since the last `retain_seen_transcripts`
+    seen: HashSet<PathBuf>,
+}
+
+static TRANSCRIPT_START_CACHE: Lazy<Mutex<TranscriptStarts>> = Lazy::new(|| Mutex::new(TranscriptStarts::default()));
+
+/// Read what the binder needs to know about a transcript
+pub fn transcript_info(path: &Path) -> TranscriptInfo {
+    let modified_at = path.metadata()
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+
+    let cached = {
+        let mut cache = TRANSCRIPT_START_CACHE.lock().unwrap();
+        cache.seen.insert(path.to_path_buf());
+        cache.starts.get(path).copied()
+    };
+    let start = cached.or_else(|| {
+        let start = read_transcript_start(path)?;
+        TRANSCRIPT_START_CACHE.lock().unwrap().starts.insert(path.to_path_buf(), start);
+        Some(start)
+    });
+
+    TranscriptInfo {
+        path: path.to_path_buf(),
+        started_at: start.map(|(started_at, _)| started_at),
+        modified_at,
+        starts_with_clear: start.map(|(_, clear)| clear).unwrap_or(false),
+    }
+}
+
+/// Forget the starts of transcripts that weren't looked at since the previous call,
+/// i.e. those no running process could be writing anymore
+pub fn retain_seen_transcripts() {
+    let mut cache = TRANSCRIPT_START_CACHE.lock().unwrap();
+    let seen = std::mem::take(&mut cache.seen);
+    cache.starts.retain(|path, _| seen.contains(path));
+}
+
+/// Find the first timestamp in a transcript and whether it opens with /clear
+fn read_transcript_start(path: &Path) -> Option<(i64, bool)> {
+    let reader = BufReader::new(File::open(path).ok()?);
+    let mut started_at = None;
+    let mut starts_with_clear = false;
+
+    for line in reader.lines().take(10).map_while(Result::ok) {
+        let Ok(msg) = serde_json::from_str::<JsonlMessage>(&line) else {
+            continue;
+        };
+        if started_at.is_none() {
+            started_at = msg.timestamp.as_deref().and_then(parse_timestamp);
+        }
+        let is_clear = msg.message
+            .and_then(|m| m.content)
+            .and_then(|c| c.as_str().map(|s| s.contains("<command-name>/clear</command-name>")))
+            .unwrap_or(false);
+        starts_with_clear |= is_clear;
+    }
+
+    started_at.map(|t| (t, starts_with_clear))
+}
+
+/// Parse an RFC 3339 transcript timestamp into seconds since the epoch
+fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(timestamp).ok().map(|t| t.timestamp())
+}
+
+fn file_stem(path: &Path) -> Option<&str> {
+    path.file_stem().and_then(|s| s.to_str())
+}
+
+/// List the transcripts a process has open. Only Linux exposes this cheaply (via /proc),
+/// elsewhere the binder relies on the other signals.
+#[cfg(target_os = "linux")]
+fn open_jsonl_files(pid: u32) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(format!("/proc/{}/fd", pid)) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|e| std::fs::read_link(e.path()).ok())
+        .filter(|p| p.extension().map(|ext| ext == "jsonl").unwrap_or(false))
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_jsonl_files(_pid: u32) -> Vec<PathBuf> {
+    Vec::new()
+}
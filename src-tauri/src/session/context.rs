@@ -0,0 +1,136 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use super::incremental::TranscriptUpdate;
+use super::model::JsonlMessage;
+
+/// Context window of current Claude models
+pub const DEFAULT_CONTEXT_WINDOW: u64 = 200_000;
+
+/// Context window of the long-context model variants (e.g. "sonnet[1m]")
+pub const LONG_CONTEXT_WINDOW: u64 = 1_000_000;
+
+/// Context usage and compaction history of a transcript
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextStats {
+    /// Tokens sent with the latest assistant request, None until the first
+    /// request after the session started or after the last compaction
+    pub context_tokens: Option<u64>,
+    /// Number of compact_boundary entries (manual /compact and auto-compact)
+    pub compaction_count: usize,
+    /// Input tokens of all requests, including cached input and subagent requests
+    pub total_input_tokens: u64,
+    /// Output tokens of all requests, including subagent requests
+    pub total_output_tokens: u64,
+}
+
+/// Incremental scan state for one session's transcript
+#[derive(Default)]
+struct ContextState {
+    stats: ContextStats,
+    /// Id of the last response counted in the totals; a response with several
+    /// content blocks is written as several entries repeating its usage
+    last_counted_message_id: Option<String>,
+}
+
+/// Context stats by session id, updated from the lines appended since the last poll
+static CONTEXT_CACHE: Lazy<Mutex<HashMap<String, ContextState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Get context stats for a session, updated with what was appended to its transcript
+/// since the previous poll
+pub fn get_context_stats(session_id: &str, update: &TranscriptUpdate) -> ContextStats {
+    let mut cache = CONTEXT_CACHE.lock().unwrap();
+    let state = cache.entry(session_id.to_string()).or_default();
+    if update.from_start {
+        *state = ContextState::default();
+    }
+
+    for msg in update.lines.iter().filter_map(|line| line.msg.as_ref()) {
+        add_token_totals(&mut state.stats, &mut state.last_counted_message_id, msg);
+        apply_message(&mut state.stats, msg);
+    }
+
+    state.stats.clone()
+}
+
+/// Add the usage of an assistant response to the session's token totals, once per response
+fn add_token_totals(stats: &mut ContextStats, last_counted_message_id: &mut Option<String>, msg: &JsonlMessage) {
+    if msg.msg_type.as_deref() != Some("assistant") {
+        return;
+    }
+    let Some(message) = &msg.message else {
+        return;
+    };
+    let Some(usage) = &message.usage else {
+        return;
+    };
+    if message.id.is_some() && message.id == *last_counted_message_id {
+        return;
+    }
+    *last_counted_message_id = message.id.clone();
+
+    stats.total_input_tokens += usage.input_tokens.unwrap_or(0)
+        + usage.cache_read_input_tokens.unwrap_or(0)
+        + usage.cache_creation_input_tokens.unwrap_or(0);
+    stats.total_output_tokens += usage.output_tokens.unwrap_or(0);
+}
+
+/// Update context stats with one transcript entry
+fn apply_message(stats: &mut ContextStats, msg: &JsonlMessage) {
+    // Sidechain (subagent) requests have their own context window
+    if msg.is_sidechain == Some(true) {
+        return;
+    }
+
+    if msg.subtype.as_deref() == Some("compact_boundary") {
+        stats.compaction_count += 1;
+        // Usage from before the compaction no longer reflects the context
+        stats.context_tokens = None;
+        return;
+    }
+
+    if msg.msg_type.as_deref() != Some("assistant") {
+        return;
+    }
+    let Some(message) = &msg.message else {
+        return;
+    };
+    // Synthetic messages (e.g., API errors) report zero usage
+    if message.model.as_deref() == Some("<synthetic>") {
+        return;
+    }
+    if let Some(usage) = &message.usage {
+        stats.context_tokens = Some(
+            usage.input_tokens.unwrap_or(0)
+                + usage.cache_read_input_tokens.unwrap_or(0)
+                + usage.cache_creation_input_tokens.unwrap_or(0),
+        );
+    }
+}
+
+/// Context window size for a model.
+/// Long-context variants are only recognizable by the "[1m]" suffix the CLI accepts
+/// in --model, so usage beyond the default window also implies the long variant.
+pub fn context_window_size(model: Option<&str>, context_tokens: u64) -> u64 {
+    let is_long_context = model
+        .map(|m| m.to_ascii_lowercase().ends_with("[1m]"))
+        .unwrap_or(false);
+
+    if is_long_context || context_tokens > DEFAULT_CONTEXT_WINDOW {
+        LONG_CONTEXT_WINDOW
+    } else {
+        DEFAULT_CONTEXT_WINDOW
+    }
+}
+
+/// Percentage of the model's context window used by `context_tokens`, capped at 100
+pub fn context_usage_percent(context_tokens: u64, model: Option<&str>) -> f32 {
+    let window = context_window_size(model, context_tokens);
+    (context_tokens as f32 / window as f32 * 100.0).min(100.0)
+}
+
+/// Drop context state for sessions that no longer exist
+pub fn cleanup_stale_context_entries(active_session_ids: &HashSet<String>) {
+    CONTEXT_CACHE.lock().unwrap().retain(|id, _| active_session_ids.contains(id));
+}
@@ -0,0 +1,96 @@
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use crate::policy::PolicyActionKind;
+
+use super::model::{AgentType, SessionStatus};
+
+/// Number of events kept in memory for `recent_events`
+const MAX_EVENTS: usize = 1000;
+
+/// What happened to a session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum SessionEventKind {
+    SessionStarted { status: SessionStatus, project_path: String },
+    StatusChanged { from: SessionStatus, to: SessionStatus },
+    SessionEnded,
+    SubagentStarted { active_subagent_count: usize },
+    SubagentFinished { active_subagent_count: usize },
+    /// An audit rule matched a command, web request or edit
+    AuditAlert { rule: String, detail: String },
+    /// A policy rule acted on the session or one of the processes it left behind
+    PolicyAction { rule: String, action: PolicyActionKind, detail: String, dry_run: bool },
+}
+
+/// A change in a session's lifecycle, as observed between two polls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEvent {
+    /// Increasing sequence number, assigned when the event is recorded
+    pub seq: u64,
+    /// When the change happened (RFC 3339)
+    pub at: String,
+    pub session_id: String,
+    pub agent_type: AgentType,
+    pub project_name: String,
+    #[serde(flatten)]
+    pub kind: SessionEventKind,
+}
+
+type Listener = Box<dyn Fn(&SessionEvent) + Send>;
+
+/// Bounded history of recorded events plus the listeners to notify
+struct EventLog {
+    events: VecDeque<SessionEvent>,
+    next_seq: u64,
+    listeners: Vec<Listener>,
+}
+
+static EVENT_LOG: Lazy<Mutex<EventLog>> = Lazy::new(|| Mutex::new(EventLog {
+    events: VecDeque::with_capacity(MAX_EVENTS),
+    next_seq: 1,
+    listeners: Vec::new(),
+}));
+
+/// Register a callback for every recorded event, e.g. to forward events to the frontend
+pub fn add_listener(listener: impl Fn(&SessionEvent) + Send + 'static) {
+    EVENT_LOG.lock().unwrap().listeners.push(Box::new(listener));
+}
+
+/// Assign sequence numbers to events, store them and notify listeners
+pub fn record_events(events: Vec<SessionEvent>) {
+    if events.is_empty() {
+        return;
+    }
+
+    let mut log = EVENT_LOG.lock().unwrap();
+    for mut event in events {
+        event.seq = log.next_seq;
+        log.next_seq += 1;
+        info!("Session event: seq={}, session={}, project={}, {:?}", event.seq, event.session_id, event.project_name, event.kind);
+
+        for listener in &log.listeners {
+            listener(&event);
+        }
+
+        if log.events.len() == MAX_EVENTS {
+            log.events.pop_front();
+        }
+        log.events.push_back(event);
+    }
+}
+
+/// Events recorded after sequence number `since` (all kept events if None), oldest first
+pub fn recent_events(since: Option<u64>) -> Vec<SessionEvent> {
+    let since = since.unwrap_or(0);
+    EVENT_LOG.lock().unwrap()
+        .events
+        .iter()
+        .filter(|e| e.seq > since)
+        .cloned()
+        .collect()
+}
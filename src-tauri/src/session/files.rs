@@ -0,0 +1,223 @@
+use chrono::{DateTime, Duration, Utc};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use super::incremental::TranscriptUpdate;
+use super::model::{JsonlMessage, Session};
+use super::parser::host_file_path;
+
+/// Two sessions editing the same file this close together are reported as a conflict
+pub const EDIT_CONFLICT_WINDOW_SECS: i64 = 10 * 60;
+
+/// Edit timestamps kept per file for conflict detection
+const MAX_RECENT_EDITS: usize = 20;
+
+/// Tools that modify files, with the input field holding the path
+const EDIT_TOOLS: &[(&str, &str)] = &[
+    ("Edit", "file_path"),
+    ("MultiEdit", "file_path"),
+    ("Write", "file_path"),
+    ("NotebookEdit", "notebook_path"),
+];
+
+/// A file a session has edited
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TouchedFile {
+    /// Absolute path of the file
+    pub path: String,
+    /// Number of edit tool calls on the file
+    pub edit_count: usize,
+    /// Timestamp of the latest edit (RFC 3339)
+    pub last_touched_at: String,
+    /// Tool of the latest edit, e.g. "Edit" or "Write"
+    pub last_tool: String,
+    /// Timestamps of the latest edits (RFC 3339), oldest first
+    #[serde(skip)]
+    pub recent_edits: Vec<String>,
+}
+
+/// A file another live session has edited around the same time
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EditConflict {
+    pub path: String,
+    /// Sessions that also edited the file within the conflict window
+    pub other_session_ids: Vec<String>,
+}
+
+/// Incremental scan state for one session's transcript
+#[derive(Default)]
+struct FilesState {
+    files: HashMap<String, TouchedFile>,
+}
+
+/// Touched files by session id, updated from the lines appended since the last poll
+static FILES_CACHE: Lazy<Mutex<HashMap<String, FilesState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Record the edits appended to a session's transcript since the previous poll
+pub fn update_touched_files(session_id: &str, update: &TranscriptUpdate) {
+    let mut cache = FILES_CACHE.lock().unwrap();
+    let state = cache.entry(session_id.to_string()).or_default();
+    if update.from_start {
+        state.files.clear();
+    }
+
+    for msg in update.lines.iter().filter_map(|line| line.msg.as_ref()) {
+        for (tool, path) in extract_edits(msg) {
+            record_edit(&mut state.files, path, tool, msg.timestamp.as_deref().unwrap_or_default());
+        }
+    }
+}
+
+/// Files a session has edited, most recently touched first.
+/// Only sessions seen by the latest poll are known.
+pub fn get_touched_files(session_id: &str) -> Option<Vec<TouchedFile>> {
+    let cache = FILES_CACHE.lock().unwrap();
+    let mut files: Vec<TouchedFile> = cache.get(session_id)?.files.values()
+        .cloned()
+        .map(|mut file| {
+            if let Some(path) = host_file_path(session_id, &file.path) {
+                file.path = path;
+            }
+            file
+        })
+        .collect();
+    files.sort_by(|a, b| b.last_touched_at.cmp(&a.last_touched_at).then_with(|| a.path.cmp(&b.path)));
+    Some(files)
+}
+
+/// Files a session has edited that the host can see, at their host paths
+fn host_touched_files(session_id: &str) -> Option<Vec<TouchedFile>> {
+    let cache = FILES_CACHE.lock().unwrap();
+    let files = cache.get(session_id)?.files.values()
+        .filter_map(|file| Some(TouchedFile { path: host_file_path(session_id, &file.path)?, ..file.clone() }))
+        .collect();
+    Some(files)
+}
+
+/// Find the (tool, absolute path) of every edit tool call in a transcript entry
+fn extract_edits(msg: &JsonlMessage) -> Vec<(&'static str, String)> {
+    if msg.msg_type.as_deref() != Some("assistant") {
+        return Vec::new();
+    }
+    let Some(serde_json::Value::Array(blocks)) = msg.message.as_ref().and_then(|m| m.content.as_ref()) else {
+        return Vec::new();
+    };
+
+    blocks.iter()
+        .filter_map(|b| edited_path(b, msg.cwd.as_deref()))
+        .collect()
+}
+
+/// The tool and absolute path of a content block if it is an edit tool call.
+/// Relative paths are resolved against the entry's cwd.
+pub(crate) fn edited_path(block: &serde_json::Value, cwd: Option<&str>) -> Option<(&'static str, String)> {
+    if block.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+        return None;
+    }
+    let name = block.get("name").and_then(|n| n.as_str())?;
+    let (tool, field) = EDIT_TOOLS.iter().find(|(tool, _)| *tool == name)?;
+    let path = block.get("input").and_then(|i| i.get(*field)).and_then(|p| p.as_str())?;
+    Some((*tool, absolute_path(path, cwd)))
+}
+
+fn absolute_path(path: &str, cwd: Option<&str>) -> String {
+    match cwd {
+        Some(cwd) if !Path::new(path).is_absolute() => Path::new(cwd).join(path).to_string_lossy().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// Count an edit and keep the latest timestamps
+fn record_edit(files: &mut HashMap<String, TouchedFile>, path: String, tool: &str, timestamp: &str) {
+    let file = files.entry(path.clone()).or_insert_with(|| TouchedFile {
+        path,
+        edit_count: 0,
+        last_touched_at: String::new(),
+        last_tool: String::new(),
+        recent_edits: Vec::new(),
+    });
+    file.edit_count += 1;
+    let at = file.recent_edits.partition_point(|t| t.as_str() <= timestamp);
+    file.recent_edits.insert(at, timestamp.to_string());
+    if file.recent_edits.len() > MAX_RECENT_EDITS {
+        file.recent_edits.remove(0);
+    }
+    if timestamp >= file.last_touched_at.as_str() {
+        file.last_touched_at = timestamp.to_string();
+        file.last_tool = tool.to_string();
+    }
+}
+
+/// A session's recent edit times of one file
+type SessionEdits<'a> = (&'a str, Vec<DateTime<Utc>>);
+
+/// Find files edited by more than one of the given sessions within `window` of each other.
+/// Each session's recent edits are compared, not only its latest one.
+/// Paths are absolute, so only sessions sharing a working tree can collide.
+pub fn find_edit_conflicts(sessions: &[(String, Vec<TouchedFile>)], window: Duration) -> HashMap<String, Vec<EditConflict>> {
+    let mut edits_by_path: HashMap<&str, Vec<SessionEdits>> = HashMap::new();
+    for (session_id, files) in sessions {
+        for file in files {
+            let times: Vec<DateTime<Utc>> = file.recent_edits.iter()
+                .filter_map(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc))
+                .collect();
+            if !times.is_empty() {
+                edits_by_path.entry(&file.path).or_default().push((session_id, times));
+            }
+        }
+    }
+
+    let mut conflicts: HashMap<String, Vec<EditConflict>> = HashMap::new();
+    for (path, edits) in edits_by_path.iter().filter(|(_, edits)| edits.len() > 1) {
+        for (session_id, times) in edits {
+            let mut other_session_ids: Vec<String> = edits.iter()
+                .filter(|(other, other_times)| {
+                    other != session_id
+                        && times.iter().any(|at| other_times.iter().any(|other_at| (*other_at - *at).abs() <= window))
+                })
+                .map(|(other, _)| other.to_string())
+                .collect();
+            if other_session_ids.is_empty() {
+                continue;
+            }
+            other_session_ids.sort();
+            conflicts.entry(session_id.to_string()).or_default().push(EditConflict {
+                path: path.to_string(),
+                other_session_ids,
+            });
+        }
+    }
+
+    for list in conflicts.values_mut() {
+        list.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+    conflicts
+}
+
+/// Set `edit_conflicts` on every session from the touched files of all live sessions
+pub fn apply_edit_conflicts(sessions: &mut [Session]) {
+    // Files only inside a container can't be the same file as another session's
+    let touched: Vec<(String, Vec<TouchedFile>)> = sessions.iter()
+        .filter_map(|s| Some((s.id.clone(), host_touched_files(&s.id)?)))
+        .collect();
+    let mut conflicts = find_edit_conflicts(&touched, Duration::seconds(EDIT_CONFLICT_WINDOW_SECS));
+
+    for session in sessions {
+        session.edit_conflicts = conflicts.remove(&session.id).unwrap_or_default();
+        for conflict in &session.edit_conflicts {
+            debug!("Edit conflict: session {} and {:?} both edited {}", session.id, conflict.other_session_ids, conflict.path);
+        }
+    }
+}
+
+/// Drop touched files of sessions that no longer exist
+pub fn cleanup_stale_files_entries(active_session_ids: &HashSet<String>) {
+    FILES_CACHE.lock().unwrap().retain(|id, _| active_session_ids.contains(id));
+}
@@ -0,0 +1,66 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::git::{git_status, repo_name, resolve_repo, retain_git_status};
+use super::model::{Session, SessionGroup, SessionStatus, StatusCounts};
+
+/// Fill in each session's repository, worktree and working-tree status from its project path
+pub fn apply_repo_identity(sessions: &mut [Session]) {
+    let mut worktree_roots: HashSet<PathBuf> = HashSet::new();
+    for session in sessions {
+        // A project only inside a container would resolve to whatever is at its path on the host
+        if session.container.as_ref().is_some_and(|c| !c.project_on_host) {
+            continue;
+        }
+        if let Some(repo) = resolve_repo(&session.project_path) {
+            session.git_status = git_status(&repo);
+            session.repo_id = Some(repo.repo_id);
+            session.worktree = repo.worktree;
+            worktree_roots.insert(repo.worktree_root);
+        }
+    }
+    retain_git_status(&worktree_roots);
+}
+
+/// Group sessions by repository. Sessions outside git are grouped by project path.
+/// Groups are ordered by their first session, so the sort order of `sessions` carries over.
+pub fn group_sessions(sessions: &[Session]) -> Vec<SessionGroup> {
+    let mut groups: Vec<SessionGroup> = Vec::new();
+    let mut index_by_id: HashMap<String, usize> = HashMap::new();
+
+    for session in sessions {
+        let id = session.repo_id.clone().unwrap_or_else(|| session.project_path.clone());
+        let index = *index_by_id.entry(id.clone()).or_insert_with(|| {
+            groups.push(SessionGroup {
+                name: group_name(session),
+                is_repo: session.repo_id.is_some(),
+                id,
+                session_ids: Vec::new(),
+                status_counts: StatusCounts::default(),
+            });
+            groups.len() - 1
+        });
+
+        let group = &mut groups[index];
+        group.session_ids.push(session.id.clone());
+        let counts = &mut group.status_counts;
+        match session.status {
+            SessionStatus::Waiting => counts.waiting += 1,
+            SessionStatus::Processing => counts.processing += 1,
+            SessionStatus::Thinking => counts.thinking += 1,
+            SessionStatus::Compacting => counts.compacting += 1,
+            SessionStatus::Idle => counts.idle += 1,
+            SessionStatus::Paused => counts.paused += 1,
+        }
+    }
+
+    groups
+}
+
+/// Name a group after the repository, falling back to the session's project name
+fn group_name(session: &Session) -> String {
+    match &session.repo_id {
+        Some(repo_id) => repo_name(Path::new(repo_id)),
+        None => session.project_name.clone(),
+    }
+}
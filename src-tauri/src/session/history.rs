@@ -0,0 +1,171 @@
+use log::{debug, trace};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use once_cell::sync::Lazy;
+
+use super::model::{AgentType, HistorySession, JsonlMessage};
+use super::parser::is_subagent_file;
+use super::project_dir::convert_dir_name_to_path;
+use super::title::prompt_from_content;
+
+/// A parsed history entry along with the file state it was parsed from
+struct CachedHistoryEntry {
+    modified: SystemTime,
+    size: u64,
+    session: HistorySession,
+}
+
+/// Cache parsed history entries by transcript path.
+/// History needs a full read of every transcript, so entries are only re-parsed
+/// when the file's modification time or size changes.
+static HISTORY_CACHE: Lazy<Mutex<HashMap<PathBuf, CachedHistoryEntry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// List every Claude Code session transcript under ~/.claude/projects,
+/// including sessions whose process has exited
+pub fn get_claude_history() -> Vec<HistorySession> {
+    let claude_dir = dirs::home_dir()
+        .map(|h| h.join(".claude").join("projects"))
+        .unwrap_or_default();
+
+    let mut sessions = Vec::new();
+    let mut seen_paths = std::collections::HashSet::new();
+
+    let Ok(entries) = fs::read_dir(&claude_dir) else {
+        debug!("Claude projects directory not readable: {:?}", claude_dir);
+        return sessions;
+    };
+
+    for entry in entries.flatten() {
+        let project_dir = entry.path();
+        if !project_dir.is_dir() {
+            continue;
+        }
+
+        let dir_name = project_dir.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        for file in fs::read_dir(&project_dir).into_iter().flatten().flatten() {
+            let path = file.path();
+            let is_jsonl = path.extension().map(|ext| ext == "jsonl").unwrap_or(false);
+            if !is_jsonl || is_subagent_file(&path) {
+                continue;
+            }
+
+            seen_paths.insert(path.clone());
+            if let Some(session) = get_cached_history_entry(&path, &dir_name) {
+                sessions.push(session);
+            }
+        }
+    }
+
+    // Drop cache entries for transcripts that were deleted
+    HISTORY_CACHE.lock().unwrap().retain(|path, _| seen_paths.contains(path));
+
+    debug!("Found {} Claude history sessions", sessions.len());
+    sessions
+}
+
+/// Return the history entry for a transcript, re-parsing only if the file changed
+fn get_cached_history_entry(path: &Path, dir_name: &str) -> Option<HistorySession> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?;
+    let size = metadata.len();
+
+    {
+        let cache = HISTORY_CACHE.lock().unwrap();
+        if let Some(cached) = cache.get(path) {
+            if cached.modified == modified && cached.size == size {
+                return Some(cached.session.clone());
+            }
+        }
+    }
+
+    trace!("Parsing history for {:?}", path);
+    let session = parse_history_file(path, dir_name)?;
+    HISTORY_CACHE.lock().unwrap().insert(path.to_path_buf(), CachedHistoryEntry {
+        modified,
+        size,
+        session: session.clone(),
+    });
+    Some(session)
+}
+
+/// Parse a complete JSONL transcript into a history entry.
+/// Returns None for transcripts without any user or assistant messages
+/// (e.g., files that only hold summaries of other sessions).
+pub fn parse_history_file(jsonl_path: &Path, dir_name: &str) -> Option<HistorySession> {
+    let file = File::open(jsonl_path).ok()?;
+    let size_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let reader = BufReader::new(file);
+
+    let mut cwd: Option<String> = None;
+    let mut git_branch: Option<String> = None;
+    let mut last_timestamp: Option<String> = None;
+    let mut first_prompt: Option<String> = None;
+    let mut title: Option<String> = None;
+    let mut message_count = 0;
+
+    for line in reader.lines().map_while(Result::ok) {
+        let Ok(msg) = serde_json::from_str::<JsonlMessage>(&line) else {
+            continue;
+        };
+
+        if msg.msg_type.as_deref() == Some("summary") {
+            // Later summaries describe more of the conversation - keep the last one
+            if msg.summary.is_some() {
+                title = msg.summary;
+            }
+            continue;
+        }
+
+        if cwd.is_none() {
+            cwd = msg.cwd.filter(|c| c.starts_with('/'));
+        }
+        if let Some(branch) = msg.git_branch.filter(|b| !b.is_empty()) {
+            git_branch = Some(branch);
+        }
+        if msg.timestamp.is_some() {
+            last_timestamp = msg.timestamp;
+        }
+
+        let is_user = msg.msg_type.as_deref() == Some("user");
+        let is_assistant = msg.msg_type.as_deref() == Some("assistant");
+        if !(is_user || is_assistant) || msg.is_meta == Some(true) {
+            continue;
+        }
+
+        let Some(content) = msg.message.as_ref().and_then(|m| m.content.as_ref()) else {
+            continue;
+        };
+        message_count += 1;
+
+        if is_user && first_prompt.is_none() {
+            first_prompt = prompt_from_content(content);
+        }
+    }
+
+    if message_count == 0 {
+        return None;
+    }
+
+    // Claude Code names each transcript after its session id
+    let id = jsonl_path.file_stem()?.to_str()?.to_string();
+
+    Some(HistorySession {
+        id,
+        agent_type: AgentType::Claude,
+        project_path: cwd.unwrap_or_else(|| convert_dir_name_to_path(dir_name)),
+        first_prompt,
+        title,
+        last_activity_at: last_timestamp.unwrap_or_else(|| "Unknown".to_string()),
+        message_count,
+        git_branch,
+        size_bytes,
+    })
+}
@@ -0,0 +1,163 @@
+use log::{debug, trace};
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::model::JsonlMessage;
+
+/// Read position within an append-only transcript.
+///
+/// Transcripts only ever grow, so per-session features that need the whole file
+/// (compaction counts, touched files, ...) are fed only the lines appended since the
+/// previous poll instead of re-reading everything, see `read_session_update`.
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptCursor {
+    /// Byte offset just past the last complete line that was read
+    pub offset: u64,
+    /// Number of complete lines read so far
+    pub line_count: usize,
+    /// Identity of the file that was read, to notice when it is replaced (e.g. by redaction)
+    pub file_id: Option<u64>,
+}
+
+/// A complete line appended to a transcript, with its 1-based line number
+#[derive(Debug, Clone)]
+pub struct AppendedLine {
+    pub line_number: usize,
+    pub text: String,
+}
+
+/// Result of reading a transcript from a cursor
+#[derive(Debug, Default)]
+pub struct AppendedLines {
+    pub lines: Vec<AppendedLine>,
+    /// True if the file shrank or was replaced since the last read and was read again
+    /// from the start. Callers must discard any state derived from earlier reads.
+    pub reset: bool,
+}
+
+/// Read the complete lines appended to `path` since `cursor`, advancing the cursor.
+///
+/// A trailing line without a newline is left for the next call, since the CLI may
+/// still be writing it.
+pub fn read_appended_lines(path: &Path, cursor: &mut TranscriptCursor) -> io::Result<AppendedLines> {
+    let mut file = File::open(path)?;
+    let metadata = file.metadata()?;
+    let size = metadata.len();
+    let file_id = file_id(&metadata);
+
+    let mut result = AppendedLines::default();
+    let replaced = cursor.file_id.is_some() && cursor.file_id != file_id;
+    if size < cursor.offset || replaced {
+        *cursor = TranscriptCursor::default();
+        result.reset = true;
+    }
+    cursor.file_id = file_id;
+    if size == cursor.offset {
+        return Ok(result);
+    }
+
+    file.seek(SeekFrom::Start(cursor.offset))?;
+    let mut reader = BufReader::new(file);
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        let read = reader.read_until(b'\n', &mut buf)?;
+        if read == 0 || buf.last() != Some(&b'\n') {
+            break;
+        }
+
+        cursor.offset += read as u64;
+        cursor.line_count += 1;
+
+        let text = String::from_utf8_lossy(&buf);
+        let text = text.trim_end_matches(['\n', '\r']);
+        if !text.is_empty() {
+            result.lines.push(AppendedLine {
+                line_number: cursor.line_count,
+                text: text.to_string(),
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// A line appended to a session's transcript, parsed once for all per-session features
+#[derive(Debug)]
+pub struct TranscriptLine {
+    pub line_number: usize,
+    pub text: String,
+    /// None for lines that aren't a transcript entry
+    pub(crate) msg: Option<JsonlMessage>,
+}
+
+/// What was appended to a session's transcript since the previous poll
+#[derive(Debug, Default)]
+pub struct TranscriptUpdate {
+    pub lines: Vec<TranscriptLine>,
+    /// The lines start at the beginning of the transcript: it is read for the first time,
+    /// or again because the session moved to another file or the file was replaced.
+    /// Features must then discard what they derived from earlier reads.
+    pub from_start: bool,
+}
+
+/// Where each session's transcript was read up to
+struct SessionCursor {
+    path: PathBuf,
+    cursor: TranscriptCursor,
+}
+
+static SESSION_CURSORS: Lazy<Mutex<HashMap<String, SessionCursor>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Read and parse the lines appended to a session's transcript since the previous call.
+/// This is the only pass over a transcript per poll; context usage, touched files,
+/// secrets, the audit log and the title are all updated from its result.
+pub fn read_session_update(session_id: &str, jsonl_path: &Path) -> TranscriptUpdate {
+    let mut cursors = SESSION_CURSORS.lock().unwrap();
+    let state = cursors.entry(session_id.to_string()).or_insert_with(|| SessionCursor {
+        path: jsonl_path.to_path_buf(),
+        cursor: TranscriptCursor::default(),
+    });
+    if state.path != jsonl_path {
+        debug!("Session {} moved to {:?}, rescanning its transcript", session_id, jsonl_path);
+        state.path = jsonl_path.to_path_buf();
+        state.cursor = TranscriptCursor::default();
+    }
+
+    let was_at_start = state.cursor.offset == 0;
+    let Ok(appended) = read_appended_lines(jsonl_path, &mut state.cursor) else {
+        return TranscriptUpdate::default();
+    };
+    trace!("Read {} new lines of {:?}", appended.lines.len(), jsonl_path);
+    TranscriptUpdate {
+        from_start: was_at_start || appended.reset,
+        lines: appended.lines.into_iter()
+            .map(|line| TranscriptLine {
+                line_number: line.line_number,
+                msg: serde_json::from_str(&line.text).ok(),
+                text: line.text,
+            })
+            .collect(),
+    }
+}
+
+/// Forget where transcripts of sessions that no longer exist were read up to
+pub fn cleanup_stale_session_cursors(active_session_ids: &HashSet<String>) {
+    SESSION_CURSORS.lock().unwrap().retain(|id, _| active_session_ids.contains(id));
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &std::fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &std::fs::Metadata) -> Option<u64> {
+    None
+}
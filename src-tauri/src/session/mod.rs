@@ -1,7 +1,24 @@
-mod model;
+pub mod activity;
+pub mod audit;
+pub mod binding;
+pub mod context;
+pub mod events;
+pub mod files;
+pub mod groups;
+pub mod history;
+pub mod incremental;
+pub(crate) mod model;
 pub mod parser;
+pub mod project_dir;
+pub mod resources;
+pub mod secrets;
+pub(crate) mod shell;
 mod status;
+pub mod title;
+pub mod tracker;
 
-pub use model::{AgentType, Session, SessionStatus, SessionsResponse};
-pub use parser::{parse_session_file, convert_dir_name_to_path, convert_path_to_dir_name, get_sessions, get_sessions_internal, cleanup_stale_status_entries};
+pub use model::{AgentType, HistorySession, ProjectHistory, Session, SessionGroup, SessionStatus, SessionsResponse, StatusCounts};
+pub use parser::{parse_session_file, get_sessions, get_sessions_internal};
+pub use project_dir::{convert_dir_name_to_path, convert_path_to_dir_name};
+pub use tracker::{cleanup_stale_status_entries, track_session_status};
 pub use status::{determine_status, status_sort_priority, has_tool_use, has_tool_result, is_local_slash_command, is_interrupted_request, is_waiting_for_user_input};
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::git::{GitRemote, GitStatus};
+use crate::process::{ChildProcess, ContainerRef, SessionOrigin};
+use super::files::EditConflict;
+
 /// Type of AI coding agent
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -16,15 +20,66 @@ pub struct Session {
     pub agent_type: AgentType,
     pub project_name: String,
     pub project_path: String,
+    /// Latest summary or cleaned first prompt, distinguishes sessions in the same project
+    pub title: Option<String>,
     pub git_branch: Option<String>,
-    pub github_url: Option<String>,
+    /// Common git dir of the repository, shared by all of its worktrees
+    pub repo_id: Option<String>,
+    /// Directory name of the linked worktree, None in the main working tree
+    pub worktree: Option<String>,
+    /// Changes in the working tree, refreshed in the background
+    pub git_status: Option<GitStatus>,
+    /// Web links for the `origin` remote
+    pub remote: Option<GitRemote>,
+    /// Model used by the latest assistant message (or --model flag)
+    pub model: Option<String>,
+    /// Permission mode such as "default", "acceptEdits", "plan" or "bypassPermissions"
+    pub permission_mode: Option<String>,
+    /// Claude Code version that wrote the latest transcript entry
+    pub cli_version: Option<String>,
+    /// Tokens in the context window as of the latest assistant request
+    pub context_tokens: Option<u64>,
+    /// How full the model's context window is (0-100), None until the first request
+    pub context_usage_percent: Option<f32>,
+    /// Number of times the conversation was compacted
+    pub compaction_count: usize,
+    /// Input tokens of all requests so far, including cached input
+    pub total_input_tokens: u64,
+    pub total_output_tokens: u64,
     pub status: SessionStatus,
+    /// When the current status began (RFC 3339)
+    pub status_since: String,
     pub last_message: Option<String>,
     pub last_message_role: Option<String>,
     pub last_activity_at: String,
     pub pid: u32,
     pub cpu_usage: f32,
+    /// Resident memory of the agent process in bytes
+    pub memory: u64,
+    /// When the agent process started (RFC 3339)
+    pub process_started_at: Option<String>,
+    /// CPU of the agent and all processes it started, in percent of one core
+    pub tree_cpu_usage: f32,
+    /// Resident memory of the agent and all processes it started, in bytes
+    pub tree_memory: u64,
+    /// Processes the agent started (builds, test runners, dev servers), parents first
+    pub child_processes: Vec<ChildProcess>,
+    /// Docker, Podman or devcontainer the agent runs in, None on the host
+    pub container: Option<ContainerRef>,
+    /// Terminal, IDE or SDK that launched the agent
+    pub origin: SessionOrigin,
+    /// Remote host the session was collected from over ssh, None for this machine
+    pub host: Option<String>,
     pub active_subagent_count: usize,
+    /// Files another live session edited around the same time as this one
+    pub edit_conflicts: Vec<EditConflict>,
+    /// Ids of the audit rules the session's commands, web requests or edits have matched
+    pub flagged_rules: Vec<String>,
+    /// Number of credentials found in the transcript
+    pub secret_finding_count: usize,
+    /// True if the process could not be tied to this transcript with certainty,
+    /// e.g. several sessions in the same directory without distinguishing signals
+    pub binding_ambiguous: bool,
 }
 
 /// Status of a Claude Code session
@@ -36,6 +91,8 @@ pub enum SessionStatus {
     Thinking,
     Compacting,
     Idle,
+    /// The agent process is stopped (SIGSTOP, Ctrl-Z), by this app or anyone else
+    Paused,
 }
 
 /// Response containing all sessions and counts
@@ -45,6 +102,57 @@ pub struct SessionsResponse {
     pub sessions: Vec<Session>,
     pub total_count: usize,
     pub waiting_count: usize,
+    /// Sessions grouped by repository, most urgent group first
+    pub groups: Vec<SessionGroup>,
+}
+
+/// Number of sessions in each status
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCounts {
+    pub waiting: usize,
+    pub processing: usize,
+    pub thinking: usize,
+    pub compacting: usize,
+    pub idle: usize,
+    pub paused: usize,
+}
+
+/// Sessions of one repository (across its worktrees), or of one directory outside git
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionGroup {
+    /// The repository's common git dir, or the project path for sessions outside git
+    pub id: String,
+    pub name: String,
+    pub is_repo: bool,
+    /// Ids of the sessions in this group, in the same order as `SessionsResponse::sessions`
+    pub session_ids: Vec<String>,
+    pub status_counts: StatusCounts,
+}
+
+/// A past session found on disk, whether or not its agent is still running
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistorySession {
+    pub id: String,
+    pub agent_type: AgentType,
+    pub project_path: String,
+    pub first_prompt: Option<String>,
+    pub title: Option<String>,
+    pub last_activity_at: String,
+    pub message_count: usize,
+    pub git_branch: Option<String>,
+    pub size_bytes: u64,
+}
+
+/// Past sessions of a single project, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectHistory {
+    pub project_name: String,
+    pub project_path: String,
+    pub sessions: Vec<HistorySession>,
 }
 
 /// Internal struct for parsing JSONL messages
@@ -61,6 +169,14 @@ pub(crate) struct JsonlMessage {
     pub subtype: Option<String>,
     #[serde(rename = "isCompactSummary")]
     pub is_compact_summary: Option<bool>,
+    #[serde(rename = "isMeta")]
+    pub is_meta: Option<bool>,
+    #[serde(rename = "isSidechain")]
+    pub is_sidechain: Option<bool>,
+    pub summary: Option<String>,
+    pub version: Option<String>,
+    #[serde(rename = "permissionMode")]
+    pub permission_mode: Option<String>,
     pub message: Option<MessageContent>,
 }
 
@@ -68,5 +184,18 @@ pub(crate) struct JsonlMessage {
 #[derive(Debug, Deserialize)]
 pub(crate) struct MessageContent {
     pub role: Option<String>,
+    pub model: Option<String>,
     pub content: Option<serde_json::Value>,
+    pub usage: Option<TokenUsage>,
+    /// API message id, shared by the entries of one multi-block response
+    pub id: Option<String>,
+}
+
+/// Internal struct for the token usage of an assistant message
+#[derive(Debug, Deserialize)]
+pub(crate) struct TokenUsage {
+    pub input_tokens: Option<u64>,
+    pub cache_read_input_tokens: Option<u64>,
+    pub cache_creation_input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
 }
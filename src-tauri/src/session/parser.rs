@@ -1,34 +1,26 @@
 use log::{debug, info, trace, warn};
-use std::collections::HashMap;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use once_cell::sync::Lazy;
 
 use crate::agent::AgentProcess;
+use crate::git::get_remote;
+use crate::process::container::{container_name, host_mounts, translate_path, MountEntry};
+use crate::process::{parse_claude_args, ContainerInfo, ContainerRef, SessionOrigin};
 use super::model::{AgentType, Session, SessionStatus, SessionsResponse, JsonlMessage};
+use super::binding::{retain_seen_transcripts, transcript_info, ProcessSignals, TranscriptBinder, TranscriptInfo, TRANSCRIPT_BINDER};
+use super::project_dir::{convert_dir_name_to_path, convert_path_to_dir_name};
+use super::context::{context_usage_percent, get_context_stats};
+use super::audit::update_audit;
+use super::incremental::read_session_update;
+use super::files::update_touched_files;
+use super::secrets::update_secret_findings;
+use super::title::get_session_title;
 use super::status::{determine_status, has_tool_use, has_tool_result, is_local_slash_command, is_interrupted_request, is_waiting_for_user_input};
 
-/// Track previous status for each session to detect transitions
-static PREVIOUS_STATUS: Lazy<Mutex<HashMap<String, SessionStatus>>> = Lazy::new(|| Mutex::new(HashMap::new()));
-
-/// Cache git remote URLs by project path — remote URL never changes during app lifetime
-static GIT_URL_CACHE: Lazy<Mutex<HashMap<String, Option<String>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
-
-/// Clean up PREVIOUS_STATUS entries for sessions that no longer exist.
-/// Call this after all agent detectors have run to prevent unbounded memory growth.
-pub fn cleanup_stale_status_entries(active_session_ids: &std::collections::HashSet<String>) {
-    let mut prev_status_map = PREVIOUS_STATUS.lock().unwrap();
-    let before_count = prev_status_map.len();
-    prev_status_map.retain(|id, _| active_session_ids.contains(id));
-    let removed = before_count - prev_status_map.len();
-    if removed > 0 {
-        debug!("Cleaned up {} stale entries from PREVIOUS_STATUS (kept {})", removed, prev_status_map.len());
-    }
-}
-
 /// Extract a preview of content for debugging
 fn get_content_preview(content: &serde_json::Value) -> String {
     match content {
@@ -66,184 +58,72 @@ fn extract_cwd_from_jsonl(jsonl_path: &PathBuf) -> Option<String> {
     None
 }
 
-/// Get GitHub URL from a project's git remote origin (cached)
-fn get_github_url(project_path: &str) -> Option<String> {
-    // Check cache first — avoids spawning a git subprocess on every poll
-    {
-        let cache = GIT_URL_CACHE.lock().unwrap();
-        if let Some(cached) = cache.get(project_path) {
-            return cached.clone();
-        }
-    }
-
-    let result = get_github_url_uncached(project_path);
-
-    // Cache the result (including None) so we don't retry failed lookups
-    {
-        let mut cache = GIT_URL_CACHE.lock().unwrap();
-        cache.insert(project_path.to_string(), result.clone());
-    }
-
-    result
+/// Get all active Claude Code sessions (delegates to agent module)
+pub fn get_sessions() -> SessionsResponse {
+    crate::agent::get_all_sessions()
 }
 
-fn get_github_url_uncached(project_path: &str) -> Option<String> {
-    let output = Command::new("git")
-        .args(["remote", "get-url", "origin"])
-        .current_dir(project_path)
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let remote_url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-    // Convert SSH format to HTTPS
-    // git@github.com:user/repo.git -> https://github.com/user/repo
-    if remote_url.starts_with("git@github.com:") {
-        let path = remote_url
-            .strip_prefix("git@github.com:")?
-            .strip_suffix(".git")
-            .unwrap_or(&remote_url[15..]);
-        return Some(format!("https://github.com/{}", path));
-    }
-
-    // Already HTTPS format
-    // https://github.com/user/repo.git -> https://github.com/user/repo
-    if remote_url.starts_with("https://github.com/") {
-        let url = remote_url
-            .strip_suffix(".git")
-            .unwrap_or(&remote_url);
-        return Some(url.to_string());
-    }
-
-    None
-}
+/// Internal function to get sessions for a specific agent type
+/// Called by agent detectors (ClaudeDetector, OpenCodeDetector, etc.)
+pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType) -> Vec<Session> {
+    info!("=== Getting sessions for {:?} ===", agent_type);
+    debug!("Found {} processes total", processes.len());
 
-/// Convert a file system path like "/Users/ozan/Projects/my-project" to a directory name
-/// This is the reverse of convert_dir_name_to_path
-/// e.g., "/Users/ozan/Projects/my-project/.rsworktree/branch-name" -> "-Users-ozan-Projects-my-project--rsworktree-branch-name"
-pub fn convert_path_to_dir_name(path: &str) -> String {
-    // Remove leading slash and replace path separators with dashes
-    let path = path.strip_prefix('/').unwrap_or(path);
-
-    let mut result = String::from("-");
-    let mut chars = path.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        match c {
-            '/' => {
-                // Check if next char starts a hidden folder (.)
-                if chars.peek() == Some(&'.') {
-                    // Hidden folder: use double dash and skip the dot
-                    result.push('-');
-                    result.push('-');
-                    chars.next(); // skip the dot
-                } else {
-                    result.push('-');
-                }
-            }
-            _ => result.push(c),
+    // Agents in a container write transcripts to the container's home, which
+    // is read through /proc/<pid>/root, so each container is scanned on its own
+    let mut host_processes = Vec::new();
+    let mut containers: HashMap<&str, Vec<&AgentProcess>> = HashMap::new();
+    for process in processes {
+        match &process.container {
+            Some(container) => containers.entry(container.id.as_str()).or_default().push(process),
+            None => host_processes.push(process),
         }
     }
 
-    result
-}
+    // Scan ~/.claude/projects for session files
+    let claude_dir = dirs::home_dir()
+        .map(|h| h.join(".claude").join("projects"))
+        .unwrap_or_default();
+    let mut sessions = scan_projects_dir(&claude_dir, &host_processes, None, &agent_type);
 
-/// Convert a directory name like "-Users-ozan-Projects-ai-image-dashboard" back to a path
-/// The challenge is that both path separators AND project names can contain dashes
-/// We handle this by recognizing that the path structure is predictable:
-/// /Users/<username>/Projects/<project-name> or /Users/<username>/.../<project-name>
-///
-/// Special case: Double dashes (--) indicate a hidden folder (starting with .)
-/// followed by subfolders separated by single dashes
-/// e.g., "ai-image-dashboard--rsworktree-analytics" becomes "ai-image-dashboard/.rsworktree/analytics"
-pub fn convert_dir_name_to_path(dir_name: &str) -> String {
-    // Remove leading dash if present
-    let name = dir_name.strip_prefix('-').unwrap_or(dir_name);
-
-    // Split by dash
-    let parts: Vec<&str> = name.split('-').collect();
-
-    if parts.is_empty() {
-        return String::new();
+    if !containers.is_empty() {
+        let host_mounts = host_mounts();
+        for group in containers.values() {
+            let Some(container) = group[0].container.as_ref() else {
+                continue;
+            };
+            let Some(home) = &container.home else {
+                warn!("No home directory for container {}, skipping {} processes", container.id, group.len());
+                continue;
+            };
+            let claude_dir = container.host_view(&home.join(".claude").join("projects"));
+            let mut found = scan_projects_dir(&claude_dir, group, Some(container), &agent_type);
+            tag_container_sessions(&mut found, container, &host_mounts);
+            sessions.extend(found);
+        }
     }
 
-    // Find "Projects" or "UnityProjects" index - everything after that is the project name
-    let projects_idx = parts.iter().position(|&p| p == "Projects" || p == "UnityProjects");
-
-    if let Some(idx) = projects_idx {
-        // Path components are before and including "Projects"
-        let path_parts = &parts[..=idx];
-        // Project name is everything after "Projects"
-        let project_parts = &parts[idx + 1..];
-
-        let mut path = String::from("/");
-        path.push_str(&path_parts.join("/"));
-
-        if !project_parts.is_empty() {
-            path.push('/');
-            // Handle the project path with potential hidden folders
-            // Double dash (empty string between dashes when split) indicates hidden folder
-            // After a hidden folder marker, subsequent parts are subfolders
-            let mut in_hidden_folder = false;
-            let mut segments: Vec<String> = Vec::new();
-            let mut current_segment = String::new();
-
-            for part in project_parts {
-                if part.is_empty() {
-                    // Empty part means we hit a double dash - start hidden folder
-                    if !current_segment.is_empty() {
-                        segments.push(current_segment);
-                        current_segment = String::new();
-                    }
-                    in_hidden_folder = true;
-                } else if in_hidden_folder {
-                    // After double dash, each part is a subfolder
-                    // First part after -- gets the dot prefix
-                    if current_segment.is_empty() {
-                        current_segment = format!(".{}", part);
-                    } else {
-                        segments.push(current_segment);
-                        current_segment = part.to_string();
-                    }
-                } else {
-                    // Normal project name part - join with dashes
-                    if current_segment.is_empty() {
-                        current_segment = part.to_string();
-                    } else {
-                        current_segment.push('-');
-                        current_segment.push_str(part);
-                    }
-                }
-            }
-            if !current_segment.is_empty() {
-                segments.push(current_segment);
-            }
+    // Forget transcript bindings of processes that exited, and transcripts this poll didn't look at
+    let live: HashSet<(u32, u64)> = processes.iter().map(|p| (p.pid, p.start_time)).collect();
+    TRANSCRIPT_BINDER.lock().unwrap().retain_live(&live);
+    retain_seen_transcripts();
 
-            path.push_str(&segments.join("/"));
-        }
-
-        path
-    } else {
-        // Fallback: just replace dashes with slashes (old behavior)
-        format!("/{}", name.replace('-', "/"))
-    }
-}
+    info!(
+        "=== Session scan complete for {:?}: {} total ===",
+        agent_type, sessions.len()
+    );
 
-/// Get all active Claude Code sessions (delegates to agent module)
-pub fn get_sessions() -> SessionsResponse {
-    crate::agent::get_all_sessions()
+    sessions
 }
 
-/// Internal function to get sessions for a specific agent type
-/// Called by agent detectors (ClaudeDetector, OpenCodeDetector, etc.)
-pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType) -> Vec<Session> {
-    info!("=== Getting sessions for {:?} ===", agent_type);
-    debug!("Found {} processes total", processes.len());
-
+/// Bind processes to the transcripts under one Claude projects directory.
+/// `container` is set when the directory and the processes' paths are inside a container.
+fn scan_projects_dir(
+    claude_dir: &Path,
+    processes: &[&AgentProcess],
+    container: Option<&ContainerInfo>,
+    agent_type: &AgentType,
+) -> Vec<Session> {
     let mut sessions = Vec::new();
 
     // Build a map of cwd -> list of processes (multiple sessions can run in same folder)
@@ -251,7 +131,7 @@ pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType)
     // Pre-compute expected project directory names from process CWDs.
     // This lets us skip scanning directories that can't match any running process.
     let mut expected_dir_names: std::collections::HashSet<String> = std::collections::HashSet::new();
-    for process in processes {
+    for &process in processes {
         if let Some(cwd) = &process.cwd {
             let cwd_str = cwd.to_string_lossy().to_string();
             debug!("Mapping process pid={} to cwd={}", process.pid, cwd_str);
@@ -262,11 +142,6 @@ pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType)
         }
     }
 
-    // Scan ~/.claude/projects for session files
-    let claude_dir = dirs::home_dir()
-        .map(|h| h.join(".claude").join("projects"))
-        .unwrap_or_default();
-
     debug!("Claude projects directory: {:?}", claude_dir);
 
     if !claude_dir.exists() {
@@ -275,7 +150,7 @@ pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType)
     }
 
     // For each project directory
-    if let Ok(entries) = fs::read_dir(&claude_dir) {
+    if let Ok(entries) = fs::read_dir(claude_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
             if !path.is_dir() {
@@ -327,51 +202,125 @@ pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType)
                 debug!("  cwd {} -> {} processes, {} files",
                        project_path, matching_processes.len(), files_for_cwd.len());
 
-                // Match processes to JSONL files
-                for (index, process) in matching_processes.iter().enumerate() {
-                    debug!("Matching process pid={} to JSONL file index {}", process.pid, index);
-                    if let Some(session) = find_session_for_process(files_for_cwd, &path, project_path, process, index, agent_type.clone()) {
-                    // Track status transitions
-                    let mut prev_status_map = PREVIOUS_STATUS.lock().unwrap();
-                    let prev_status = prev_status_map.get(&session.id).cloned();
-
-                    // Log status transition if it changed
-                    if let Some(prev) = &prev_status {
-                        if *prev != session.status {
-                            warn!(
-                                "STATUS TRANSITION: project={}, {:?} -> {:?}, cpu={:.1}%, file_age=?, last_msg_role={:?}",
-                                session.project_name, prev, session.status, session.cpu_usage, session.last_message_role
-                            );
+                // Bind each process to the transcript it is writing
+                let signals: Vec<ProcessSignals> = matching_processes.iter()
+                    .map(|p| {
+                        let mut signals = ProcessSignals::from_process(p);
+                        // Open files are listed as the container sees them
+                        if let Some(container) = container {
+                            signals.open_files = signals.open_files.iter().map(|f| container.host_view(f)).collect();
                         }
+                        signals
+                    })
+                    .collect();
+                let transcripts: Vec<TranscriptInfo> = files_for_cwd.iter()
+                    .map(|f| transcript_info(f))
+                    .collect();
+                let bindings = TRANSCRIPT_BINDER.lock().unwrap().bind(&signals, &transcripts);
+
+                for process in matching_processes {
+                    let Some(binding) = bindings.get(&process.pid) else {
+                        warn!("No transcript for process pid={} in project {}", process.pid, project_path);
+                        continue;
+                    };
+                    debug!("Bound process pid={} to {:?} (ambiguous={})", process.pid, binding.path, binding.ambiguous);
+                    if let Some(mut session) = find_session_for_process(&binding.path, &path, project_path, process, agent_type.clone()) {
+                        session.binding_ambiguous = binding.ambiguous;
+
+                        info!(
+                            "Session created: id={}, project={}, status={:?}, pid={}, cpu={:.1}%",
+                            session.id, session.project_name, session.status, session.pid, session.cpu_usage
+                        );
+                        sessions.push(session);
+                    } else {
+                        warn!("Failed to create session for process pid={} in project {}", process.pid, project_path);
                     }
-
-                    // Update stored status
-                    prev_status_map.insert(session.id.clone(), session.status.clone());
-                    drop(prev_status_map);
-
-                    info!(
-                        "Session created: id={}, project={}, status={:?}, pid={}, cpu={:.1}%",
-                        session.id, session.project_name, session.status, session.pid, session.cpu_usage
-                    );
-                    sessions.push(session);
-                } else {
-                    warn!("Failed to create session for process pid={} in project {}", process.pid, project_path);
                 }
             }
-            }
         }
     }
 
-    info!(
-        "=== Session scan complete for {:?}: {} total ===",
-        agent_type, sessions.len()
-    );
-
     sessions
 }
 
+/// Whether one of `processes` is writing a transcript, bound the same way live
+/// sessions are. Unlike the session list this counts every process passed in,
+/// including those hidden by their origin or left out as orphans.
+pub fn is_transcript_in_use(jsonl_path: &Path, processes: &[AgentProcess]) -> bool {
+    let Some(project_dir) = jsonl_path.parent().map(Path::to_path_buf) else {
+        return false;
+    };
+    let dir_name = project_dir.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let cwd_of = |file: &PathBuf| extract_cwd_from_jsonl(file).unwrap_or_else(|| convert_dir_name_to_path(dir_name));
+    let project_path = cwd_of(&jsonl_path.to_path_buf());
+
+    // Agents in containers write to the container's home, not to this directory
+    let signals: Vec<ProcessSignals> = processes.iter()
+        .filter(|p| p.container.is_none())
+        .filter(|p| p.cwd.as_ref().is_some_and(|cwd| cwd.to_string_lossy() == project_path))
+        .map(ProcessSignals::from_process)
+        .collect();
+    if signals.is_empty() {
+        return false;
+    }
+
+    let transcripts: Vec<TranscriptInfo> = get_recently_active_jsonl_files(&project_dir, 100).iter()
+        .filter(|f| cwd_of(f) == project_path)
+        .map(|f| transcript_info(f))
+        .collect();
+    // A binder of its own, so the bindings of listed sessions aren't disturbed
+    TranscriptBinder::new()
+        .bind(&signals, &transcripts)
+        .values()
+        .any(|binding| binding.path == jsonl_path)
+}
+
+/// Mounts of a container and of the host, see `translate_path`
+type Mounts = (Vec<MountEntry>, Vec<MountEntry>);
+
+/// Mounts of each containerised session, for translating the file paths in its transcript
+static CONTAINER_MOUNTS: Lazy<Mutex<HashMap<String, Mounts>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Tag the sessions of an agent in a container, and show bind-mounted projects
+/// at their host path so git status and links work from here
+fn tag_container_sessions(sessions: &mut [Session], container: &ContainerInfo, host_mounts: &[MountEntry]) {
+    let name = container_name(&container.id, container.runtime.as_deref());
+    let mut mounts = CONTAINER_MOUNTS.lock().unwrap();
+    for session in sessions {
+        let host_path = translate_path(Path::new(&session.project_path), &container.mounts, host_mounts);
+        session.container = Some(ContainerRef {
+            id: container.id.clone(),
+            runtime: container.runtime.clone(),
+            name: name.clone(),
+            project_on_host: host_path.is_some(),
+        });
+        // A project only inside the container gets no remote: whatever is at its path on the host is unrelated
+        if let Some(host_path) = host_path {
+            debug!("Project {} of session {} is {:?} on the host", session.project_path, session.id, host_path);
+            session.project_path = host_path.to_string_lossy().to_string();
+            session.remote = get_remote(&session.project_path, session.git_branch.as_deref());
+        }
+        mounts.insert(session.id.clone(), (container.mounts.clone(), host_mounts.to_vec()));
+    }
+}
+
+/// A file path from a session's transcript as the host sees it. Paths of host
+/// sessions are unchanged; None for paths that only exist inside the session's container.
+pub fn host_file_path(session_id: &str, path: &str) -> Option<String> {
+    let mounts = CONTAINER_MOUNTS.lock().unwrap();
+    let Some((container_mounts, host_mounts)) = mounts.get(session_id) else {
+        return Some(path.to_string());
+    };
+    translate_path(Path::new(path), container_mounts, host_mounts).map(|p| p.to_string_lossy().to_string())
+}
+
+/// Drop the mounts of sessions that no longer exist
+pub fn cleanup_stale_container_mounts(active_session_ids: &HashSet<String>) {
+    CONTAINER_MOUNTS.lock().unwrap().retain(|id, _| active_session_ids.contains(id));
+}
+
 /// Check if a JSONL file is a subagent file (named agent-*.jsonl)
-fn is_subagent_file(path: &PathBuf) -> bool {
+pub(crate) fn is_subagent_file(path: &PathBuf) -> bool {
     path.file_name()
         .and_then(|n| n.to_str())
         .map(|name| name.starts_with("agent-") && name.ends_with(".jsonl"))
@@ -457,18 +406,37 @@ fn get_recently_active_jsonl_files(project_dir: &PathBuf, _expected_count: usize
         .collect()
 }
 
-/// Find a session for a specific process from available JSONL files
+/// Create the session for a process from the transcript it is bound to
 fn find_session_for_process(
-    jsonl_files: &[PathBuf],
+    jsonl_path: &PathBuf,
     project_dir: &PathBuf,
     project_path: &str,
     process: &AgentProcess,
-    index: usize,
     agent_type: AgentType,
 ) -> Option<Session> {
-    let jsonl_path = jsonl_files.get(index)?;
     let mut session = parse_session_file(jsonl_path, project_path, process.pid, process.cpu_usage, agent_type)?;
 
+    // Fall back to command line flags when the transcript doesn't record them yet
+    let cli_args = parse_claude_args(&process.cmd);
+    if session.permission_mode.is_none() {
+        session.permission_mode = cli_args.permission_mode;
+    }
+
+    // The transcript records the API model id, which doesn't say whether the long
+    // context variant is in use - only the --model flag ("sonnet[1m]") does
+    if let (Some(tokens), Some(cli_model)) = (session.context_tokens, cli_args.model.as_deref()) {
+        session.context_usage_percent = Some(context_usage_percent(tokens, Some(cli_model)));
+    }
+    if session.model.is_none() {
+        session.model = cli_args.model;
+    }
+
+    // Web links for the origin remote, pointing at the session's branch. A container's
+    // paths mean nothing here until `tag_container_sessions` translates them.
+    if process.container.is_none() {
+        session.remote = get_remote(project_path, session.git_branch.as_deref());
+    }
+
     // Count active subagents for this session
     session.active_subagent_count = count_active_subagents(project_dir, &session.id);
 
@@ -521,6 +489,9 @@ pub fn parse_session_file(
     let mut last_is_user_input_tool = false;
     let mut found_status_info = false;
     let mut is_compacting = false;
+    let mut model = None;
+    let mut permission_mode = None;
+    let mut cli_version = None;
 
     // Read last N lines for efficiency
     // Must be large enough to cover long stretches of progress entries during tool execution
@@ -552,6 +523,18 @@ pub fn parse_session_file(
             if last_timestamp.is_none() {
                 last_timestamp = msg.timestamp;
             }
+            if cli_version.is_none() {
+                cli_version = msg.version;
+            }
+            if permission_mode.is_none() {
+                permission_mode = msg.permission_mode;
+            }
+            if model.is_none() {
+                // Synthetic messages (e.g., API errors) are written by the CLI, not a model
+                model = msg.message.as_ref()
+                    .and_then(|m| m.model.clone())
+                    .filter(|m| m != "<synthetic>");
+            }
 
             // Detect compaction: if we see compact_boundary before any content message
             // or isCompactSummary, the session is currently compacting.
@@ -598,7 +581,8 @@ pub fn parse_session_file(
                 }
             }
 
-            if session_id.is_some() && found_status_info {
+            if session_id.is_some() && found_status_info
+                && model.is_some() && permission_mode.is_some() && cli_version.is_some() {
                 break;
             }
         }
@@ -669,22 +653,61 @@ pub fn parse_session_file(
         }
     });
 
-    // Get GitHub URL from git remote
-    let github_url = get_github_url(project_path);
+    let last_activity_at = last_timestamp.unwrap_or_else(|| "Unknown".to_string());
 
-    Some(Session {
+    // The title, context usage, touched files, secrets and the audit need the whole
+    // transcript, which is read once per poll and only from where the last poll stopped
+    let update = read_session_update(&session_id, jsonl_path);
+    let title = get_session_title(&session_id, &update);
+    let context = get_context_stats(&session_id, &update);
+    let context_usage = context.context_tokens
+        .map(|tokens| context_usage_percent(tokens, model.as_deref()));
+    update_touched_files(&session_id, &update);
+    let secret_finding_count = update_secret_findings(&session_id, &update);
+
+    let mut session = Session {
         id: session_id,
         agent_type,
         project_name,
         project_path: project_path.to_string(),
+        title,
         git_branch,
-        github_url,
+        repo_id: None, // Set by get_all_sessions
+        worktree: None,
+        git_status: None,
+        remote: None,
+        model,
+        permission_mode,
+        cli_version,
+        context_tokens: context.context_tokens,
+        context_usage_percent: context_usage,
+        compaction_count: context.compaction_count,
+        total_input_tokens: context.total_input_tokens,
+        total_output_tokens: context.total_output_tokens,
         status,
         last_message,
         last_message_role: last_role,
-        last_activity_at: last_timestamp.unwrap_or_else(|| "Unknown".to_string()),
+        status_since: last_activity_at.clone(), // Set by the status tracker
+        last_activity_at,
         pid,
         cpu_usage,
+        memory: 0, // Set by get_all_sessions, with the other process fields below
+        process_started_at: None,
+        tree_cpu_usage: cpu_usage,
+        tree_memory: 0,
+        child_processes: Vec::new(),
+        container: None,
+        host: None,
+        origin: SessionOrigin::Terminal,
         active_subagent_count: 0, // Set by find_session_for_process
-    })
+        edit_conflicts: Vec::new(), // Set by get_all_sessions
+        flagged_rules: Vec::new(),
+        secret_finding_count,
+        binding_ambiguous: false, // Set by get_sessions_internal
+    };
+
+    // Audit commands and web requests appended since the last poll, alerting on rule matches
+    session.flagged_rules = update_audit(&session, &update);
+
+    Some(session)
 }
@@ -0,0 +1,179 @@
+use log::trace;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Convert a file system path like "/Users/ozan/Projects/my-project" to the directory
+/// name Claude Code stores its transcripts under in ~/.claude/projects.
+///
+/// Claude Code replaces every character that isn't an ASCII letter or digit with `-`.
+/// It does so per UTF-16 code unit, so characters outside the BMP become two dashes.
+/// e.g., "/Users/ozan/Projects/my-project/.rsworktree/branch-name" -> "-Users-ozan-Projects-my-project--rsworktree-branch-name"
+pub fn convert_path_to_dir_name(path: &str) -> String {
+    path.encode_utf16()
+        .map(|unit| match u8::try_from(unit) {
+            Ok(byte) if byte.is_ascii_alphanumeric() => byte as char,
+            _ => '-',
+        })
+        .collect()
+}
+
+/// Cache decoded paths by directory name — a project's transcripts stay under the same name,
+/// so the file system is only walked the first time it is found. Guesses aren't cached, so a
+/// directory that shows up later is still found.
+static DECODED_PATH_CACHE: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Convert a directory name like "-Users-ozan-Projects-ai-image-dashboard" back to a path.
+///
+/// The encoding is lossy (`/`, `.`, `_`, `-` and spaces all become `-`), so the name is
+/// decoded by walking the file system for a directory that encodes to it. If no such
+/// directory exists anymore, the path is guessed with [`guess_path_from_dir_name`].
+pub fn convert_dir_name_to_path(dir_name: &str) -> String {
+    if let Some(path) = DECODED_PATH_CACHE.lock().unwrap().get(dir_name) {
+        return path.clone();
+    }
+
+    match find_path_for_dir_name(dir_name, Path::new("/")) {
+        Some(path) => {
+            let path = path.to_string_lossy().to_string();
+            DECODED_PATH_CACHE.lock().unwrap().insert(dir_name.to_string(), path.clone());
+            path
+        }
+        None => guess_path_from_dir_name(dir_name),
+    }
+}
+
+/// Find an existing directory under `root` (itself included in the encoding) whose
+/// encoded path equals `dir_name`.
+///
+/// Only directory entries whose encoded name is a prefix of the remaining encoded
+/// string are descended into, so this reads one directory per path component in the
+/// common case. When several directories encode the same way, the first in sorted
+/// order wins.
+pub fn find_path_for_dir_name(dir_name: &str, root: &Path) -> Option<PathBuf> {
+    let root_encoded = convert_path_to_dir_name(&root.to_string_lossy());
+    let mut remaining = dir_name.strip_prefix(root_encoded.as_str())?;
+
+    // Past the root, each component is preceded by the `-` its separator encodes to
+    if root != Path::new("/") {
+        if remaining.is_empty() {
+            return Some(root.to_path_buf());
+        }
+        remaining = remaining.strip_prefix('-')?;
+    }
+
+    find_descendant(root, remaining)
+}
+
+/// Depth-first search for the directory below `dir` that encodes to `remaining`
+fn find_descendant(dir: &Path, remaining: &str) -> Option<PathBuf> {
+    let mut entries: Vec<(String, PathBuf)> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_str()?.to_string();
+            let encoded = convert_path_to_dir_name(&name);
+            remaining.starts_with(encoded.as_str()).then(|| (encoded, e.path()))
+        })
+        .filter(|(_, path)| path.is_dir())
+        .collect();
+    entries.sort_by(|a, b| a.1.cmp(&b.1));
+
+    for (encoded, path) in entries {
+        let rest = &remaining[encoded.len()..];
+        if rest.is_empty() {
+            return Some(path);
+        }
+        if let Some(rest) = rest.strip_prefix('-') {
+            trace!("Decoding project dir: descending into {:?}", path);
+            if let Some(found) = find_descendant(&path, rest) {
+                return Some(found);
+            }
+        }
+    }
+
+    None
+}
+
+/// Guess the path of a directory name without checking the file system.
+/// Used for projects whose directory no longer exists.
+///
+/// The path structure is assumed to be /Users/<username>/Projects/<project-name>:
+/// dashes after a "Projects" or "UnityProjects" segment are kept as part of the
+/// project name. Double dashes (--) indicate a hidden folder (starting with .)
+/// followed by subfolders separated by single dashes,
+/// e.g., "ai-image-dashboard--rsworktree-analytics" becomes "ai-image-dashboard/.rsworktree/analytics"
+pub fn guess_path_from_dir_name(dir_name: &str) -> String {
+    // Remove leading dash if present
+    let name = dir_name.strip_prefix('-').unwrap_or(dir_name);
+
+    // Split by dash
+    let parts: Vec<&str> = name.split('-').collect();
+
+    if parts.is_empty() {
+        return String::new();
+    }
+
+    // Find "Projects" or "UnityProjects" index - everything after that is the project name
+    let projects_idx = parts.iter().position(|&p| p == "Projects" || p == "UnityProjects");
+
+    if let Some(idx) = projects_idx {
+        // Path components are before and including "Projects"
+        let path_parts = &parts[..=idx];
+        // Project name is everything after "Projects"
+        let project_parts = &parts[idx + 1..];
+
+        let mut path = String::from("/");
+        path.push_str(&path_parts.join("/"));
+
+        if !project_parts.is_empty() {
+            path.push('/');
+            // Handle the project path with potential hidden folders
+            // Double dash (empty string between dashes when split) indicates hidden folder
+            // After a hidden folder marker, subsequent parts are subfolders
+            let mut in_hidden_folder = false;
+            let mut segments: Vec<String> = Vec::new();
+            let mut current_segment = String::new();
+
+            for part in project_parts {
+                if part.is_empty() {
+                    // Empty part means we hit a double dash - start hidden folder
+                    if !current_segment.is_empty() {
+                        segments.push(current_segment);
+                        current_segment = String::new();
+                    }
+                    in_hidden_folder = true;
+                } else if in_hidden_folder {
+                    // After double dash, each part is a subfolder
+                    // First part after -- gets the dot prefix
+                    if current_segment.is_empty() {
+                        current_segment = format!(".{}", part);
+                    } else {
+                        segments.push(current_segment);
+                        current_segment = part.to_string();
+                    }
+                } else {
+                    // Normal project name part - join with dashes
+                    if current_segment.is_empty() {
+                        current_segment = part.to_string();
+                    } else {
+                        current_segment.push('-');
+                        current_segment.push_str(part);
+                    }
+                }
+            }
+            if !current_segment.is_empty() {
+                segments.push(current_segment);
+            }
+
+            path.push_str(&segments.join("/"));
+        }
+
+        path
+    } else {
+        // Fallback: just replace dashes with slashes (old behavior)
+        format!("/{}", name.replace('-', "/"))
+    }
+}
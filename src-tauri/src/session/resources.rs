@@ -0,0 +1,74 @@
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+
+use super::model::Session;
+
+/// How far back the resource history of a session goes
+pub const RESOURCE_HISTORY_WINDOW_SECS: i64 = 10 * 60;
+
+/// Polls closer together than this are not sampled, keeping at most 120 samples
+const MIN_SAMPLE_INTERVAL_SECS: i64 = 5;
+
+/// CPU and memory of an agent and the processes it started, at one poll
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSample {
+    /// RFC 3339
+    pub at: String,
+    /// CPU of the whole process tree in percent of one core
+    pub cpu_usage: f32,
+    /// Resident memory of the whole process tree in bytes
+    pub memory: u64,
+}
+
+/// Rolling samples of one session
+#[derive(Debug, Default)]
+pub struct ResourceHistory {
+    samples: VecDeque<(DateTime<Utc>, ResourceSample)>,
+}
+
+impl ResourceHistory {
+    pub fn record(&mut self, now: DateTime<Utc>, cpu_usage: f32, memory: u64) {
+        if self.samples.back().is_some_and(|(at, _)| now - *at < Duration::seconds(MIN_SAMPLE_INTERVAL_SECS)) {
+            return;
+        }
+        let at = now.to_rfc3339_opts(SecondsFormat::Secs, true);
+        self.samples.push_back((now, ResourceSample { at, cpu_usage, memory }));
+        let cutoff = now - Duration::seconds(RESOURCE_HISTORY_WINDOW_SECS);
+        while self.samples.front().is_some_and(|(at, _)| *at < cutoff) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Samples oldest first
+    pub fn samples(&self) -> Vec<ResourceSample> {
+        self.samples.iter().map(|(_, sample)| sample.clone()).collect()
+    }
+}
+
+static RESOURCE_HISTORY: Lazy<Mutex<HashMap<String, ResourceHistory>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Add the sessions' current tree CPU and memory to their history
+pub fn record_resource_samples(sessions: &[Session], now: DateTime<Utc>) {
+    let mut history = RESOURCE_HISTORY.lock().unwrap();
+    for session in sessions {
+        history.entry(session.id.clone())
+            .or_default()
+            .record(now, session.tree_cpu_usage, session.tree_memory);
+    }
+}
+
+/// Samples of the last ten minutes, oldest first, None if the session isn't live
+pub fn get_resource_history(session_id: &str) -> Option<Vec<ResourceSample>> {
+    RESOURCE_HISTORY.lock().unwrap()
+        .get(session_id)
+        .map(ResourceHistory::samples)
+}
+
+/// Drop the history of sessions that no longer exist
+pub fn cleanup_stale_resource_entries(active_session_ids: &HashSet<String>) {
+    RESOURCE_HISTORY.lock().unwrap().retain(|id, _| active_session_ids.contains(id));
+}
@@ -0,0 +1,347 @@
+use chrono::Utc;
+use log::{info, warn};
+use regex::{Regex, RegexSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use super::incremental::TranscriptUpdate;
+use super::parser::is_transcript_in_use;
+use crate::agent::AgentProcess;
+use crate::export::claude::find_claude_transcript;
+
+/// Findings kept per session; the oldest are dropped first
+const MAX_SECRET_FINDINGS: usize = 500;
+
+/// Generic `token = ...` assignments need values at least this long and this random
+const MIN_GENERIC_SECRET_LEN: usize = 16;
+const MIN_GENERIC_SECRET_ENTROPY: f64 = 3.5;
+
+/// A credential format to look for
+struct SecretPattern {
+    kind: &'static str,
+    /// Matches the secret, or a named `secret` group within the match
+    regex: &'static str,
+    /// Only count matches at least this random, for formats that also fit ordinary text
+    min_entropy: Option<f64>,
+}
+
+/// Patterns in priority order; where matches overlap, the earlier pattern wins.
+/// Transcripts are JSON, so quotes may be escaped (`\"`) and newlines inside
+/// private key blocks appear as `\n`.
+const SECRET_PATTERNS: &[SecretPattern] = &[
+    SecretPattern {
+        kind: "private-key",
+        regex: r"-----BEGIN[A-Z ]*PRIVATE KEY-----(?:(?:\\n|\r?\n)[A-Za-z\-]+: [^\\\r\n]*)*(?:[A-Za-z0-9+/=\r\n]|\\[nr])*(?:-----END[A-Z ]*PRIVATE KEY-----)?",
+        min_entropy: None,
+    },
+    SecretPattern { kind: "aws-access-key", regex: r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b", min_entropy: None },
+    SecretPattern {
+        kind: "aws-secret-key",
+        regex: r#"(?i)aws_?secret_?access_?key\\?["']?\s*[:=]\s*\\?["']?(?P<secret>[A-Za-z0-9/+=]{40})"#,
+        min_entropy: None,
+    },
+    SecretPattern { kind: "gcp-api-key", regex: r"\bAIza[0-9A-Za-z_\-]{35}", min_entropy: None },
+    SecretPattern { kind: "github-token", regex: r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})", min_entropy: None },
+    SecretPattern { kind: "gitlab-token", regex: r"\bglpat-[A-Za-z0-9_\-]{20,}", min_entropy: None },
+    SecretPattern { kind: "slack-token", regex: r"\bxox[abposr]-[A-Za-z0-9\-]{10,}", min_entropy: None },
+    SecretPattern { kind: "anthropic-key", regex: r"\bsk-ant-[A-Za-z0-9_\-]{20,}", min_entropy: None },
+    SecretPattern { kind: "openai-key", regex: r"\bsk-(?:proj-)?[A-Za-z0-9_\-]{20,}", min_entropy: Some(MIN_GENERIC_SECRET_ENTROPY) },
+    SecretPattern {
+        kind: "generic-secret",
+        regex: r#"(?i)[A-Za-z0-9_.\-]*(?:secret|token|passwd|password|api_?key|access_?key|auth_?key|credential)[A-Za-z0-9_\-]*\\?["']?\s*[:=]\s*\\?["']?(?P<secret>[A-Za-z0-9+/=_\-]{16,})"#,
+        min_entropy: Some(MIN_GENERIC_SECRET_ENTROPY),
+    },
+];
+
+static SECRET_SET: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new(SECRET_PATTERNS.iter().map(|p| p.regex)).expect("secret patterns are valid")
+});
+
+static SECRET_REGEXES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    SECRET_PATTERNS.iter().map(|p| Regex::new(p.regex).expect("secret pattern is valid")).collect()
+});
+
+/// Where a secret was found in a line, as byte offsets
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecretMatch {
+    pub kind: &'static str,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A secret found in a session's transcript
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretFinding {
+    /// Credential format, e.g. "github-token" or "private-key"
+    pub kind: String,
+    /// Line of the transcript the secret appears in
+    pub line_number: usize,
+    /// Start of the secret with the rest masked, safe to display
+    pub preview: String,
+}
+
+/// Outcome of redacting a transcript
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionResult {
+    /// Number of secrets replaced
+    pub redacted_count: usize,
+    /// Copy of the transcript before redaction, None if nothing was redacted
+    pub backup_path: Option<String>,
+}
+
+/// Shannon entropy of a string in bits per character
+pub fn shannon_entropy(text: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    let mut total = 0;
+    for c in text.chars() {
+        *counts.entry(c).or_default() += 1;
+        total += 1;
+    }
+    counts.values()
+        .map(|&n| {
+            let p = n as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Values of generic assignments must look like generated keys, not words or env lookups
+fn is_random_enough(value: &str, min_entropy: f64) -> bool {
+    value.len() >= MIN_GENERIC_SECRET_LEN
+        && value.chars().any(|c| c.is_ascii_digit())
+        && value.chars().any(|c| c.is_ascii_alphabetic())
+        && shannon_entropy(value) >= min_entropy
+}
+
+/// Find the secrets in one line, in order of position, without overlaps
+pub fn find_secrets(line: &str) -> Vec<SecretMatch> {
+    let mut found: Vec<SecretMatch> = Vec::new();
+    for index in SECRET_SET.matches(line).iter() {
+        let pattern = &SECRET_PATTERNS[index];
+        for captures in SECRET_REGEXES[index].captures_iter(line) {
+            let Some(secret) = captures.name("secret").or_else(|| captures.get(0)) else {
+                continue;
+            };
+            if pattern.min_entropy.is_some_and(|min| !is_random_enough(secret.as_str(), min)) {
+                continue;
+            }
+            if found.iter().any(|m| secret.start() < m.end && m.start < secret.end()) {
+                continue;
+            }
+            found.push(SecretMatch { kind: pattern.kind, start: secret.start(), end: secret.end() });
+        }
+    }
+    found.sort_by_key(|m| m.start);
+    found
+}
+
+/// Show the first few characters of a secret, enough to recognize it but not to use it
+fn mask(secret: &str) -> String {
+    let shown: String = secret.chars().take(4).collect();
+    format!("{}… ({} chars)", shown, secret.chars().count())
+}
+
+/// Replace every secret in a line with `[REDACTED:<kind>]`
+pub fn redact_line(line: &str) -> (String, usize) {
+    let matches = find_secrets(line);
+    let mut redacted = String::with_capacity(line.len());
+    let mut last = 0;
+    for m in &matches {
+        redacted.push_str(&line[last..m.start]);
+        redacted.push_str(&format!("[REDACTED:{}]", m.kind));
+        last = m.end;
+    }
+    redacted.push_str(&line[last..]);
+    (redacted, matches.len())
+}
+
+/// Redact every line of a chunk of transcript. Fails if a line that was valid
+/// JSON would no longer parse, rather than corrupting the transcript.
+fn redact_text(text: &str, first_line_number: usize) -> Result<(String, usize), String> {
+    let mut redacted = String::with_capacity(text.len());
+    let mut count = 0;
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let (new_line, n) = redact_line(line);
+        if n > 0
+            && serde_json::from_str::<serde_json::Value>(line).is_ok()
+            && serde_json::from_str::<serde_json::Value>(&new_line).is_err()
+        {
+            return Err(format!("Redacting line {} would leave invalid JSON", first_line_number + index));
+        }
+        redacted.push_str(&new_line);
+        count += n;
+    }
+    Ok((redacted, count))
+}
+
+/// Incremental scan state for one session's transcript
+#[derive(Default)]
+struct SecretsState {
+    findings: VecDeque<SecretFinding>,
+}
+
+/// Secret findings by session id, updated from the lines appended since the last poll
+static SECRETS_CACHE: Lazy<Mutex<HashMap<String, SecretsState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Scan the lines appended to a session's transcript since the previous poll and
+/// return the number of secrets found in the transcript so far
+pub fn update_secret_findings(session_id: &str, update: &TranscriptUpdate) -> usize {
+    let mut cache = SECRETS_CACHE.lock().unwrap();
+    let state = cache.entry(session_id.to_string()).or_default();
+    if update.from_start {
+        state.findings.clear();
+    }
+
+    for line in &update.lines {
+        for m in find_secrets(&line.text) {
+            if state.findings.len() == MAX_SECRET_FINDINGS {
+                state.findings.pop_front();
+            }
+            state.findings.push_back(SecretFinding {
+                kind: m.kind.to_string(),
+                line_number: line.line_number,
+                preview: mask(&line.text[m.start..m.end]),
+            });
+        }
+    }
+    state.findings.len()
+}
+
+/// Secrets found in a live session's transcript, in order of appearance
+pub fn get_secret_findings(session_id: &str) -> Option<Vec<SecretFinding>> {
+    SECRETS_CACHE.lock().unwrap().get(session_id).map(|state| state.findings.iter().cloned().collect())
+}
+
+/// Redact the secrets in an ended session's transcript, keeping a backup under the app's data directory.
+/// Refused while the agent runs: it would keep writing to the replaced file, and its later lines would be lost.
+pub fn redact_session_secrets(session_id: &str) -> Result<RedactionResult, String> {
+    let path = find_claude_transcript(session_id)
+        .ok_or_else(|| format!("No transcript for session {}", session_id))?;
+    ensure_not_running(&path, &crate::agent::running_claude_processes())?;
+    let backup_dir = dirs::data_dir()
+        .ok_or("Could not determine data directory")?
+        .join("agent-sessions")
+        .join("redaction-backups");
+    redact_file(&path, &backup_dir)
+}
+
+/// Refuse a transcript that one of `processes` is writing, whether or not its session is listed
+pub fn ensure_not_running(path: &Path, processes: &[AgentProcess]) -> Result<(), String> {
+    if is_transcript_in_use(path, processes) {
+        return Err("The session is still running. End it before redacting its transcript.".to_string());
+    }
+    Ok(())
+}
+
+/// Replace the secrets in a transcript in place.
+///
+/// The original is copied into `backup_dir` (readable by the owner only), the redacted
+/// version written next to the transcript and renamed over it, so readers see either
+/// the old or the new file. The rename gives the transcript a new identity, which makes
+/// every incremental reader rescan it.
+///
+/// A writer that keeps the transcript open would go on writing to the replaced file, so
+/// this is only safe once the agent exited. Lines appended while redacting, e.g. by a
+/// session resumed meanwhile, are carried over up to the rename.
+pub fn redact_file(path: &Path, backup_dir: &Path) -> Result<RedactionResult, String> {
+    let mut original = String::new();
+    File::open(path)
+        .and_then(|mut f| f.read_to_string(&mut original))
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+
+    let (mut redacted, mut redacted_count) = redact_text(&original, 1)?;
+    if redacted_count == 0 {
+        return Ok(RedactionResult { redacted_count: 0, backup_path: None });
+    }
+
+    let file_name = path.file_name().and_then(|n| n.to_str()).ok_or_else(|| format!("Invalid transcript path {:?}", path))?;
+    let temp_path = path.with_file_name(format!(".{}.redacting", file_name));
+    let backup_path = backup_dir.join(format!("{}.{}", file_name, Utc::now().format("%Y%m%dT%H%M%S%.3fZ")));
+
+    create_private_dir(backup_dir).map_err(|e| format!("Failed to create {:?}: {}", backup_dir, e))?;
+
+    let result = (|| -> Result<(), String> {
+        let mut backup = create_private_file(&backup_path).map_err(|e| format!("Failed to create backup: {}", e))?;
+        let mut temp = create_private_file(&temp_path).map_err(|e| format!("Failed to create {:?}: {}", temp_path, e))?;
+
+        let mut copied = original.len() as u64;
+        let mut line_count = original.matches('\n').count();
+        backup.write_all(original.as_bytes()).map_err(|e| format!("Failed to write backup: {}", e))?;
+        loop {
+            temp.write_all(redacted.as_bytes()).map_err(|e| format!("Failed to write {:?}: {}", temp_path, e))?;
+
+            // Carry over lines appended while we were writing
+            let tail = read_from(path, copied).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+            if tail.is_empty() {
+                break;
+            }
+            backup.write_all(tail.as_bytes()).map_err(|e| format!("Failed to write backup: {}", e))?;
+            let (tail_redacted, n) = redact_text(&tail, line_count + 1)?;
+            copied += tail.len() as u64;
+            line_count += tail.matches('\n').count();
+            redacted = tail_redacted;
+            redacted_count += n;
+        }
+
+        backup.sync_all().map_err(|e| format!("Failed to write backup: {}", e))?;
+        temp.sync_all().map_err(|e| format!("Failed to write {:?}: {}", temp_path, e))?;
+        if let Ok(metadata) = fs::metadata(path) {
+            let _ = fs::set_permissions(&temp_path, metadata.permissions());
+        }
+        fs::rename(&temp_path, path).map_err(|e| format!("Failed to replace {:?}: {}", path, e))
+    })();
+
+    if let Err(e) = result {
+        warn!("Redaction of {:?} failed: {}", path, e);
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    info!("Redacted {} secrets in {:?}, backup at {:?}", redacted_count, path, backup_path);
+    Ok(RedactionResult {
+        redacted_count,
+        backup_path: Some(backup_path.to_string_lossy().to_string()),
+    })
+}
+
+/// Read a file from `offset` to its end
+fn read_from(path: &Path, offset: u64) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut tail = String::new();
+    file.read_to_string(&mut tail)?;
+    Ok(tail)
+}
+
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+fn create_private_file(path: &Path) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Drop findings of sessions that no longer exist
+pub fn cleanup_stale_secrets_entries(active_session_ids: &HashSet<String>) {
+    SECRETS_CACHE.lock().unwrap().retain(|id, _| active_session_ids.contains(id));
+}
@@ -0,0 +1,203 @@
+/// A command with its arguments and the files its output is redirected to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimpleCommand {
+    pub words: Vec<String>,
+    /// Targets of `>`, `>>`, `2>` and `&>` redirections
+    pub redirects: Vec<String>,
+}
+
+/// Commands connected by `|`
+pub type Pipeline = Vec<SimpleCommand>;
+
+/// Wrappers that run the command given as their arguments
+const WRAPPERS: &[&str] = &["sudo", "doas", "env", "command", "exec", "nohup", "time", "nice"];
+
+impl SimpleCommand {
+    /// The command that actually runs, after environment assignments and wrappers
+    /// like `sudo` or `env`, e.g. `sudo -E FOO=1 rm -rf x` -> `rm -rf x`
+    pub fn effective_words(&self) -> &[String] {
+        let mut words = self.words.as_slice();
+        loop {
+            match words.first() {
+                Some(w) if is_assignment(w) => words = &words[1..],
+                Some(w) if WRAPPERS.contains(&program_name(w)) => {
+                    words = &words[1..];
+                    // Wrapper options, e.g. `sudo -E` or `nice -n 10`
+                    while let Some(w) = words.first().filter(|w| w.starts_with('-')) {
+                        let takes_value = matches!(w.as_str(), "-u" | "-g" | "-n" | "-C");
+                        words = &words[if takes_value { 2 } else { 1 }.min(words.len())..];
+                    }
+                }
+                _ => return words,
+            }
+        }
+    }
+
+    /// File name of the program that runs, e.g. "rm" for `/bin/rm`
+    pub fn program(&self) -> Option<&str> {
+        self.effective_words().first().map(|w| program_name(w))
+    }
+
+    /// True if the command runs through `sudo` or `doas`
+    pub fn is_privileged(&self) -> bool {
+        let prefix_len = self.words.len() - self.effective_words().len();
+        self.words[..prefix_len].iter().any(|w| matches!(program_name(w), "sudo" | "doas"))
+    }
+}
+
+fn program_name(word: &str) -> &str {
+    word.rsplit('/').next().unwrap_or(word)
+}
+
+/// `NAME=value`, as used to set environment variables for one command
+fn is_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((name, _)) => !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !name.starts_with(|c: char| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+/// What the next word completes
+#[derive(PartialEq)]
+enum NextWord {
+    Argument,
+    RedirectTarget,
+    /// Input files and duplicated file descriptors aren't written to
+    Ignored,
+}
+
+/// Split a shell command line into pipelines of simple commands.
+///
+/// Understands quoting, escapes, comments, the `;`, `&&`, `||`, `&` and `|` operators
+/// and output redirections. Command substitutions are kept as literal words, so
+/// commands nested in `$(...)` aren't inspected.
+pub fn parse_command_line(line: &str) -> Vec<Pipeline> {
+    let mut parser = Parser::default();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                parser.in_word = true;
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    parser.word.push(c);
+                }
+            }
+            '"' => {
+                parser.in_word = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' if matches!(chars.peek(), Some('"' | '\\' | '$' | '`')) => parser.word.extend(chars.next()),
+                        _ => parser.word.push(c),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') | None => {}
+                Some(c) => {
+                    parser.in_word = true;
+                    parser.word.push(c);
+                }
+            },
+            '#' if !parser.in_word => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+            }
+            ' ' | '\t' => parser.finish_word(),
+            ';' | '\n' => parser.finish_pipeline(),
+            '|' => {
+                if chars.next_if_eq(&'|').is_some() {
+                    parser.finish_pipeline();
+                } else {
+                    chars.next_if_eq(&'&');
+                    parser.finish_command();
+                }
+            }
+            '&' => {
+                if chars.next_if_eq(&'&').is_some() {
+                    parser.finish_pipeline();
+                } else if chars.next_if_eq(&'>').is_some() {
+                    chars.next_if_eq(&'>');
+                    parser.start_redirect(NextWord::RedirectTarget);
+                } else {
+                    parser.finish_pipeline();
+                }
+            }
+            '>' => {
+                // A file descriptor number right before `>` belongs to the redirection
+                if parser.word.chars().all(|c| c.is_ascii_digit()) {
+                    parser.word.clear();
+                    parser.in_word = false;
+                }
+                chars.next_if(|c| *c == '>' || *c == '|');
+                if chars.next_if_eq(&'&').is_some() {
+                    parser.start_redirect(NextWord::Ignored);
+                } else {
+                    parser.start_redirect(NextWord::RedirectTarget);
+                }
+            }
+            '<' => {
+                while chars.next_if(|c| *c == '<').is_some() {}
+                parser.start_redirect(NextWord::Ignored);
+            }
+            _ => {
+                parser.in_word = true;
+                parser.word.push(c);
+            }
+        }
+    }
+
+    parser.finish_pipeline();
+    parser.pipelines
+}
+
+#[derive(Default)]
+struct Parser {
+    pipelines: Vec<Pipeline>,
+    pipeline: Pipeline,
+    command: SimpleCommand,
+    word: String,
+    in_word: bool,
+    next_word: Option<NextWord>,
+}
+
+impl Parser {
+    fn start_redirect(&mut self, next_word: NextWord) {
+        self.finish_word();
+        self.next_word = Some(next_word);
+    }
+
+    fn finish_word(&mut self) {
+        if !self.in_word {
+            return;
+        }
+        let word = std::mem::take(&mut self.word);
+        self.in_word = false;
+        match self.next_word.take().unwrap_or(NextWord::Argument) {
+            NextWord::Argument => self.command.words.push(word),
+            NextWord::RedirectTarget => self.command.redirects.push(word),
+            NextWord::Ignored => {}
+        }
+    }
+
+    fn finish_command(&mut self) {
+        self.finish_word();
+        self.next_word = None;
+        let command = std::mem::take(&mut self.command);
+        if !command.words.is_empty() || !command.redirects.is_empty() {
+            self.pipeline.push(command);
+        }
+    }
+
+    fn finish_pipeline(&mut self) {
+        self.finish_command();
+        let pipeline = std::mem::take(&mut self.pipeline);
+        if !pipeline.is_empty() {
+            self.pipelines.push(pipeline);
+        }
+    }
+}
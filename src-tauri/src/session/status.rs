@@ -133,6 +133,7 @@ pub fn status_sort_priority(status: &SessionStatus) -> u8 {
         SessionStatus::Compacting => 0,  // Active - compressing context - show first
         SessionStatus::Waiting => 1,     // Needs attention - show second
         SessionStatus::Idle => 2,        // Inactive - show last
+        SessionStatus::Paused => 2,      // Stopped on purpose - show with inactive
     }
 }
 
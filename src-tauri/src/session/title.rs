@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use super::incremental::TranscriptUpdate;
+use super::model::JsonlMessage;
+use super::status::has_tool_result;
+
+/// Maximum characters in a session title
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Wrapper tags written by Claude Code whose content is never part of the user's prompt
+const NOISE_TAGS: [&str; 7] = [
+    "command-name",
+    "command-message",
+    "local-command-stdout",
+    "local-command-stderr",
+    "local-command-caveat",
+    "system-reminder",
+    "user-prompt-submit-hook",
+];
+
+/// Incremental title scan state for one session's transcript
+#[derive(Default)]
+struct TitleState {
+    /// Latest summary entry so far
+    summary: Option<String>,
+    first_prompt: Option<String>,
+}
+
+/// Titles by session id, updated from the lines appended since the last poll
+static TITLE_CACHE: Lazy<Mutex<HashMap<String, TitleState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Get the title for a Claude session: its latest summary, or else its first prompt.
+/// Summaries appended later in the session replace the prompt title.
+pub fn get_session_title(session_id: &str, update: &TranscriptUpdate) -> Option<String> {
+    let mut cache = TITLE_CACHE.lock().unwrap();
+    let state = cache.entry(session_id.to_string()).or_default();
+    if update.from_start {
+        *state = TitleState::default();
+    }
+
+    for msg in update.lines.iter().filter_map(|line| line.msg.as_ref()) {
+        scan_title_entry(msg, &mut state.summary, &mut state.first_prompt);
+    }
+
+    title_of(state.summary.as_deref(), state.first_prompt.as_ref())
+}
+
+/// Drop titles of sessions that no longer exist
+pub fn cleanup_stale_title_entries(active_session_ids: &HashSet<String>) {
+    TITLE_CACHE.lock().unwrap().retain(|id, _| active_session_ids.contains(id));
+}
+
+/// Take the summary or first prompt from a transcript entry
+fn scan_title_entry(msg: &JsonlMessage, summary: &mut Option<String>, first_prompt: &mut Option<String>) {
+    if msg.msg_type.as_deref() == Some("summary") {
+        if let Some(s) = msg.summary.as_ref().filter(|s| !s.trim().is_empty()) {
+            *summary = Some(s.clone());
+        }
+        return;
+    }
+
+    if first_prompt.is_none() && msg.msg_type.as_deref() == Some("user") && msg.is_meta != Some(true) {
+        if let Some(content) = msg.message.as_ref().and_then(|m| m.content.as_ref()) {
+            *first_prompt = prompt_from_content(content);
+        }
+    }
+}
+
+fn title_of(summary: Option<&str>, first_prompt: Option<&String>) -> Option<String> {
+    summary
+        .map(|s| truncate_title(s.trim()))
+        .or_else(|| first_prompt.cloned())
+}
+
+/// Extract a title from a full transcript: the latest summary entry if there is one,
+/// otherwise the first user prompt after cleanup
+pub fn extract_title_from_jsonl(jsonl_path: &Path) -> Option<String> {
+    let file = File::open(jsonl_path).ok()?;
+    let reader = BufReader::new(file);
+
+    let mut summary: Option<String> = None;
+    let mut first_prompt: Option<String> = None;
+
+    for line in reader.lines().map_while(Result::ok) {
+        if let Ok(msg) = serde_json::from_str::<JsonlMessage>(&line) {
+            scan_title_entry(&msg, &mut summary, &mut first_prompt);
+        }
+    }
+
+    title_of(summary.as_deref(), first_prompt.as_ref())
+}
+
+/// Get a cleaned prompt from user message content, skipping tool results
+pub fn prompt_from_content(content: &serde_json::Value) -> Option<String> {
+    if has_tool_result(content) {
+        return None;
+    }
+
+    let text = match content {
+        serde_json::Value::String(s) => s.as_str(),
+        serde_json::Value::Array(arr) => arr.iter()
+            .find_map(|v| v.get("text").and_then(|t| t.as_str()))?,
+        _ => return None,
+    };
+
+    clean_prompt(text)
+}
+
+/// Turn a raw user prompt into a one-line title.
+///
+/// - Custom slash commands keep their arguments (`<command-args>`), built-in ones are dropped
+/// - CLI wrapper tags like `<command-name>` and `<system-reminder>` are removed with their content
+/// - Any other XML-style tags are removed but their text is kept
+/// - A leading `/command` token is stripped
+///
+/// Returns None if nothing meaningful is left.
+pub fn clean_prompt(text: &str) -> Option<String> {
+    let mut text = match tag_content(text, "command-args") {
+        Some(args) => args.to_string(),
+        None => text.to_string(),
+    };
+
+    for tag in NOISE_TAGS {
+        text = remove_tag_blocks(&text, tag);
+    }
+    let text = strip_tags(&text);
+
+    // Drop a leading slash command such as "/review" or "/compact keep tests"
+    let trimmed = text.trim_start();
+    let without_command = if trimmed.starts_with('/') {
+        trimmed.split_once(char::is_whitespace).map(|(_, rest)| rest).unwrap_or("")
+    } else {
+        trimmed
+    };
+
+    let first_line = without_command
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())?;
+
+    let collapsed = first_line.split_whitespace().collect::<Vec<_>>().join(" ");
+    Some(truncate_title(&collapsed))
+}
+
+/// Get the trimmed content of the first `<tag>...</tag>` block, if it is non-empty
+fn tag_content<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = text.find(&open)? + open.len();
+    let end = start + text[start..].find(&close)?;
+    Some(text[start..end].trim()).filter(|s| !s.is_empty())
+}
+
+/// Remove every `<tag ...>...</tag>` block, including unterminated trailing blocks
+fn remove_tag_blocks(text: &str, tag: &str) -> String {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(&open) {
+        // Make sure we matched the whole tag name, not a prefix of a longer one
+        let after = &rest[start + open.len()..];
+        if !after.starts_with('>') && !after.starts_with(char::is_whitespace) {
+            result.push_str(&rest[..start + open.len()]);
+            rest = after;
+            continue;
+        }
+
+        result.push_str(&rest[..start]);
+        match after.find(&close) {
+            Some(end) => rest = &after[end + close.len()..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Remove anything that looks like an XML tag, keeping the text between tags
+fn strip_tags(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        let after = &rest[start + 1..];
+        let looks_like_tag = after.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/');
+        match after.find('>') {
+            Some(end) if looks_like_tag => {
+                result.push_str(&rest[..start]);
+                result.push(' ');
+                rest = &after[end + 1..];
+            }
+            _ => {
+                result.push_str(&rest[..=start]);
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Truncate a title to MAX_TITLE_CHARS, respecting UTF-8 char boundaries
+fn truncate_title(title: &str) -> String {
+    if title.chars().count() > MAX_TITLE_CHARS {
+        format!("{}...", title.chars().take(MAX_TITLE_CHARS).collect::<String>().trim_end())
+    } else {
+        title.to_string()
+    }
+}
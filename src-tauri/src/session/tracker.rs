@@ -0,0 +1,192 @@
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use log::{debug, warn};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use super::audit::cleanup_stale_audit_entries;
+use super::context::cleanup_stale_context_entries;
+use super::files::cleanup_stale_files_entries;
+use super::incremental::cleanup_stale_session_cursors;
+use super::parser::cleanup_stale_container_mounts;
+use super::resources::cleanup_stale_resource_entries;
+use super::secrets::cleanup_stale_secrets_entries;
+use super::title::cleanup_stale_title_entries;
+use super::events::{record_events, SessionEvent, SessionEventKind};
+use super::model::{AgentType, Session, SessionStatus};
+
+/// How long a session waits for input before it counts as idle
+pub const DEFAULT_IDLE_THRESHOLD_SECS: u64 = 15 * 60;
+
+/// Current idle threshold in seconds, 0 disables idle detection
+static IDLE_THRESHOLD_SECS: AtomicU64 = AtomicU64::new(DEFAULT_IDLE_THRESHOLD_SECS);
+
+/// Set how long a session has to wait for input before it is reported as Idle.
+/// 0 keeps waiting sessions in Waiting indefinitely.
+pub fn set_idle_threshold(secs: u64) {
+    IDLE_THRESHOLD_SECS.store(secs, Ordering::Relaxed);
+}
+
+/// Get the current idle threshold in seconds
+pub fn idle_threshold() -> u64 {
+    IDLE_THRESHOLD_SECS.load(Ordering::Relaxed)
+}
+
+/// What the tracker remembers about a session between polls
+#[derive(Debug, Clone)]
+struct TrackedStatus {
+    /// Status as determined from the transcript
+    status: SessionStatus,
+    /// When `status` began
+    since: DateTime<Utc>,
+    /// Status last reported to the frontend, after idle demotion
+    reported: SessionStatus,
+    active_subagent_count: usize,
+    agent_type: AgentType,
+    project_name: String,
+}
+
+/// Tracks when each session's status began across polls
+#[derive(Default)]
+pub struct StatusTracker {
+    sessions: HashMap<String, TrackedStatus>,
+}
+
+impl StatusTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the session's current status and fill in `status_since`.
+    /// Sessions that have been waiting longer than `idle_threshold_secs` are demoted to Idle.
+    /// Returns the events since the previous call for this session.
+    pub fn track(&mut self, session: &mut Session, now: DateTime<Utc>, idle_threshold_secs: u64) -> Vec<SessionEvent> {
+        let is_new = !self.sessions.contains_key(&session.id);
+        let tracked = self.sessions.entry(session.id.clone()).or_insert_with(|| {
+            // First time we see this session (new, or the app just started):
+            // the status most likely began with the last transcript entry
+            let since = DateTime::parse_from_rfc3339(&session.last_activity_at)
+                .map(|t| t.with_timezone(&Utc).min(now))
+                .unwrap_or(now);
+            TrackedStatus {
+                status: session.status.clone(),
+                since,
+                reported: session.status.clone(),
+                active_subagent_count: 0,
+                agent_type: session.agent_type.clone(),
+                project_name: session.project_name.clone(),
+            }
+        });
+
+        if tracked.status != session.status {
+            tracked.status = session.status.clone();
+            tracked.since = now;
+        }
+
+        let mut since = tracked.since;
+        if session.status == SessionStatus::Waiting && idle_threshold_secs > 0 {
+            let idle_at = since + Duration::seconds(idle_threshold_secs as i64);
+            if now >= idle_at {
+                debug!("Session {} has been waiting since {}, reporting as idle", session.id, since);
+                session.status = SessionStatus::Idle;
+                since = idle_at;
+            }
+        }
+        session.status_since = since.to_rfc3339_opts(SecondsFormat::Millis, true);
+
+        let mut kinds = Vec::new();
+        if is_new {
+            kinds.push((now, SessionEventKind::SessionStarted {
+                status: session.status.clone(),
+                project_path: session.project_path.clone(),
+            }));
+        } else if tracked.reported != session.status {
+            warn!(
+                "STATUS TRANSITION: project={}, {:?} -> {:?}, cpu={:.1}%, last_msg_role={:?}",
+                session.project_name, tracked.reported, session.status, session.cpu_usage, session.last_message_role
+            );
+            kinds.push((since, SessionEventKind::StatusChanged {
+                from: tracked.reported.clone(),
+                to: session.status.clone(),
+            }));
+        }
+        tracked.reported = session.status.clone();
+
+        let active_subagent_count = session.active_subagent_count;
+        if active_subagent_count > tracked.active_subagent_count {
+            kinds.push((now, SessionEventKind::SubagentStarted { active_subagent_count }));
+        } else if active_subagent_count < tracked.active_subagent_count {
+            kinds.push((now, SessionEventKind::SubagentFinished { active_subagent_count }));
+        }
+        tracked.active_subagent_count = active_subagent_count;
+        tracked.project_name = session.project_name.clone();
+
+        kinds.into_iter()
+            .map(|(at, kind)| session_event(&session.id, tracked, at, kind))
+            .collect()
+    }
+
+    /// Forget sessions that no longer exist, returning a SessionEnded event for each
+    pub fn retain(&mut self, active_session_ids: &HashSet<String>, now: DateTime<Utc>) -> Vec<SessionEvent> {
+        let ended: Vec<String> = self.sessions.keys()
+            .filter(|id| !active_session_ids.contains(*id))
+            .cloned()
+            .collect();
+
+        ended.into_iter()
+            .filter_map(|id| {
+                let tracked = self.sessions.remove(&id)?;
+                Some(session_event(&id, &tracked, now, SessionEventKind::SessionEnded))
+            })
+            .collect()
+    }
+}
+
+/// Build an event for a tracked session; the sequence number is assigned when it is recorded
+fn session_event(session_id: &str, tracked: &TrackedStatus, at: DateTime<Utc>, kind: SessionEventKind) -> SessionEvent {
+    SessionEvent {
+        seq: 0,
+        at: at.to_rfc3339_opts(SecondsFormat::Millis, true),
+        session_id: session_id.to_string(),
+        agent_type: tracked.agent_type.clone(),
+        project_name: tracked.project_name.clone(),
+        kind,
+    }
+}
+
+/// Status history of all live sessions, across agents
+static STATUS_TRACKER: Lazy<Mutex<StatusTracker>> = Lazy::new(|| Mutex::new(StatusTracker::new()));
+
+/// Track the status of every session found in this poll
+pub fn track_session_status(sessions: &mut [Session]) {
+    let now = Utc::now();
+    let threshold = idle_threshold();
+    let mut events = Vec::new();
+    {
+        let mut tracker = STATUS_TRACKER.lock().unwrap();
+        for session in sessions {
+            events.extend(tracker.track(session, now, threshold));
+        }
+    }
+    record_events(events);
+}
+
+/// Clean up tracking entries for sessions that no longer exist and report them as ended.
+/// Call this after all agent detectors have run to prevent unbounded memory growth.
+pub fn cleanup_stale_status_entries(active_session_ids: &HashSet<String>) {
+    let ended = STATUS_TRACKER.lock().unwrap().retain(active_session_ids, Utc::now());
+    if !ended.is_empty() {
+        debug!("Cleaned up {} stale status tracking entries", ended.len());
+    }
+    record_events(ended);
+
+    cleanup_stale_context_entries(active_session_ids);
+    cleanup_stale_files_entries(active_session_ids);
+    cleanup_stale_audit_entries(active_session_ids);
+    cleanup_stale_secrets_entries(active_session_ids);
+    cleanup_stale_resource_entries(active_session_ids);
+    cleanup_stale_container_mounts(active_session_ids);
+    cleanup_stale_title_entries(active_session_ids);
+    cleanup_stale_session_cursors(active_session_ids);
+}
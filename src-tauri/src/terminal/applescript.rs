@@ -21,3 +21,8 @@ pub fn execute_applescript(script: &str) -> Result<(), String> {
         Err(format!("AppleScript error: {}", stderr))
     }
 }
+
+/// Escape a string for embedding inside an AppleScript string literal
+pub fn escape_applescript_string(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -1,4 +1,4 @@
-use super::applescript::execute_applescript;
+use super::applescript::{escape_applescript_string, execute_applescript};
 
 /// Focus iTerm2 tab/session by TTY
 pub fn focus_iterm_by_tty(tty: &str) -> Result<(), String> {
@@ -29,3 +29,28 @@ pub fn focus_iterm_by_tty(tty: &str) -> Result<(), String> {
 
     execute_applescript(&script)
 }
+
+/// Open a new iTerm2 tab (or window if none are open) and run a shell command in it
+pub fn open_iterm_tab(shell_command: &str) -> Result<(), String> {
+    let script = format!(r#"
+        tell application "System Events"
+            if not (exists process "iTerm2") then
+                error "iTerm2 not running"
+            end if
+        end tell
+
+        tell application "iTerm2"
+            activate
+            if (count of windows) = 0 then
+                set w to (create window with default profile)
+            else
+                set w to current window
+                tell w to create tab with default profile
+            end if
+            tell current session of w to write text "{}"
+        end tell
+        return "found"
+    "#, escape_applescript_string(shell_command));
+
+    execute_applescript(&script)
+}
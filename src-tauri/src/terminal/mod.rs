@@ -53,6 +53,28 @@ pub fn focus_terminal_by_path(path: &str) -> Result<(), String> {
     execute_applescript(&script)
 }
 
+/// Open a new terminal in `cwd` running `command`.
+/// Uses the same backends as focusing: an attached tmux client first,
+/// then iTerm2 if it is running, then Terminal.app.
+pub fn open_terminal_with_command(cwd: &str, command: &str) -> Result<(), String> {
+    if tmux::open_tmux_window(cwd, command).is_ok() {
+        return Ok(());
+    }
+
+    let shell_command = format!("cd {} && {}", shell_quote(cwd), command);
+
+    if iterm::open_iterm_tab(&shell_command).is_ok() {
+        return Ok(());
+    }
+
+    terminal_app::open_terminal_app_window(&shell_command)
+}
+
+/// Quote a string for POSIX shells using single quotes
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
 /// Get the TTY device for a given PID using ps command
 fn get_tty_for_pid(pid: u32) -> Result<String, String> {
     use std::process::Command;
@@ -1,4 +1,4 @@
-use super::applescript::execute_applescript;
+use super::applescript::{escape_applescript_string, execute_applescript};
 
 /// Focus Terminal.app tab by TTY
 pub fn focus_terminal_app_by_tty(tty: &str) -> Result<(), String> {
@@ -40,3 +40,17 @@ pub fn focus_terminal_app_by_tty(tty: &str) -> Result<(), String> {
 
     execute_applescript(&script)
 }
+
+/// Open a new Terminal.app window running a shell command.
+/// Terminal.app ships with macOS, so this launches it if needed.
+pub fn open_terminal_app_window(shell_command: &str) -> Result<(), String> {
+    let script = format!(r#"
+        tell application "Terminal"
+            activate
+            do script "{}"
+        end tell
+        return "found"
+    "#, escape_applescript_string(shell_command));
+
+    execute_applescript(&script)
+}
@@ -49,6 +49,31 @@ pub fn focus_tmux_pane_by_tty(tty: &str) -> Result<(), String> {
     Err("Pane not found in tmux".to_string())
 }
 
+/// Open a new tmux window running a command in the given directory.
+/// Only used when a tmux client is attached, otherwise the window would be invisible.
+pub fn open_tmux_window(cwd: &str, command: &str) -> Result<(), String> {
+    let output = Command::new("tmux")
+        .args(["list-clients", "-F", "#{client_tty}"])
+        .output()
+        .map_err(|e| format!("Failed to run tmux: {}", e))?;
+
+    if !output.status.success() || String::from_utf8_lossy(&output.stdout).trim().is_empty() {
+        return Err("No attached tmux client".to_string());
+    }
+
+    let output = Command::new("tmux")
+        .args(["new-window", "-c", cwd, command])
+        .output()
+        .map_err(|e| format!("Failed to open tmux window: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("tmux new-window failed: {}", stderr));
+    }
+
+    focus_tmux_client_terminal()
+}
+
 /// Focus the terminal application that is running the tmux client
 fn focus_tmux_client_terminal() -> Result<(), String> {
     // Get the tmux client TTY
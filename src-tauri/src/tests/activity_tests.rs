@@ -0,0 +1,185 @@
+use crate::session::activity::{
+    last_weeks, project_time_csv, read_records, sessions_per_week, time_by_project_day, weekly_sessions_csv,
+    ActivityKind, ActivityRecord, ActivityStore, ProjectDayActivity,
+};
+use crate::session::{AgentType, SessionStatus};
+use chrono::{DateTime, FixedOffset, Utc};
+use std::io::Write;
+use tempfile::tempdir;
+
+// Helper functions
+
+fn at(time: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(time).unwrap().with_timezone(&Utc)
+}
+
+fn record(time: &str, session_id: Option<&str>, kind: ActivityKind) -> ActivityRecord {
+    ActivityRecord::new(at(time), session_id, kind)
+}
+
+fn started(time: &str, session_id: &str, project: &str, status: SessionStatus) -> ActivityRecord {
+    started_in(time, session_id, &format!("/src/{}", project), status)
+}
+
+fn started_in(time: &str, session_id: &str, project_path: &str, status: SessionStatus) -> ActivityRecord {
+    record(time, Some(session_id), ActivityKind::SessionStarted {
+        project: project_path.rsplit('/').next().unwrap_or_default().to_string(),
+        project_path: Some(project_path.to_string()),
+        agent_type: AgentType::Claude,
+        status,
+    })
+}
+
+fn status(time: &str, session_id: &str, status: SessionStatus) -> ActivityRecord {
+    record(time, Some(session_id), ActivityKind::StatusChanged { status })
+}
+
+fn day_row(rows: &[ProjectDayActivity], day: &str, project: &str) -> ProjectDayActivity {
+    rows.iter().find(|r| r.day == day && r.project == project).cloned().unwrap_or_default()
+}
+
+// Tests for the activity log
+
+#[test]
+fn test_activity_store_round_trip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("nested").join("activity.jsonl");
+    let records = vec![
+        record("2024-01-01T09:00:00Z", None, ActivityKind::AppStarted),
+        started("2024-01-01T09:00:00Z", "s1", "api", SessionStatus::Processing),
+        record("2024-01-01T09:01:00Z", Some("s1"), ActivityKind::Tokens { input_tokens: 1000, output_tokens: 50 }),
+    ];
+
+    let mut store = ActivityStore::open(&path).unwrap();
+    store.append(&records[..2]).unwrap();
+    store.append(&records[2..]).unwrap();
+    // A line cut off by a crash is skipped
+    std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"at\":\"2024").unwrap();
+
+    assert_eq!(store.read_all().unwrap(), records);
+    let started_line = std::fs::read_to_string(&path).unwrap().lines().nth(1).unwrap().to_string();
+    assert!(started_line.contains(r#""type":"sessionStarted""#));
+    assert!(started_line.contains(r#""sessionId":"s1""#));
+    assert!(read_records(&dir.path().join("missing.jsonl")).is_err());
+}
+
+#[test]
+fn test_time_by_project_day_splits_statuses_and_days() {
+    let records = vec![
+        started("2024-01-01T22:00:00Z", "s1", "api", SessionStatus::Processing),
+        status("2024-01-01T23:30:00Z", "s1", SessionStatus::Waiting),
+        status("2024-01-02T00:30:00Z", "s1", SessionStatus::Processing),
+        record("2024-01-02T00:40:00Z", Some("s1"), ActivityKind::Tokens { input_tokens: 1000, output_tokens: 100 }),
+        record("2024-01-02T00:45:00Z", Some("s1"), ActivityKind::Tokens { input_tokens: 1500, output_tokens: 120 }),
+        record("2024-01-02T01:00:00Z", Some("s1"), ActivityKind::SessionEnded),
+        started("2024-01-02T10:00:00Z", "s2", "web", SessionStatus::Waiting),
+    ];
+
+    let rows = time_by_project_day(&records, &Utc, at("2024-01-02T10:15:00Z"));
+    assert_eq!(rows.iter().map(|r| (r.day.as_str(), r.project.as_str())).collect::<Vec<_>>(),
+        vec![("2024-01-01", "api"), ("2024-01-02", "api"), ("2024-01-02", "web")]);
+
+    let first_day = day_row(&rows, "2024-01-01", "api");
+    assert_eq!((first_day.processing_secs, first_day.waiting_secs), (90 * 60, 30 * 60));
+    let second_day = day_row(&rows, "2024-01-02", "api");
+    assert_eq!((second_day.processing_secs, second_day.waiting_secs), (30 * 60, 30 * 60));
+    assert_eq!((second_day.input_tokens, second_day.output_tokens), (1500, 120));
+    // Still open, counted until now
+    assert_eq!(day_row(&rows, "2024-01-02", "web").waiting_secs, 15 * 60);
+
+    // Days follow the given time zone
+    let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+    let rows = time_by_project_day(&records[..2], &tz, at("2024-01-01T23:30:00Z"));
+    assert_eq!(rows.len(), 1);
+    assert_eq!((rows[0].day.as_str(), rows[0].processing_secs), ("2024-01-02", 90 * 60));
+}
+
+#[test]
+fn test_time_by_project_day_across_app_restarts() {
+    let records = vec![
+        record("2024-01-01T09:00:00Z", None, ActivityKind::AppStarted),
+        started("2024-01-01T09:00:00Z", "s1", "api", SessionStatus::Processing),
+        record("2024-01-01T09:10:00Z", Some("s1"), ActivityKind::Tokens { input_tokens: 100, output_tokens: 10 }),
+        // The app crashed; the last record of the run ends the interval
+        record("2024-01-01T12:00:00Z", None, ActivityKind::AppStarted),
+        started("2024-01-01T12:00:00Z", "s1", "api", SessionStatus::Waiting),
+        record("2024-01-01T12:05:00Z", Some("s1"), ActivityKind::Tokens { input_tokens: 300, output_tokens: 30 }),
+        record("2024-01-01T12:20:00Z", None, ActivityKind::AppStopped),
+    ];
+
+    let rows = time_by_project_day(&records, &Utc, at("2024-01-01T18:00:00Z"));
+    assert_eq!(rows.len(), 1);
+    assert_eq!((rows[0].processing_secs, rows[0].waiting_secs), (10 * 60, 20 * 60));
+    // Totals carry over the restart, so only the growth is counted
+    assert_eq!((rows[0].input_tokens, rows[0].output_tokens), (300, 30));
+}
+
+#[test]
+fn test_projects_with_the_same_name_are_kept_apart() {
+    let records = vec![
+        started_in("2024-01-01T09:00:00Z", "s1", "/work/api", SessionStatus::Processing),
+        started_in("2024-01-01T09:00:00Z", "s2", "/personal/api", SessionStatus::Processing),
+        // Written before logs had project paths
+        record("2024-01-01T09:00:00Z", Some("s3"), ActivityKind::SessionStarted {
+            project: "api".to_string(),
+            project_path: None,
+            agent_type: AgentType::Claude,
+            status: SessionStatus::Processing,
+        }),
+    ];
+
+    let rows = time_by_project_day(&records, &Utc, at("2024-01-01T10:00:00Z"));
+    assert_eq!(rows.iter().map(|r| (r.project.as_str(), r.project_path.as_str(), r.processing_secs)).collect::<Vec<_>>(),
+        vec![("api", "/personal/api", 3600), ("api", "/work/api", 3600), ("api", "api", 3600)]);
+    assert_eq!(sessions_per_week(&records, &Utc)[0].project_count, 3);
+}
+
+#[test]
+fn test_sessions_per_week() {
+    let records = vec![
+        started("2024-01-01T09:00:00Z", "s1", "api", SessionStatus::Processing),
+        started("2024-01-03T09:00:00Z", "s2", "api", SessionStatus::Processing),
+        started("2024-01-07T23:00:00Z", "s3", "web", SessionStatus::Processing),
+        // Seen again after an app restart, not a new session
+        started("2024-01-08T09:00:00Z", "s1", "api", SessionStatus::Waiting),
+        started("2024-01-09T09:00:00Z", "s4", "web", SessionStatus::Processing),
+    ];
+
+    let weeks = sessions_per_week(&records, &Utc);
+    assert_eq!(weeks.iter().map(|w| (w.week_start.as_str(), w.sessions_started, w.project_count)).collect::<Vec<_>>(),
+        vec![("2024-01-01", 3, 2), ("2024-01-08", 1, 1)]);
+    assert_eq!(weekly_sessions_csv(&weeks), "week_start,sessions_started,project_count\n2024-01-01,3,2\n2024-01-08,1,1\n");
+}
+
+#[test]
+fn test_last_weeks_are_calendar_weeks() {
+    let records = vec![
+        started("2023-11-06T09:00:00Z", "s1", "api", SessionStatus::Processing),
+        started("2023-11-13T09:00:00Z", "s2", "api", SessionStatus::Processing),
+        // Nothing for weeks, then one session last week
+        started("2024-01-02T09:00:00Z", "s3", "web", SessionStatus::Processing),
+    ];
+    let weeks = sessions_per_week(&records, &Utc);
+    let today = chrono::NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+
+    let last_four = last_weeks(weeks.clone(), today, 4);
+    assert_eq!(last_four.iter().map(|w| w.week_start.as_str()).collect::<Vec<_>>(), vec!["2024-01-01"]);
+    assert!(last_weeks(weeks.clone(), today, 1).is_empty());
+    assert_eq!(last_weeks(weeks, today, 9).len(), 2);
+}
+
+#[test]
+fn test_project_time_csv() {
+    let rows = vec![ProjectDayActivity {
+        day: "2024-01-01".to_string(),
+        project: "api, v2".to_string(),
+        project_path: "/src/api, v2".to_string(),
+        processing_secs: 5400,
+        waiting_secs: 900,
+        idle_secs: 0,
+        input_tokens: 1000,
+        output_tokens: 50,
+    }];
+    assert_eq!(project_time_csv(&rows),
+        "day,project,project_path,processing_hours,waiting_hours,idle_hours,input_tokens,output_tokens\n2024-01-01,\"api, v2\",\"/src/api, v2\",1.50,0.25,0.00,1000,50\n");
+}
@@ -0,0 +1,131 @@
+use crate::session::binding::{retain_seen_transcripts, transcript_info, ProcessSignals, TranscriptBinder, TranscriptBinding, TranscriptInfo};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// Helper functions
+
+fn process(pid: u32, start_time: u64) -> ProcessSignals {
+    ProcessSignals {
+        pid,
+        start_time,
+        ..Default::default()
+    }
+}
+
+fn transcript(name: &str, started_at: i64, modified_at: i64) -> TranscriptInfo {
+    TranscriptInfo {
+        path: PathBuf::from(format!("/projects/-tmp-project/{}.jsonl", name)),
+        started_at: Some(started_at),
+        modified_at: Some(modified_at),
+        starts_with_clear: false,
+    }
+}
+
+fn bound_name(bindings: &HashMap<u32, TranscriptBinding>, pid: u32) -> Option<String> {
+    bindings.get(&pid)
+        .and_then(|b| b.path.file_stem())
+        .map(|s| s.to_string_lossy().to_string())
+}
+
+#[test]
+fn test_bind_by_start_time_ignores_activity_order() {
+    let mut binder = TranscriptBinder::new();
+    let processes = [process(1, 1000), process(2, 2000)];
+    // The older session was active more recently, so it is listed first
+    let files = [transcript("older", 1010, 3000), transcript("newer", 2005, 2500)];
+
+    let result = binder.bind(&processes, &files);
+    assert_eq!(bound_name(&result, 1).as_deref(), Some("older"));
+    assert_eq!(bound_name(&result, 2).as_deref(), Some("newer"));
+    assert!(!result[&1].ambiguous);
+    assert!(!result[&2].ambiguous);
+}
+
+#[test]
+fn test_binding_is_sticky_for_the_process_lifetime() {
+    let mut binder = TranscriptBinder::new();
+    let processes = [process(1, 1000), process(2, 2000)];
+    let files = [transcript("a", 1010, 3000), transcript("b", 2005, 2500)];
+    binder.bind(&processes, &files);
+
+    // Activity order flips - bindings must not swap
+    let files = [transcript("b", 2005, 4000), transcript("a", 1010, 3000)];
+    let result = binder.bind(&processes, &files);
+    assert_eq!(bound_name(&result, 1).as_deref(), Some("a"));
+    assert_eq!(bound_name(&result, 2).as_deref(), Some("b"));
+
+    // A reused pid with a different start time is a different process
+    let result = binder.bind(&[process(1, 5000)], &[transcript("a", 1010, 3000), transcript("c", 5001, 5002)]);
+    assert_eq!(bound_name(&result, 1).as_deref(), Some("c"));
+}
+
+#[test]
+fn test_bind_by_resume_argument_and_open_files() {
+    let mut binder = TranscriptBinder::new();
+    let resumed = ProcessSignals {
+        cli_session_id: Some("resumed".to_string()),
+        ..process(1, 5000)
+    };
+    let writing = ProcessSignals {
+        open_files: vec![PathBuf::from("/projects/-tmp-project/open.jsonl")],
+        ..process(2, 100)
+    };
+    // Neither transcript started after its process, so start time can't tell them apart
+    let files = [transcript("open", 50, 6000), transcript("resumed", 10, 5500)];
+
+    let result = binder.bind(&[resumed, writing], &files);
+    assert_eq!(bound_name(&result, 1).as_deref(), Some("resumed"));
+    assert_eq!(bound_name(&result, 2).as_deref(), Some("open"));
+    assert!(!result[&1].ambiguous);
+}
+
+#[test]
+fn test_clear_rolls_over_to_new_transcript() {
+    let mut binder = TranscriptBinder::new();
+    let processes = [process(1, 1000), process(2, 1500)];
+    binder.bind(&processes, &[transcript("first", 1010, 2000), transcript("other", 1505, 2500)]);
+
+    // Process 1 ran /clear after its transcript went quiet at 2000
+    let cleared = TranscriptInfo {
+        starts_with_clear: true,
+        ..transcript("cleared", 2100, 2200)
+    };
+    let files = [transcript("other", 1505, 2500), cleared, transcript("first", 1010, 2000)];
+    let result = binder.bind(&processes, &files);
+    assert_eq!(bound_name(&result, 1).as_deref(), Some("cleared"));
+    assert_eq!(bound_name(&result, 2).as_deref(), Some("other"));
+}
+
+#[test]
+fn test_fallback_without_signals_is_flagged_ambiguous() {
+    let mut binder = TranscriptBinder::new();
+    // Both processes resumed old transcripts, nothing identifies which is which
+    let processes = [process(1, 5000), process(2, 6000)];
+    let files = [transcript("x", 100, 7000), transcript("y", 200, 6500)];
+
+    let result = binder.bind(&processes, &files);
+    assert_eq!(result.len(), 2);
+    assert!(result[&1].ambiguous);
+    assert!(result[&2].ambiguous);
+    assert_ne!(result[&1].path, result[&2].path);
+
+    // A single process with a single transcript is not a guess
+    let mut binder = TranscriptBinder::new();
+    let result = binder.bind(&[process(3, 5000)], &[transcript("z", 100, 7000)]);
+    assert!(!result[&3].ambiguous);
+}
+
+#[test]
+fn test_transcript_starts_are_forgotten_once_unseen() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("session.jsonl");
+    let entry = |at: &str| format!("{{\"type\":\"user\",\"timestamp\":\"{}\"}}\n", at);
+    std::fs::write(&path, entry("2024-01-01T10:00:00Z")).unwrap();
+    assert_eq!(transcript_info(&path).started_at, Some(1704103200));
+
+    // Seen by this poll, then by none: the start is read again when the transcript comes back
+    retain_seen_transcripts();
+    retain_seen_transcripts();
+    std::fs::write(&path, entry("2024-01-01T11:00:00Z")).unwrap();
+    assert_eq!(transcript_info(&path).started_at, Some(1704106800));
+}
@@ -0,0 +1,201 @@
+use crate::agent::opencode::load_opencode_transcript;
+use crate::export::claude::load_claude_transcript;
+use crate::export::{apply_options, render, ExportFormat, ExportOptions, MessageRole, Transcript, TranscriptBlock};
+use serde_json::json;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use tempfile::{tempdir, NamedTempFile};
+
+// Helper functions
+
+fn write_jsonl(lines: &[serde_json::Value]) -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    for line in lines {
+        writeln!(file, "{}", line).unwrap();
+    }
+    file
+}
+
+fn assistant_entry(message_id: &str, content: serde_json::Value, timestamp: &str) -> serde_json::Value {
+    json!({
+        "type": "assistant",
+        "cwd": "/home/user/project",
+        "gitBranch": "main",
+        "timestamp": timestamp,
+        "message": {
+            "id": message_id,
+            "role": "assistant",
+            "model": "claude-sonnet-4",
+            "content": [content],
+            "usage": {"input_tokens": 10, "cache_read_input_tokens": 90, "output_tokens": 5},
+        },
+    })
+}
+
+/// A transcript with one Bash call and its (long) result
+fn tool_transcript(output: &str) -> Transcript {
+    let call = crate::export::TranscriptMessage {
+        role: MessageRole::Assistant,
+        timestamp: Some("2024-01-01T10:00:00Z".to_string()),
+        blocks: vec![
+            TranscriptBlock::Thinking { text: "Let me look".to_string() },
+            TranscriptBlock::ToolUse { id: "t1".to_string(), name: "Bash".to_string(), input: json!({"command": "ls <dir>"}) },
+        ],
+    };
+    let result = crate::export::TranscriptMessage {
+        role: MessageRole::User,
+        timestamp: Some("2024-01-01T10:00:01Z".to_string()),
+        blocks: vec![TranscriptBlock::ToolResult { tool_use_id: "t1".to_string(), output: output.to_string(), is_error: false, omitted_chars: 0 }],
+    };
+    Transcript {
+        session_id: "export-session".to_string(),
+        agent_type: crate::session::AgentType::Claude,
+        title: Some("Fix the build".to_string()),
+        project_path: Some("/home/user/project".to_string()),
+        git_branch: Some("main".to_string()),
+        model: Some("claude-sonnet-4".to_string()),
+        started_at: Some("2024-01-01T10:00:00Z".to_string()),
+        ended_at: Some("2024-01-01T11:30:00Z".to_string()),
+        input_tokens: 1500,
+        output_tokens: 20,
+        messages: vec![call, result],
+    }
+}
+
+fn options(format: ExportFormat, include_thinking: bool) -> ExportOptions {
+    ExportOptions { format, include_thinking, max_tool_output_chars: 10 }
+}
+
+fn write_json(path: &Path, value: serde_json::Value) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, value.to_string()).unwrap();
+}
+
+// Tests for reading Claude Code transcripts
+
+#[test]
+fn test_load_claude_transcript_merges_response_entries() {
+    let file = write_jsonl(&[
+        json!({"type": "user", "cwd": "/home/user/project", "timestamp": "2024-01-01T10:00:00Z", "message": {"role": "user", "content": "Fix the build"}}),
+        json!({"type": "user", "isMeta": true, "timestamp": "2024-01-01T10:00:00Z", "message": {"role": "user", "content": "<local-command-caveat>"}}),
+        assistant_entry("msg_1", json!({"type": "thinking", "thinking": "Check the logs"}), "2024-01-01T10:00:01Z"),
+        assistant_entry("msg_1", json!({"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": "cargo build"}}), "2024-01-01T10:00:02Z"),
+        json!({"type": "assistant", "isSidechain": true, "timestamp": "2024-01-01T10:00:03Z", "message": {"id": "msg_side", "role": "assistant", "content": [{"type": "text", "text": "subagent"}]}}),
+        json!({"type": "user", "timestamp": "2024-01-01T10:00:04Z", "message": {"role": "user", "content": [{"type": "tool_result", "tool_use_id": "t1", "content": [{"type": "text", "text": "error[E0432]"}], "is_error": true}]}}),
+        assistant_entry("msg_2", json!({"type": "text", "text": "Fixed."}), "2024-01-01T10:00:05Z"),
+    ]);
+
+    let transcript = load_claude_transcript("claude-export", file.path()).unwrap();
+
+    assert_eq!(transcript.project_path.as_deref(), Some("/home/user/project"));
+    assert_eq!(transcript.git_branch.as_deref(), Some("main"));
+    assert_eq!(transcript.model.as_deref(), Some("claude-sonnet-4"));
+    assert_eq!(transcript.started_at.as_deref(), Some("2024-01-01T10:00:00Z"));
+    assert_eq!(transcript.ended_at.as_deref(), Some("2024-01-01T10:00:05Z"));
+    // msg_1 is written twice but counted once
+    assert_eq!(transcript.input_tokens, 200);
+    assert_eq!(transcript.output_tokens, 10);
+
+    let roles: Vec<MessageRole> = transcript.messages.iter().map(|m| m.role).collect();
+    assert_eq!(roles, vec![MessageRole::User, MessageRole::Assistant, MessageRole::User, MessageRole::Assistant]);
+    assert_eq!(transcript.messages[1].blocks.len(), 2);
+    assert_eq!(
+        transcript.messages[2].blocks,
+        vec![TranscriptBlock::ToolResult { tool_use_id: "t1".to_string(), output: "error[E0432]".to_string(), is_error: true, omitted_chars: 0 }]
+    );
+}
+
+// Tests for export options
+
+#[test]
+fn test_apply_options_drops_thinking_and_truncates_output() {
+    let transcript = apply_options(tool_transcript("0123456789abcdef"), &options(ExportFormat::Json, false));
+
+    assert!(!transcript.messages[0].blocks.iter().any(|b| matches!(b, TranscriptBlock::Thinking { .. })));
+    assert_eq!(
+        transcript.messages[1].blocks[0],
+        TranscriptBlock::ToolResult { tool_use_id: "t1".to_string(), output: "0123456789".to_string(), is_error: false, omitted_chars: 6 }
+    );
+
+    let unlimited = ExportOptions { max_tool_output_chars: 0, ..options(ExportFormat::Json, true) };
+    let transcript = apply_options(tool_transcript("0123456789abcdef"), &unlimited);
+    assert_eq!(transcript.messages[0].blocks.len(), 2);
+    assert!(matches!(&transcript.messages[1].blocks[0], TranscriptBlock::ToolResult { output, .. } if output == "0123456789abcdef"));
+}
+
+#[test]
+fn test_json_export_round_trips() {
+    let json = render(tool_transcript("ok"), &options(ExportFormat::Json, true)).unwrap();
+    let parsed: Transcript = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed, tool_transcript("ok"));
+    assert!(json.contains("\"toolUseId\": \"t1\""));
+}
+
+// Tests for the Markdown and HTML renderers
+
+#[test]
+fn test_markdown_export_nests_results_in_collapsed_tool_calls() {
+    let unlimited = ExportOptions { max_tool_output_chars: 0, ..options(ExportFormat::Markdown, true) };
+    let markdown = render(tool_transcript("```\nfenced\n```"), &unlimited).unwrap();
+
+    assert!(markdown.starts_with("# Fix the build\n"));
+    assert!(markdown.contains("| Branch | main |"));
+    assert!(markdown.contains("| Tokens | 1.5k in / 20 out |"));
+    assert!(markdown.contains("(1h 30m)"));
+    assert!(markdown.contains("<summary>Thinking</summary>"));
+    assert!(markdown.contains("<summary>Bash: ls &lt;dir&gt;</summary>"));
+    // The output's own fence can't close the block around it
+    assert!(markdown.contains("````\n```\nfenced\n```\n````"));
+    // The result is shown inside its call, not as a message of its own
+    assert_eq!(markdown.matches("\n## ").count(), 1);
+}
+
+#[test]
+fn test_html_export_escapes_content() {
+    let html = render(tool_transcript("<script>alert(1)</script>0123"), &options(ExportFormat::Html, false)).unwrap();
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("&lt;script&gt;al</pre>"));
+    assert!(html.contains("more characters not shown"));
+    assert!(!html.contains("Thinking"));
+}
+
+// Tests for reading OpenCode storage
+
+#[test]
+fn test_load_opencode_transcript() {
+    let storage = tempdir().unwrap();
+    let root = storage.path();
+    write_json(&root.join("session/proj1/ses_1.json"), json!({"id": "ses_1", "projectID": "proj1", "directory": "/home/user/app", "title": "Add login"}));
+    write_json(&root.join("message/ses_1/msg_a.json"), json!({"id": "msg_a", "sessionID": "ses_1", "role": "user", "time": {"created": 1704103200000u64}}));
+    write_json(&root.join("message/ses_1/msg_b.json"), json!({
+        "id": "msg_b", "sessionID": "ses_1", "role": "assistant", "modelID": "gpt-5",
+        "time": {"created": 1704103201000u64, "completed": 1704103260000u64},
+        "tokens": {"input": 100, "output": 20, "reasoning": 5, "cache": {"read": 50, "write": 0}},
+    }));
+    write_json(&root.join("part/msg_a/prt_1.json"), json!({"id": "prt_1", "type": "text", "text": "Add a login page"}));
+    write_json(&root.join("part/msg_b/prt_2.json"), json!({"id": "prt_2", "type": "reasoning", "text": "Plan it"}));
+    write_json(&root.join("part/msg_b/prt_3.json"), json!({
+        "id": "prt_3", "type": "tool", "tool": "bash", "callID": "call_1",
+        "state": {"status": "completed", "input": {"command": "ls"}, "output": "src"},
+    }));
+
+    let transcript = load_opencode_transcript(root, "ses_1").unwrap();
+
+    assert_eq!(transcript.title.as_deref(), Some("Add login"));
+    assert_eq!(transcript.project_path.as_deref(), Some("/home/user/app"));
+    assert_eq!(transcript.model.as_deref(), Some("gpt-5"));
+    assert_eq!((transcript.input_tokens, transcript.output_tokens), (150, 25));
+    assert_eq!(transcript.started_at.as_deref(), Some("2024-01-01T10:00:00.000Z"));
+    assert_eq!(transcript.messages.len(), 2);
+    assert_eq!(transcript.messages[1].blocks, vec![
+        TranscriptBlock::Thinking { text: "Plan it".to_string() },
+        TranscriptBlock::ToolUse { id: "call_1".to_string(), name: "bash".to_string(), input: json!({"command": "ls"}) },
+        TranscriptBlock::ToolResult { tool_use_id: "call_1".to_string(), output: "src".to_string(), is_error: false, omitted_chars: 0 },
+    ]);
+
+    assert!(load_opencode_transcript(root, "ses_missing").is_err());
+}
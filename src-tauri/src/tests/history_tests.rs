@@ -0,0 +1,84 @@
+use crate::agent::get_resume_command;
+use crate::session::history::parse_history_file;
+use crate::session::AgentType;
+use crate::terminal::shell_quote;
+use std::io::Write;
+use tempfile::TempDir;
+
+fn write_transcript(dir: &TempDir, name: &str, lines: &[&str]) -> std::path::PathBuf {
+    let path = dir.path().join(name);
+    let mut file = std::fs::File::create(&path).unwrap();
+    for line in lines {
+        writeln!(file, "{}", line).unwrap();
+    }
+    path
+}
+
+#[test]
+fn test_parse_history_file_extracts_metadata() {
+    let dir = TempDir::new().unwrap();
+    let path = write_transcript(&dir, "abc-123.jsonl", &[
+        r#"{"type":"summary","summary":"Old summary","leafUuid":"x"}"#,
+        r#"{"sessionId":"abc-123","type":"user","cwd":"/Users/test/Projects/app","gitBranch":"main","message":{"role":"user","content":"<command-name>/clear</command-name>"},"timestamp":"2024-01-01T00:00:00Z"}"#,
+        r#"{"sessionId":"abc-123","type":"user","cwd":"/Users/test/Projects/app","gitBranch":"main","message":{"role":"user","content":"Fix the login bug"},"timestamp":"2024-01-01T00:00:01Z"}"#,
+        r#"{"sessionId":"abc-123","type":"assistant","gitBranch":"feature/login","message":{"role":"assistant","content":[{"type":"text","text":"On it"}]},"timestamp":"2024-01-01T00:00:02Z"}"#,
+        r#"{"type":"summary","summary":"Login bug fix","leafUuid":"y"}"#,
+    ]);
+
+    let session = parse_history_file(&path, "-Users-test-Projects-app").unwrap();
+
+    assert_eq!(session.id, "abc-123");
+    assert_eq!(session.agent_type, AgentType::Claude);
+    assert_eq!(session.project_path, "/Users/test/Projects/app");
+    assert_eq!(session.first_prompt.as_deref(), Some("Fix the login bug"));
+    assert_eq!(session.title.as_deref(), Some("Login bug fix"));
+    assert_eq!(session.git_branch.as_deref(), Some("feature/login"));
+    assert_eq!(session.last_activity_at, "2024-01-01T00:00:02Z");
+    assert_eq!(session.message_count, 3);
+    assert!(session.size_bytes > 0);
+}
+
+#[test]
+fn test_parse_history_file_skips_summary_only_transcripts() {
+    let dir = TempDir::new().unwrap();
+    let path = write_transcript(&dir, "summaries.jsonl", &[
+        r#"{"type":"summary","summary":"Something","leafUuid":"x"}"#,
+    ]);
+
+    assert!(parse_history_file(&path, "-Users-test-Projects-app").is_none());
+}
+
+#[test]
+fn test_parse_history_file_falls_back_to_dir_name_for_path() {
+    let dir = TempDir::new().unwrap();
+    let path = write_transcript(&dir, "no-cwd.jsonl", &[
+        r#"{"sessionId":"no-cwd","type":"user","message":{"role":"user","content":[{"type":"text","text":"Hello"}]},"timestamp":"2024-01-01T00:00:00Z"}"#,
+    ]);
+
+    let session = parse_history_file(&path, "-Users-test-Projects-my-app").unwrap();
+    assert_eq!(session.project_path, "/Users/test/Projects/my-app");
+    assert_eq!(session.first_prompt.as_deref(), Some("Hello"));
+}
+
+#[test]
+fn test_get_resume_command() {
+    assert_eq!(
+        get_resume_command(&AgentType::Claude, "0b1c2d3e-aaaa-bbbb").unwrap(),
+        "claude --resume 0b1c2d3e-aaaa-bbbb"
+    );
+    assert_eq!(
+        get_resume_command(&AgentType::OpenCode, "ses_abc123").unwrap(),
+        "opencode --session ses_abc123"
+    );
+
+    // Ids end up in a shell command line, so anything unusual is rejected
+    assert!(get_resume_command(&AgentType::Claude, "").is_err());
+    assert!(get_resume_command(&AgentType::Claude, "abc; rm -rf ~").is_err());
+    assert!(get_resume_command(&AgentType::Claude, "$(whoami)").is_err());
+}
+
+#[test]
+fn test_shell_quote() {
+    assert_eq!(shell_quote("/Users/test/my project"), "'/Users/test/my project'");
+    assert_eq!(shell_quote("/tmp/it's"), r"'/tmp/it'\''s'");
+}
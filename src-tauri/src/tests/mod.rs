@@ -1,2 +1,12 @@
+mod activity_tests;
+mod audit_tests;
+mod binding_tests;
+mod export_tests;
+mod git_tests;
+mod history_tests;
+mod policy_tests;
 mod process_tests;
+mod project_dir_tests;
+mod remote_tests;
+mod secrets_tests;
 mod session_tests;
@@ -0,0 +1,232 @@
+use crate::policy::{
+    apply_actions, get_policy_actions, load_config, oldest_running_tool, parse_config, PolicyActionKind,
+    PolicyCondition, PolicyEngine, PolicyInput, PolicyRule, PolicyTarget,
+};
+use crate::process::ChildProcess;
+use crate::session::audit::{parse_outcome, AuditEntry, AuditKind};
+use crate::session::events::{recent_events, SessionEventKind};
+use crate::session::{parse_session_file, AgentType, Session, SessionStatus};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+// Helper functions
+
+fn session(id: &str, status: SessionStatus, last_activity_at: &str) -> Session {
+    let mut jsonl = NamedTempFile::new().unwrap();
+    writeln!(
+        jsonl,
+        r#"{{"sessionId":"{}","type":"assistant","message":{{"role":"assistant","content":[{{"type":"text","text":"Done"}}]}},"timestamp":"{}"}}"#,
+        id, last_activity_at
+    ).unwrap();
+    jsonl.flush().unwrap();
+    let mut session = parse_session_file(&jsonl.path().to_path_buf(), "/tmp/policy-project", 12345, 0.0, AgentType::Claude).unwrap();
+    session.status = status;
+    session.last_activity_at = last_activity_at.to_string();
+    session
+}
+
+fn rule(id: &str, when: PolicyCondition, action: PolicyActionKind) -> PolicyRule {
+    PolicyRule { id: id.to_string(), when, action, cooldown_minutes: 60, dry_run: false }
+}
+
+fn child(pid: u32, name: &str) -> ChildProcess {
+    ChildProcess { pid, parent_pid: 12345, name: name.to_string(), start_time: 1000 + pid as u64, ..Default::default() }
+}
+
+fn evaluate(
+    engine: &mut PolicyEngine,
+    rules: &[PolicyRule],
+    sessions: &[Session],
+    now: DateTime<Utc>,
+    running_tools: &HashMap<String, (String, DateTime<Utc>)>,
+    is_running: &dyn Fn(u32, u64) -> bool,
+) -> Vec<(String, String)> {
+    let input = PolicyInput { sessions, now, running_tools, is_running };
+    engine.evaluate(rules, &input)
+        .into_iter()
+        .map(|a| (a.rule_id, a.session_id))
+        .collect()
+}
+
+fn noon() -> DateTime<Utc> {
+    "2024-01-01T12:00:00Z".parse().unwrap()
+}
+
+// Configuration
+
+#[test]
+fn test_parse_policy_config() {
+    let config = parse_config(r#"{
+        "dryRun": true,
+        "rules": [
+            {"id": "idle", "when": {"type": "idleFor", "minutes": 720}, "action": "terminate"},
+            {"id": "memory", "when": {"type": "treeMemoryAbove", "megabytes": 8192}, "action": "pause", "cooldownMinutes": 5},
+            {"id": "memory", "when": {"type": "toolRunningFor", "minutes": 20}, "action": "notify"},
+            {"id": "orphans", "when": {"type": "orphanedChildren"}, "action": "pause"},
+            {"id": "slow-tool", "when": {"type": "toolRunningFor", "minutes": 20}, "action": "notify", "dryRun": true}
+        ]
+    }"#).unwrap();
+
+    assert!(config.dry_run);
+    // The duplicate id and the rule that would pause orphans are dropped
+    let ids: Vec<&str> = config.rules.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, vec!["idle", "memory", "slow-tool"]);
+    assert_eq!(config.rules[0].when, PolicyCondition::IdleFor { minutes: 720 });
+    assert_eq!(config.rules[0].cooldown_minutes, 60);
+    assert_eq!(config.rules[1].cooldown_minutes, 5);
+    assert!(config.rules[2].dry_run);
+
+    assert!(parse_config(r#"{"rules": [{"id": "x", "when": {"type": "never"}, "action": "notify"}]}"#).is_err());
+
+    // No policy file means no rules
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(load_config(&dir.path().join("policies.json")).unwrap().rules, vec![]);
+}
+
+// Rules
+
+#[test]
+fn test_idle_rule_respects_cooldown() {
+    let mut engine = PolicyEngine::new();
+    let rules = [rule("idle", PolicyCondition::IdleFor { minutes: 720 }, PolicyActionKind::Terminate)];
+    let sessions = vec![
+        session("policy-idle", SessionStatus::Idle, "2023-12-31T23:00:00Z"),
+        session("policy-recent", SessionStatus::Waiting, "2024-01-01T11:00:00Z"),
+        // Busy sessions aren't idle however old their last transcript entry is
+        session("policy-busy", SessionStatus::Processing, "2023-12-31T23:00:00Z"),
+    ];
+    let tools = HashMap::new();
+
+    let planned = evaluate(&mut engine, &rules, &sessions, noon(), &tools, &|_, _| true);
+    assert_eq!(planned, vec![("idle".to_string(), "policy-idle".to_string())]);
+
+    // Within the cooldown nothing happens again
+    assert!(evaluate(&mut engine, &rules, &sessions, noon() + Duration::minutes(30), &tools, &|_, _| true).is_empty());
+    assert_eq!(evaluate(&mut engine, &rules, &sessions, noon() + Duration::minutes(60), &tools, &|_, _| true).len(), 1);
+}
+
+#[test]
+fn test_memory_rule_skips_paused_sessions() {
+    let mut engine = PolicyEngine::new();
+    let rules = [rule("memory", PolicyCondition::TreeMemoryAbove { megabytes: 8192 }, PolicyActionKind::Pause)];
+    let mut large = session("policy-large", SessionStatus::Processing, "2024-01-01T11:59:00Z");
+    large.tree_memory = 9 * 1024 * 1024 * 1024;
+    let mut paused = large.clone();
+    paused.id = "policy-large-paused".to_string();
+    paused.status = SessionStatus::Paused;
+    let mut small = session("policy-small", SessionStatus::Processing, "2024-01-01T11:59:00Z");
+    small.tree_memory = 1024 * 1024 * 1024;
+
+    let planned = evaluate(&mut engine, &rules, &[large, paused, small], noon(), &HashMap::new(), &|_, _| true);
+    assert_eq!(planned, vec![("memory".to_string(), "policy-large".to_string())]);
+}
+
+#[test]
+fn test_tool_rule_reports_each_long_tool() {
+    let mut engine = PolicyEngine::new();
+    let rules = [rule("slow-tool", PolicyCondition::ToolRunningFor { minutes: 20 }, PolicyActionKind::Notify)];
+    let sessions = vec![session("policy-tool", SessionStatus::Processing, "2024-01-01T11:30:00Z")];
+
+    let mut tools = HashMap::from([("policy-tool".to_string(), ("npm test".to_string(), noon() - Duration::minutes(10)))]);
+    assert!(evaluate(&mut engine, &rules, &sessions, noon(), &tools, &|_, _| true).is_empty());
+    assert_eq!(evaluate(&mut engine, &rules, &sessions, noon() + Duration::minutes(10), &tools, &|_, _| true).len(), 1);
+
+    // A different long-running tool isn't held back by the previous one's cooldown
+    let later = noon() + Duration::minutes(40);
+    tools.insert("policy-tool".to_string(), ("cargo build".to_string(), later - Duration::minutes(25)));
+    assert_eq!(evaluate(&mut engine, &rules, &sessions, later, &tools, &|_, _| true).len(), 1);
+}
+
+#[test]
+fn test_orphaned_children_of_ended_sessions() {
+    let mut engine = PolicyEngine::new();
+    let rules = [rule("orphans", PolicyCondition::OrphanedChildren, PolicyActionKind::Terminate)];
+    let tools = HashMap::new();
+    let mut live = session("policy-orphans", SessionStatus::Processing, "2024-01-01T11:59:00Z");
+    live.child_processes = vec![child(2001, "node"), child(2002, "vite")];
+
+    // Children of live sessions are left alone
+    assert!(evaluate(&mut engine, &rules, std::slice::from_ref(&live), noon(), &tools, &|_, _| true).is_empty());
+
+    // Once the session ends, its children that are still running are acted on
+    let still_running = |pid: u32, start_time: u64| pid == 2002 && start_time == 3002;
+    let input = PolicyInput { sessions: &[], now: noon(), running_tools: &tools, is_running: &still_running };
+    let planned = engine.evaluate(&rules, &input);
+    assert_eq!(planned.len(), 1);
+    assert_eq!(planned[0].session_id, "policy-orphans");
+    assert!(matches!(&planned[0].target, PolicyTarget::Process(p) if p.pid == 2002));
+
+    // Exited orphans are forgotten
+    assert!(evaluate(&mut engine, &rules, &[], noon() + Duration::hours(2), &tools, &|_, _| false).is_empty());
+}
+
+#[test]
+fn test_children_kept_while_agent_runs_without_session() {
+    let mut engine = PolicyEngine::new();
+    let rules = [rule("orphans", PolicyCondition::OrphanedChildren, PolicyActionKind::Terminate)];
+    let tools = HashMap::new();
+    let mut live = session("policy-unbound", SessionStatus::Processing, "2024-01-01T11:59:00Z");
+    live.process_started_at = Some("2024-01-01T10:00:00Z".to_string());
+    live.child_processes = vec![child(2003, "cargo")];
+    assert!(evaluate(&mut engine, &rules, std::slice::from_ref(&live), noon(), &tools, &|_, _| true).is_empty());
+
+    // The session is missing from a poll, but its agent is still running
+    assert!(evaluate(&mut engine, &rules, &[], noon() + Duration::minutes(1), &tools, &|_, _| true).is_empty());
+    assert!(evaluate(&mut engine, &rules, &[], noon() + Duration::minutes(2), &tools, &|_, _| true).is_empty());
+
+    // Only once the agent exits are its children orphaned
+    let agent_exited = |pid: u32, _: u64| pid != 12345;
+    let planned = evaluate(&mut engine, &rules, &[], noon() + Duration::minutes(3), &tools, &agent_exited);
+    assert_eq!(planned, vec![("orphans".to_string(), "policy-unbound".to_string())]);
+}
+
+#[test]
+fn test_running_tool_ignores_calls_that_never_finished() {
+    let entry = |id: &str, detail: &str, at: &str, finished: bool| AuditEntry {
+        tool_use_id: id.to_string(),
+        kind: AuditKind::Command,
+        detail: detail.to_string(),
+        at: at.to_string(),
+        line_number: 1,
+        outcome: finished.then(|| parse_outcome(false, "")),
+        matched_rules: Vec::new(),
+    };
+
+    // An interrupted call left without a result, then later calls that finished
+    let mut entries = vec![
+        entry("toolu_1", "npm test", "2024-01-01T09:00:00Z", false),
+        entry("toolu_2", "git status", "2024-01-01T11:00:00Z", true),
+    ];
+    assert_eq!(oldest_running_tool(entries.clone()), None);
+
+    // Calls made together with the last finished one, or after it, are running
+    entries.push(entry("toolu_3", "cargo build", "2024-01-01T11:00:00Z", false));
+    entries.push(entry("toolu_4", "sleep 60", "2024-01-01T11:30:00Z", false));
+    let (detail, started) = oldest_running_tool(entries).unwrap();
+    assert_eq!(detail, "cargo build");
+    assert_eq!(started, "2024-01-01T11:00:00Z".parse::<DateTime<Utc>>().unwrap());
+}
+
+// Applying actions
+
+#[test]
+fn test_dry_run_records_without_acting() {
+    let mut engine = PolicyEngine::new();
+    let rules = [rule("idle-dry-run", PolicyCondition::IdleFor { minutes: 60 }, PolicyActionKind::Terminate)];
+    // Not a live session: carrying out the action would fail and record an error
+    let sessions = vec![session("policy-dry-run", SessionStatus::Idle, "2024-01-01T09:00:00Z")];
+    let input = PolicyInput { sessions: &sessions, now: noon(), running_tools: &HashMap::new(), is_running: &|_, _| true };
+    apply_actions(engine.evaluate(&rules, &input), true, noon());
+
+    let records: Vec<_> = get_policy_actions().into_iter().filter(|r| r.rule == "idle-dry-run").collect();
+    assert_eq!(records.len(), 1);
+    assert!(records[0].dry_run);
+    assert_eq!(records[0].error, None);
+    assert_eq!(records[0].reason, "no activity for 180 minutes");
+
+    let events: Vec<_> = recent_events(None).into_iter().filter(|e| e.session_id == "policy-dry-run").collect();
+    assert_eq!(events.len(), 1);
+    assert!(matches!(&events[0].kind, SessionEventKind::PolicyAction { action: PolicyActionKind::Terminate, dry_run: true, .. }));
+}
@@ -1,5 +1,11 @@
-use crate::process::{find_claude_processes, is_orphaned_process, ClaudeProcess};
-use std::path::PathBuf;
+use crate::process::container::{parse_cgroup_container, parse_environ_home, parse_mountinfo, passwd_home, translate_path, MountEntry};
+use crate::process::origin::{claude_arg_index, classify_origin, parse_environ_entrypoint, SessionOrigin};
+use crate::process::suspend::{continue_process_tree, stop_process_tree};
+use crate::process::terminate::{terminate_process_tree, verify_identity, ProcessIdentity, TerminateOptions};
+use crate::process::tree::{child_processes, descendant_pids, is_descendant, terminate_child};
+use crate::process::orphan::{is_orphaned, parse_stat, tty_device_path, ProcEntry, ProcTable};
+use crate::process::{find_claude_processes, is_orphaned_process, parse_claude_args, ClaudeProcess};
+use std::path::{Path, PathBuf};
 use sysinfo::{ProcessRefreshKind, RefreshKind, System};
 
 #[test]
@@ -9,6 +15,7 @@ fn test_claude_process_creation() {
         cwd: Some(PathBuf::from("/Users/test/Projects/my-project")),
         cpu_usage: 5.5,
         memory: 1024,
+        ..Default::default()
     };
 
     assert_eq!(process.pid, 12345);
@@ -27,6 +34,7 @@ fn test_claude_process_without_cwd() {
         cwd: None,
         cpu_usage: 0.0,
         memory: 0,
+        ..Default::default()
     };
 
     assert_eq!(process.pid, 99999);
@@ -40,6 +48,7 @@ fn test_claude_process_clone() {
         cwd: Some(PathBuf::from("/test/path")),
         cpu_usage: 10.0,
         memory: 2048,
+        ..Default::default()
     };
 
     let cloned = process.clone();
@@ -56,6 +65,7 @@ fn test_claude_process_serialization() {
         cwd: Some(PathBuf::from("/test/path")),
         cpu_usage: 5.5,
         memory: 1024,
+        ..Default::default()
     };
 
     let json = serde_json::to_string(&process).unwrap();
@@ -181,3 +191,508 @@ fn test_is_orphaned_process_with_launchd() {
         let _ = is_orphaned_process(&system, process);
     }
 }
+
+// Tests for orphan detection against scripted process trees
+
+const PTS_3: u32 = 34819; // /dev/pts/3: major 136, minor 3
+
+fn proc(pid: u32, ppid: u32, name: &str, session: u32, tty_nr: u32) -> ProcEntry {
+    ProcEntry { pid, ppid, name: name.to_string(), session, tty_nr }
+}
+
+fn table(entries: Vec<ProcEntry>) -> ProcTable {
+    entries.into_iter().map(|e| (e.pid, e)).collect()
+}
+
+fn pts_3_exists(tty_nr: u32) -> bool {
+    tty_nr == PTS_3
+}
+
+#[test]
+fn test_is_orphaned_healthy_terminal_session() {
+    // systemd --user → gnome-terminal-server → bash → claude
+    let tree = table(vec![
+        proc(1, 0, "systemd", 1, 0),
+        proc(900, 1, "systemd", 900, 0),
+        proc(2000, 900, "gnome-terminal-", 2000, 0),
+        proc(2100, 2000, "bash", 2100, PTS_3),
+        proc(2200, 2100, "claude", 2100, PTS_3),
+    ]);
+    assert!(!is_orphaned(&tree, 2200, pts_3_exists));
+}
+
+#[test]
+fn test_is_orphaned_tmux_pane_adopted_by_systemd_user() {
+    // tmux runs claude as the pane's session leader; the tmux server itself
+    // is a child of systemd --user, which is not a closed terminal
+    let tree = table(vec![
+        proc(900, 1, "systemd", 900, 0),
+        proc(3000, 900, "tmux: server", 3000, 0),
+        proc(3100, 3000, "claude", 3100, PTS_3),
+    ]);
+    assert!(!is_orphaned(&tree, 3100, pts_3_exists));
+}
+
+#[test]
+fn test_is_orphaned_terminal_device_gone() {
+    let tree = table(vec![
+        proc(2100, 900, "bash", 2100, PTS_3),
+        proc(2200, 2100, "claude", 2100, PTS_3),
+    ]);
+    assert!(is_orphaned(&tree, 2200, |_| false));
+}
+
+#[test]
+fn test_is_orphaned_session_leader_exited() {
+    // The shell that owned the terminal is gone, claude was adopted by a subreaper
+    let tree = table(vec![
+        proc(900, 1, "systemd", 900, 0),
+        proc(2200, 900, "claude", 2100, PTS_3),
+    ]);
+    assert!(is_orphaned(&tree, 2200, pts_3_exists));
+}
+
+#[test]
+fn test_is_orphaned_shell_adopted_by_systemd_user_after_hangup() {
+    // The terminal emulator died: the kernel detached the tty and
+    // systemd --user adopted the shell
+    let tree = table(vec![
+        proc(1, 0, "systemd", 1, 0),
+        proc(900, 1, "systemd", 900, 0),
+        proc(2100, 900, "bash", 2100, 0),
+        proc(2200, 2100, "claude", 2100, 0),
+    ]);
+    assert!(is_orphaned(&tree, 2200, pts_3_exists));
+}
+
+#[test]
+fn test_is_orphaned_nohup_with_live_shell() {
+    // nohup claude -p: no terminal and SIGHUP ignored, but the shell that started it is still open
+    let tree = table(vec![
+        proc(2000, 900, "kitty", 2000, 0),
+        proc(2100, 2000, "bash", 2100, PTS_3),
+        proc(2200, 2100, "claude", 2100, 0),
+    ]);
+    assert!(!is_orphaned(&tree, 2200, pts_3_exists));
+}
+
+#[test]
+fn test_is_orphaned_nohup_adopted_after_shell_exited() {
+    let tree = table(vec![
+        proc(1, 0, "systemd", 1, 0),
+        proc(900, 1, "systemd", 900, 0),
+        proc(2200, 900, "claude", 2100, 0),
+    ]);
+    assert!(is_orphaned(&tree, 2200, pts_3_exists));
+}
+
+#[test]
+fn test_is_orphaned_started_by_user_service() {
+    // An SDK app running as a user service, adopted by nothing: its parent is alive
+    let tree = table(vec![
+        proc(1, 0, "systemd", 1, 0),
+        proc(900, 1, "systemd", 900, 0),
+        proc(5000, 900, "python3", 5000, 0),
+        proc(5100, 5000, "claude", 5000, 0),
+    ]);
+    assert!(!is_orphaned(&tree, 5100, pts_3_exists));
+}
+
+#[test]
+fn test_is_orphaned_headless_without_terminal() {
+    // Started without a terminal by a live parent chain (e.g. a script under cron)
+    let tree = table(vec![
+        proc(1, 0, "systemd", 1, 0),
+        proc(500, 1, "cron", 500, 0),
+        proc(4000, 500, "cron", 500, 0),
+        proc(4100, 4000, "sh", 4100, 0),
+        proc(4200, 4100, "claude", 4100, 0),
+    ]);
+    assert!(!is_orphaned(&tree, 4200, pts_3_exists));
+}
+
+#[test]
+fn test_parse_stat() {
+    let stat = "2200 (my (odd) name) S 2100 2200 2100 34819 2200 4194560 0 0 0 0 0 0 0 0 20 0 1 0 100 0 0";
+    let entry = parse_stat(stat).unwrap();
+    assert_eq!(entry.pid, 2200);
+    assert_eq!(entry.ppid, 2100);
+    assert_eq!(entry.name, "my (odd) name");
+    assert_eq!(entry.session, 2100);
+    assert_eq!(entry.tty_nr, PTS_3);
+    assert!(parse_stat("garbage").is_none());
+}
+
+#[test]
+fn test_tty_device_path() {
+    // Pseudo-terminals are looked up in devpts, they have no /sys/dev/char entry
+    assert_eq!(tty_device_path(PTS_3), "/dev/pts/3");
+    // Minors above 255 are split around the major in tty_nr
+    assert_eq!(tty_device_path((136 << 8) | (1 << 20) | 4), "/dev/pts/260");
+    // Virtual consoles and serial ports
+    assert_eq!(tty_device_path((4 << 8) | 2), "/sys/dev/char/4:2");
+}
+
+// Tests for an agent's process subtree
+
+#[test]
+fn test_descendant_pids_walks_subtree_parents_first() {
+    // claude(100) → bash(200) → cargo(300) → rustc(400), claude → node(210), unrelated(500)
+    let parents = [(400, 300), (300, 200), (200, 100), (210, 100), (500, 1), (100, 50)];
+    assert_eq!(descendant_pids(&parents, 100), vec![200, 210, 300, 400]);
+    assert_eq!(descendant_pids(&parents, 400), Vec::<u32>::new());
+
+    // A loop from a reused pid doesn't repeat or include the root
+    let looped = [(200, 100), (100, 200)];
+    assert_eq!(descendant_pids(&looped, 100), vec![200]);
+}
+
+fn process_snapshot() -> System {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::All,
+        ProcessRefreshKind::new().with_cmd(sysinfo::UpdateKind::Always).with_memory(),
+    );
+    system
+}
+
+#[cfg(unix)]
+#[test]
+fn test_child_processes_finds_and_terminates_spawned_child() {
+    let mut child = std::process::Command::new("sleep").arg("30").spawn().expect("spawn sleep");
+    let own_pid = std::process::id();
+    let system = process_snapshot();
+
+    assert!(is_descendant(&system, own_pid, child.id()));
+    let children = child_processes(&system, &[own_pid]);
+    let sleep = children[&own_pid].iter().find(|c| c.pid == child.id()).expect("sleep listed as a child");
+    assert_eq!(sleep.parent_pid, own_pid);
+    assert_eq!(sleep.command, "sleep 30");
+
+    // Only processes below the given agent can be terminated
+    assert!(terminate_child(&system, child.id(), own_pid).is_err());
+    terminate_child(&system, own_pid, child.id()).unwrap();
+    let status = child.wait().unwrap();
+    assert!(!status.success());
+}
+
+// Tests for graceful termination
+
+#[cfg(unix)]
+fn identity_of(pid: u32) -> ProcessIdentity {
+    let system = process_snapshot();
+    let process = system.process(sysinfo::Pid::from_u32(pid)).expect("spawned process listed");
+    ProcessIdentity {
+        pid,
+        start_time: process.start_time(),
+        cmd: process.cmd().iter().map(|s| s.to_string_lossy().to_string()).collect(),
+    }
+}
+
+#[cfg(unix)]
+const SHORT_TIMEOUTS: TerminateOptions = TerminateOptions { interrupt_timeout_ms: 300, term_timeout_ms: 300 };
+
+#[cfg(unix)]
+#[test]
+fn test_terminate_process_tree_stops_at_sigint() {
+    let mut child = std::process::Command::new("sleep").arg("30").spawn().expect("spawn sleep");
+    let result = terminate_process_tree(&identity_of(child.id()), &SHORT_TIMEOUTS).unwrap();
+    child.wait().unwrap();
+
+    assert!(result.exited);
+    assert_eq!(result.steps.len(), 1);
+    assert_eq!(result.steps[0].signal, "SIGINT");
+    assert_eq!(result.steps[0].signalled_pids, vec![child.id()]);
+    assert!(result.remaining_pids.is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_terminate_process_tree_escalates_to_sigkill_for_subtree() {
+    // A shell ignoring SIGINT and SIGTERM, with a child that inherits that
+    let mut child = std::process::Command::new("sh")
+        .args(["-c", "trap '' INT TERM; sleep 30 & wait"])
+        .spawn()
+        .expect("spawn sh");
+    std::thread::sleep(std::time::Duration::from_millis(300));
+
+    let result = terminate_process_tree(&identity_of(child.id()), &SHORT_TIMEOUTS).unwrap();
+    child.wait().unwrap();
+
+    let signals: Vec<&str> = result.steps.iter().map(|s| s.signal.as_str()).collect();
+    assert_eq!(signals, vec!["SIGINT", "SIGTERM", "SIGKILL"]);
+    // SIGINT goes to the agent only, later signals to the whole subtree
+    assert_eq!(result.steps[0].signalled_pids, vec![child.id()]);
+    assert_eq!(result.steps[2].signalled_pids.len(), 2);
+    assert!(result.exited);
+    assert!(result.remaining_pids.is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_terminate_refuses_reused_pid() {
+    let mut child = std::process::Command::new("sleep").arg("30").spawn().expect("spawn sleep");
+    let identity = identity_of(child.id());
+    let system = process_snapshot();
+
+    let restarted = ProcessIdentity { start_time: identity.start_time - 60, ..identity.clone() };
+    assert!(verify_identity(&system, &restarted).is_err());
+    let other_command = ProcessIdentity { cmd: vec!["claude".to_string()], ..identity.clone() };
+    assert!(terminate_process_tree(&other_command, &SHORT_TIMEOUTS).is_err());
+
+    // Nothing was signalled
+    assert!(child.try_wait().unwrap().is_none());
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+// Tests for pausing and continuing
+
+#[cfg(unix)]
+fn process_status(pid: u32) -> Option<sysinfo::ProcessStatus> {
+    process_snapshot().process(sysinfo::Pid::from_u32(pid)).map(|p| p.status())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_stop_and_continue_process_tree() {
+    let mut child = std::process::Command::new("sh")
+        .args(["-c", "sleep 30 & wait"])
+        .spawn()
+        .expect("spawn sh");
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    let identity = identity_of(child.id());
+
+    let stopped = stop_process_tree(&identity).unwrap();
+    assert_eq!(stopped.len(), 2);
+    assert_eq!(stopped[0], child.id(), "the agent is stopped first");
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    for pid in &stopped {
+        assert_eq!(process_status(*pid), Some(sysinfo::ProcessStatus::Stop));
+    }
+
+    let continued = continue_process_tree(&identity).unwrap();
+    assert_eq!(continued.last(), Some(&child.id()), "the agent is continued last");
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_ne!(process_status(child.id()), Some(sysinfo::ProcessStatus::Stop));
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    for pid in &stopped[1..] {
+        let _ = std::process::Command::new("kill").arg(pid.to_string()).status();
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_terminate_paused_process_exits_on_sigint() {
+    let mut child = std::process::Command::new("sleep").arg("30").spawn().expect("spawn sleep");
+    let identity = identity_of(child.id());
+    stop_process_tree(&identity).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    let result = terminate_process_tree(&identity, &SHORT_TIMEOUTS).unwrap();
+    child.wait().unwrap();
+
+    assert!(result.exited);
+    assert_eq!(result.steps.len(), 1, "SIGINT is delivered once the process is continued");
+}
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_parse_claude_args_plain_invocation() {
+    let parsed = parse_claude_args(&args(&["claude"]));
+    assert_eq!(parsed.model, None);
+    assert_eq!(parsed.permission_mode, None);
+}
+
+#[test]
+fn test_parse_claude_args_flags() {
+    let parsed = parse_claude_args(&args(&["claude", "--model", "opus", "--permission-mode", "plan"]));
+    assert_eq!(parsed.model.as_deref(), Some("opus"));
+    assert_eq!(parsed.permission_mode.as_deref(), Some("plan"));
+
+    let parsed = parse_claude_args(&args(&["/usr/local/bin/claude", "--model=claude-sonnet-4-5", "--permission-mode=acceptEdits"]));
+    assert_eq!(parsed.model.as_deref(), Some("claude-sonnet-4-5"));
+    assert_eq!(parsed.permission_mode.as_deref(), Some("acceptEdits"));
+}
+
+#[test]
+fn test_parse_claude_args_skip_permissions_wins() {
+    let parsed = parse_claude_args(&args(&["claude", "--dangerously-skip-permissions"]));
+    assert_eq!(parsed.permission_mode.as_deref(), Some("bypassPermissions"));
+
+    let parsed = parse_claude_args(&args(&["claude", "--dangerously-skip-permissions", "--permission-mode", "plan"]));
+    assert_eq!(parsed.permission_mode.as_deref(), Some("bypassPermissions"));
+}
+
+#[test]
+fn test_parse_claude_args_session_id() {
+    let parsed = parse_claude_args(&args(&["claude", "--resume", "abc-123"]));
+    assert_eq!(parsed.session_id.as_deref(), Some("abc-123"));
+
+    let parsed = parse_claude_args(&args(&["claude", "-r", "abc-123", "--model", "opus"]));
+    assert_eq!(parsed.session_id.as_deref(), Some("abc-123"));
+    assert_eq!(parsed.model.as_deref(), Some("opus"));
+
+    let parsed = parse_claude_args(&args(&["claude", "--session-id=def-456"]));
+    assert_eq!(parsed.session_id.as_deref(), Some("def-456"));
+
+    // A bare --resume opens the picker and must not swallow the next flag
+    let parsed = parse_claude_args(&args(&["claude", "--resume", "--model", "opus"]));
+    assert_eq!(parsed.session_id, None);
+    assert_eq!(parsed.model.as_deref(), Some("opus"));
+}
+
+// Tests for agents in containers
+
+const CONTAINER_ID: &str = "4f3c2b1a00112233445566778899aabbccddeeff00112233445566778899aabb";
+
+#[test]
+fn test_parse_cgroup_container() {
+    let docker_v2 = format!("0::/system.slice/docker-{}.scope\n", CONTAINER_ID);
+    assert_eq!(parse_cgroup_container(&docker_v2), Some((Some("docker".to_string()), CONTAINER_ID.to_string())));
+
+    let docker_v1 = format!("12:pids:/docker/{}\n11:memory:/docker/{}\n", CONTAINER_ID, CONTAINER_ID);
+    assert_eq!(parse_cgroup_container(&docker_v1), Some((Some("docker".to_string()), CONTAINER_ID.to_string())));
+
+    let podman = format!("0::/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{}.scope/container\n", CONTAINER_ID);
+    assert_eq!(parse_cgroup_container(&podman), Some((Some("podman".to_string()), CONTAINER_ID.to_string())));
+
+    let kubernetes = format!("0::/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod1.slice/cri-containerd-{}.scope\n", CONTAINER_ID);
+    assert_eq!(parse_cgroup_container(&kubernetes), Some((Some("containerd".to_string()), CONTAINER_ID.to_string())));
+
+    // Host processes, including the docker daemon itself
+    assert_eq!(parse_cgroup_container("0::/user.slice/user-1000.slice/session-2.scope\n"), None);
+    assert_eq!(parse_cgroup_container("0::/system.slice/docker.service\n"), None);
+}
+
+#[test]
+fn test_parse_mountinfo() {
+    let mountinfo = "\
+1195 1010 0:120 / / rw,relatime master:1 - overlay overlay rw,lowerdir=/var/lib/docker/overlay2/l/A
+1210 1195 259:2 /home/dev/my\\040project /workspaces/my\\040project rw,relatime - ext4 /dev/nvme0n1p2 rw
+1211 1195 0:33 /@home/dev/.gitconfig /home/node/.gitconfig ro,relatime shared:5 master:2 - btrfs /dev/sda1 rw
+";
+    let mounts = parse_mountinfo(mountinfo);
+    assert_eq!(mounts.len(), 3);
+    assert_eq!(mounts[0], MountEntry {
+        device: "0:120".to_string(),
+        root: PathBuf::from("/"),
+        mount_point: PathBuf::from("/"),
+        fs_type: "overlay".to_string(),
+    });
+    // Spaces are escaped as \040
+    assert_eq!(mounts[1].root, PathBuf::from("/home/dev/my project"));
+    assert_eq!(mounts[1].mount_point, PathBuf::from("/workspaces/my project"));
+    // Optional fields don't shift the filesystem type
+    assert_eq!(mounts[2].fs_type, "btrfs");
+}
+
+fn mount(device: &str, root: &str, mount_point: &str) -> MountEntry {
+    MountEntry { device: device.to_string(), root: PathBuf::from(root), mount_point: PathBuf::from(mount_point), fs_type: "ext4".to_string() }
+}
+
+#[test]
+fn test_translate_container_path_to_host() {
+    let host = vec![
+        mount("259:2", "/", "/"),
+        // /home is a btrfs subvolume of another disk
+        mount("0:33", "/@home", "/home"),
+    ];
+    let container = vec![
+        MountEntry { fs_type: "overlay".to_string(), ..mount("0:120", "/", "/") },
+        mount("0:33", "/@home/dev/src/app", "/workspaces/app"),
+        mount("259:2", "/srv/data", "/data"),
+    ];
+
+    assert_eq!(
+        translate_path(Path::new("/workspaces/app/packages/web"), &container, &host),
+        Some(PathBuf::from("/home/dev/src/app/packages/web")),
+    );
+    assert_eq!(translate_path(Path::new("/data"), &container, &host), Some(PathBuf::from("/srv/data")));
+    // The image's own files aren't on the host
+    assert_eq!(translate_path(Path::new("/usr/src/app"), &container, &host), None);
+    // Nor are volumes from filesystems the host hasn't mounted
+    assert_eq!(translate_path(Path::new("/workspaces/app"), &container, &[mount("259:2", "/", "/")]), None);
+}
+
+#[test]
+fn test_container_home() {
+    assert_eq!(parse_environ_home(b"PATH=/usr/bin\0HOME=/home/node\0TERM=xterm\0"), Some(PathBuf::from("/home/node")));
+    assert_eq!(parse_environ_home(b"PATH=/usr/bin\0"), None);
+
+    let passwd = "root:x:0:0:root:/root:/bin/bash\nnode:x:1000:1000::/home/node:/bin/sh\n";
+    assert_eq!(passwd_home(passwd, 1000), Some(PathBuf::from("/home/node")));
+    assert_eq!(passwd_home(passwd, 0), Some(PathBuf::from("/root")));
+    assert_eq!(passwd_home(passwd, 1001), None);
+}
+
+fn cmdline(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+#[test]
+fn test_claude_command_detection() {
+    assert_eq!(claude_arg_index(&cmdline("claude --resume abc")), Some(0));
+    assert_eq!(claude_arg_index(&cmdline("/opt/homebrew/bin/claude")), Some(0));
+    assert_eq!(claude_arg_index(&cmdline("node /usr/lib/node_modules/@anthropic-ai/claude-code/cli.js -p hi")), Some(1));
+    assert_eq!(claude_arg_index(&cmdline("node --no-warnings /home/me/.npm/_npx/1f2e/node_modules/.bin/claude")), Some(2));
+    assert_eq!(claude_arg_index(&cmdline("bun run /home/me/.bun/bin/claude")), Some(2));
+    assert_eq!(
+        claude_arg_index(&cmdline("node /home/me/.vscode/extensions/anthropic.claude-code-2.0.1/dist/cli.js")),
+        Some(1),
+    );
+
+    // Other node programs, the npx wrapper and look-alikes aren't Claude Code
+    assert_eq!(claude_arg_index(&cmdline("node /srv/app/server.js")), None);
+    assert_eq!(claude_arg_index(&cmdline("npx @anthropic-ai/claude-code")), None);
+    assert_eq!(claude_arg_index(&cmdline("claude-code-acp")), None);
+    assert_eq!(claude_arg_index(&cmdline("vim claude")), None);
+    assert_eq!(claude_arg_index(&[]), None);
+}
+
+#[test]
+fn test_classify_origin() {
+    let shell = cmdline("-zsh");
+    assert_eq!(classify_origin(&cmdline("claude"), None, &shell), SessionOrigin::Terminal);
+    assert_eq!(classify_origin(&cmdline("claude -p summarize"), None, &shell), SessionOrigin::Headless);
+    assert_eq!(classify_origin(&cmdline("claude --print --model opus"), None, &shell), SessionOrigin::Headless);
+
+    // Typed into an IDE's integrated terminal, the shell sits in between
+    assert_eq!(classify_origin(&cmdline("claude"), None, &cmdline("/bin/bash --login")), SessionOrigin::Terminal);
+
+    // Spawned by the IDE itself
+    assert_eq!(classify_origin(&cmdline("claude"), None, &cmdline("node /usr/local/bin/claude-code-acp")), SessionOrigin::Zed);
+    assert_eq!(
+        classify_origin(&cmdline("claude"), None, &cmdline("/usr/share/code/code --type=extensionHost")),
+        SessionOrigin::VsCode,
+    );
+    assert_eq!(classify_origin(&cmdline("claude"), None, &cmdline("/opt/idea/bin/idea64")), SessionOrigin::JetBrains);
+    assert_eq!(
+        classify_origin(&cmdline("/home/me/.vscode-server/extensions/anthropic.claude-code-2.0.1/resources/native-binary/claude"), None, &shell),
+        SessionOrigin::VsCode,
+    );
+
+    // Driven through stream-json, with or without a runtime in front
+    assert_eq!(
+        classify_origin(
+            &cmdline("node /app/node_modules/@anthropic-ai/claude-code/cli.js --output-format stream-json --verbose --input-format stream-json"),
+            None,
+            &cmdline("python3 agent.py"),
+        ),
+        SessionOrigin::Sdk,
+    );
+    assert_eq!(classify_origin(&cmdline("claude -p --output-format=stream-json"), None, &shell), SessionOrigin::Sdk);
+
+    // The entrypoint wins over everything else
+    assert_eq!(classify_origin(&cmdline("claude"), Some("sdk-py"), &shell), SessionOrigin::Sdk);
+    assert_eq!(classify_origin(&cmdline("claude"), Some("claude-vscode"), &shell), SessionOrigin::VsCode);
+    assert_eq!(classify_origin(&cmdline("claude -p hi"), Some("cli"), &shell), SessionOrigin::Headless);
+
+    assert_eq!(parse_environ_entrypoint(b"PATH=/usr/bin\0CLAUDE_CODE_ENTRYPOINT=sdk-ts\0"), Some("sdk-ts".to_string()));
+    assert_eq!(parse_environ_entrypoint(b"PATH=/usr/bin\0"), None);
+}
@@ -0,0 +1,94 @@
+use crate::session::project_dir::{convert_dir_name_to_path, convert_path_to_dir_name, find_path_for_dir_name};
+use proptest::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+// Helper functions
+
+/// Create `segments` as nested directories under `root` and return the deepest one
+fn create_nested_dirs(root: &Path, segments: &[String]) -> PathBuf {
+    let path = segments.iter().fold(root.to_path_buf(), |path, segment| path.join(segment));
+    fs::create_dir_all(&path).unwrap();
+    path
+}
+
+/// Directory names made of characters Claude Code collapses to `-`, mixed with alphanumerics
+fn segment_strategy() -> impl Strategy<Value = String> {
+    proptest::string::string_regex("[a-zA-Z0-9._ é€😀-]{1,12}")
+        .unwrap()
+        .prop_filter("not a relative path component", |s| s != "." && s != "..")
+}
+
+#[test]
+fn test_encoding_replaces_every_non_alphanumeric() {
+    assert_eq!(convert_path_to_dir_name("/home/me/src/my-app"), "-home-me-src-my-app");
+    assert_eq!(convert_path_to_dir_name("/home/me/my_app.v2"), "-home-me-my-app-v2");
+    assert_eq!(convert_path_to_dir_name("/home/me/My Project"), "-home-me-My-Project");
+    assert_eq!(convert_path_to_dir_name("/home/me/café"), "-home-me-caf-");
+    // Characters outside the BMP are two UTF-16 code units
+    assert_eq!(convert_path_to_dir_name("/home/me/😀"), "-home-me---");
+}
+
+#[test]
+fn test_decoding_checks_the_file_system() {
+    let root = TempDir::new().unwrap();
+    let root = root.path();
+
+    for (segments, expected) in [
+        (vec!["src", "my-app"], "src/my-app"),
+        (vec!["work", "my_app.v2"], "work/my_app.v2"),
+        (vec!["repo", ".worktrees", "feature-x"], "repo/.worktrees/feature-x"),
+    ] {
+        let segments: Vec<String> = segments.into_iter().map(String::from).collect();
+        let path = create_nested_dirs(root, &segments);
+        let encoded = convert_path_to_dir_name(&path.to_string_lossy());
+        assert_eq!(find_path_for_dir_name(&encoded, root), Some(root.join(expected)));
+    }
+
+    // A directory that doesn't exist can't be decoded
+    let missing = convert_path_to_dir_name(&root.join("gone").to_string_lossy());
+    assert_eq!(find_path_for_dir_name(&missing, root), None);
+}
+
+#[test]
+fn test_decoded_paths_are_cached() {
+    let root = TempDir::new().unwrap();
+    let path = create_nested_dirs(root.path(), &["cached-project".to_string()]);
+    let encoded = convert_path_to_dir_name(&path.to_string_lossy());
+    assert_eq!(convert_dir_name_to_path(&encoded), path.to_string_lossy());
+
+    // Decoded from the cache, not guessed again once the directory is gone
+    fs::remove_dir_all(&path).unwrap();
+    assert_eq!(convert_dir_name_to_path(&encoded), path.to_string_lossy());
+}
+
+#[test]
+fn test_guessed_paths_are_not_cached() {
+    let root = TempDir::new().unwrap();
+    let path = root.path().join("late_project.v2");
+    let encoded = convert_path_to_dir_name(&path.to_string_lossy());
+    assert_ne!(convert_dir_name_to_path(&encoded), path.to_string_lossy());
+
+    // Found once the directory exists, instead of the earlier guess
+    fs::create_dir(&path).unwrap();
+    assert_eq!(convert_dir_name_to_path(&encoded), path.to_string_lossy());
+}
+
+proptest! {
+    #[test]
+    fn prop_encoding_is_one_safe_char_per_utf16_unit(path in "\\PC{0,40}") {
+        let encoded = convert_path_to_dir_name(&path);
+        prop_assert_eq!(encoded.len(), path.encode_utf16().count());
+        prop_assert!(encoded.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+    }
+
+    #[test]
+    fn prop_round_trip_through_file_system(segments in prop::collection::vec(segment_strategy(), 1..5)) {
+        let root = TempDir::new().unwrap();
+        let path = create_nested_dirs(root.path(), &segments);
+
+        let encoded = convert_path_to_dir_name(&path.to_string_lossy());
+        prop_assert_eq!(find_path_for_dir_name(&encoded, root.path()), Some(path));
+    }
+}
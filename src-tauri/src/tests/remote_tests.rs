@@ -0,0 +1,90 @@
+use crate::remote::collector::handle_command;
+use crate::remote::{CollectorCommand, CollectorMessage, CollectorRequest, LocalTransport, RemoteConnection};
+use crate::session::{parse_session_file, AgentType, SessionsResponse};
+use std::io::Write;
+use std::time::{Duration, Instant};
+use tempfile::NamedTempFile;
+
+// Helper functions
+
+fn sessions_line(id: &str) -> String {
+    let mut jsonl = NamedTempFile::new().unwrap();
+    writeln!(
+        jsonl,
+        r#"{{"sessionId":"{}","type":"assistant","message":{{"role":"assistant","content":[{{"type":"text","text":"Done"}}]}},"timestamp":"2026-01-01T10:00:00Z"}}"#,
+        id
+    ).unwrap();
+    jsonl.flush().unwrap();
+    let session = parse_session_file(&jsonl.path().to_path_buf(), "/home/me/project", 4242, 0.0, AgentType::Claude).unwrap();
+    let response = SessionsResponse { sessions: vec![session], total_count: 1, waiting_count: 0, groups: Vec::new() };
+    serde_json::to_string(&CollectorMessage::Sessions(response)).unwrap()
+}
+
+#[test]
+fn test_collector_protocol_lines() {
+    let line = sessions_line("remote-1");
+    assert!(line.contains(r#""type":"sessions""#));
+    match serde_json::from_str::<CollectorMessage>(&line).unwrap() {
+        CollectorMessage::Sessions(response) => {
+            assert_eq!(response.sessions.len(), 1);
+            assert_eq!(response.sessions[0].id, "remote-1");
+            assert_eq!(response.sessions[0].host, None);
+        }
+        other => panic!("expected sessions, got {:?}", other),
+    }
+
+    let request: CollectorRequest = serde_json::from_str(r#"{"id":3,"command":"kill","sessionId":"abc"}"#).unwrap();
+    assert_eq!(request.id, 3);
+    assert_eq!(request.command, CollectorCommand::Kill { session_id: "abc".to_string(), options: None });
+    let request: CollectorRequest = serde_json::from_str(r#"{"id":4,"command":"killChild","sessionId":"abc","childPid":2001}"#).unwrap();
+    assert_eq!(request.command, CollectorCommand::KillChild { session_id: "abc".to_string(), child_pid: 2001 });
+
+    let reply: CollectorMessage = serde_json::from_str(r#"{"type":"reply","id":3,"error":"Session not found"}"#).unwrap();
+    match reply {
+        CollectorMessage::Reply(reply) => assert_eq!(reply.into_result(), Err("Session not found".to_string())),
+        other => panic!("expected reply, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_collector_rejects_unknown_session() {
+    let result = handle_command(CollectorCommand::Kill { session_id: "no-such-session".to_string(), options: None });
+    assert!(result.is_err());
+    // Child pids are only taken relative to a session this host reported
+    let result = handle_command(CollectorCommand::KillChild { session_id: "no-such-session".to_string(), child_pid: std::process::id() });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_remote_connection_sessions_and_requests() {
+    // Fake collector: one sessions line, then a reply naming each command it receives
+    let script = format!(
+        r#"echo '{}'
+while read -r line; do
+  id=${{line#*\"id\":}}; id=${{id%%,*}}
+  cmd=${{line#*\"command\":\"}}; cmd=${{cmd%%\"*}}
+  echo "{{\"type\":\"reply\",\"id\":$id,\"ok\":\"$cmd\"}}"
+done"#,
+        sessions_line("remote-2")
+    );
+    let transport = LocalTransport { program: "sh".to_string(), args: vec!["-c".to_string(), script] };
+    let connection = RemoteConnection::start("devbox", Box::new(transport));
+
+    let started = Instant::now();
+    while connection.sessions().is_empty() && started.elapsed() < Duration::from_secs(5) {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    let sessions = connection.sessions();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].id, "remote-2");
+    assert_eq!(sessions[0].host.as_deref(), Some("devbox"));
+
+    let status = connection.status();
+    assert!(status.connected);
+    assert_eq!(status.session_count, 1);
+
+    let reply = connection.request(CollectorCommand::Focus { pid: 4242, project_path: "/home/me/project".to_string() });
+    assert_eq!(reply, Ok(serde_json::json!("focus")));
+    let reply = connection.request(CollectorCommand::Pause { session_id: "remote-2".to_string() });
+    assert_eq!(reply, Ok(serde_json::json!("pause")));
+}
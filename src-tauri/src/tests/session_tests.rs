@@ -698,6 +698,7 @@ fn test_get_sessions_internal_process_without_cwd_is_skipped() {
         pid: 99999,
         cpu_usage: 0.0,
         cwd: None,
+        ..Default::default()
     }];
     let sessions = get_sessions_internal(&processes, AgentType::Claude);
     assert!(sessions.is_empty(), "Process without CWD should be skipped");
@@ -709,7 +710,591 @@ fn test_get_sessions_internal_process_with_nonexistent_project_is_skipped() {
         pid: 99999,
         cpu_usage: 0.0,
         cwd: Some(std::path::PathBuf::from("/nonexistent/path/that/does/not/match/any/project")),
+        ..Default::default()
     }];
     let sessions = get_sessions_internal(&processes, AgentType::Claude);
     assert!(sessions.is_empty(), "Process with non-matching CWD should produce no sessions");
 }
+
+#[test]
+fn test_get_sessions_internal_reads_transcripts_inside_container() {
+    use crate::process::container::{ContainerInfo, MountEntry};
+    use std::path::PathBuf;
+
+    // The container's filesystem, as /proc/<pid>/root would show it
+    let root = tempfile::tempdir().unwrap();
+    let project_dir = root.path().join("home/node/.claude/projects/-workspace");
+    std::fs::create_dir_all(&project_dir).unwrap();
+    let line = r#"{"sessionId":"container-session","cwd":"/workspace","type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Done"}]},"timestamp":"2024-01-01T00:00:00Z"}"#;
+    std::fs::write(project_dir.join("container-session.jsonl"), format!("{}\n", line)).unwrap();
+
+    let processes = vec![AgentProcess {
+        pid: 99998,
+        cwd: Some(PathBuf::from("/workspace")),
+        container: Some(ContainerInfo {
+            id: "mntns-4026532999".to_string(),
+            runtime: None,
+            root: root.path().to_path_buf(),
+            home: Some(PathBuf::from("/home/node")),
+            // A volume the host hasn't mounted, so the path stays as the container sees it
+            mounts: vec![MountEntry { device: "0:0".to_string(), root: PathBuf::from("/"), mount_point: PathBuf::from("/workspace"), fs_type: "tmpfs".to_string() }],
+        }),
+        ..Default::default()
+    }];
+    let sessions = get_sessions_internal(&processes, AgentType::Claude);
+
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].id, "container-session");
+    assert_eq!(sessions[0].project_path, "/workspace");
+    let container = sessions[0].container.as_ref().unwrap();
+    assert_eq!(container.id, "mntns-4026532999");
+    assert_eq!(container.name, None);
+}
+
+// Tests for session titles
+
+#[test]
+fn test_clean_prompt_plain_text() {
+    use crate::session::title::clean_prompt;
+
+    assert_eq!(clean_prompt("Fix the login bug").as_deref(), Some("Fix the login bug"));
+    assert_eq!(clean_prompt("  Fix   the\tbug  \nsecond line").as_deref(), Some("Fix the bug"));
+    assert_eq!(clean_prompt("   "), None);
+}
+
+#[test]
+fn test_clean_prompt_strips_slash_commands_and_wrappers() {
+    use crate::session::title::clean_prompt;
+
+    // Built-in command with no arguments leaves nothing
+    assert_eq!(
+        clean_prompt("<command-name>/clear</command-name>\n<command-message>clear</command-message>\n<command-args></command-args>"),
+        None
+    );
+    assert_eq!(clean_prompt("/clear"), None);
+
+    // Custom command keeps its arguments
+    assert_eq!(
+        clean_prompt("<command-message>review is running…</command-message>\n<command-name>/review</command-name>\n<command-args>auth middleware</command-args>").as_deref(),
+        Some("auth middleware")
+    );
+
+    // Plain-text slash command with arguments
+    assert_eq!(clean_prompt("/compact keep the test plan").as_deref(), Some("keep the test plan"));
+
+    // System reminders are dropped, other tags keep their text
+    assert_eq!(
+        clean_prompt("<system-reminder>ignore me</system-reminder>Refactor <b>parser</b> module").as_deref(),
+        Some("Refactor parser module")
+    );
+
+    // Comparisons are not mistaken for tags
+    assert_eq!(clean_prompt("Make sure a < b holds").as_deref(), Some("Make sure a < b holds"));
+}
+
+#[test]
+fn test_clean_prompt_truncates_long_titles() {
+    use crate::session::title::clean_prompt;
+
+    let long = "word ".repeat(40);
+    let title = clean_prompt(&long).unwrap();
+    assert!(title.ends_with("..."));
+    assert!(title.chars().count() <= 83);
+}
+
+#[test]
+fn test_title_prefers_latest_summary() {
+    let jsonl = create_test_jsonl_old(&[
+        r#"{"type":"summary","summary":"First summary","leafUuid":"a"}"#,
+        r#"{"type":"summary","summary":"Implement OAuth login","leafUuid":"b"}"#,
+        r#"{"sessionId":"title-summary","type":"user","message":{"role":"user","content":"Add login"},"timestamp":"2024-01-01T00:00:00Z"}"#,
+        r#"{"sessionId":"title-summary","type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Sure"}]},"timestamp":"2024-01-01T00:00:01Z"}"#,
+    ]);
+
+    let session = parse_session_file(&jsonl.path().to_path_buf(), "/Users/test/Projects/test-project", TEST_PID, TEST_CPU_USAGE, AgentType::Claude).unwrap();
+    assert_eq!(session.title.as_deref(), Some("Implement OAuth login"));
+}
+
+#[test]
+fn test_title_falls_back_to_first_prompt() {
+    let jsonl = create_test_jsonl_old(&[
+        r#"{"sessionId":"title-prompt","type":"user","isMeta":true,"message":{"role":"user","content":"Caveat: generated by local commands"},"timestamp":"2024-01-01T00:00:00Z"}"#,
+        r#"{"sessionId":"title-prompt","type":"user","message":{"role":"user","content":"<command-name>/clear</command-name>"},"timestamp":"2024-01-01T00:00:01Z"}"#,
+        r#"{"sessionId":"title-prompt","type":"user","message":{"role":"user","content":"Speed up the test suite"},"timestamp":"2024-01-01T00:00:02Z"}"#,
+        r#"{"sessionId":"title-prompt","type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Looking"}]},"timestamp":"2024-01-01T00:00:03Z"}"#,
+    ]);
+
+    let session = parse_session_file(&jsonl.path().to_path_buf(), "/Users/test/Projects/test-project", TEST_PID, TEST_CPU_USAGE, AgentType::Claude).unwrap();
+    assert_eq!(session.title.as_deref(), Some("Speed up the test suite"));
+}
+
+#[test]
+fn test_title_picks_up_summary_written_later() {
+    let mut jsonl = create_test_jsonl(&[
+        r#"{"sessionId":"title-later","type":"user","message":{"role":"user","content":"Add login"},"timestamp":"2024-01-01T00:00:00Z"}"#,
+    ]);
+    let parse = |jsonl: &NamedTempFile| {
+        parse_session_file(&jsonl.path().to_path_buf(), "/Users/test/Projects/test-project", TEST_PID, TEST_CPU_USAGE, AgentType::Claude).unwrap()
+    };
+    assert_eq!(parse(&jsonl).title.as_deref(), Some("Add login"));
+
+    writeln!(jsonl, r#"{{"type":"summary","summary":"Implement OAuth login","leafUuid":"a"}}"#).unwrap();
+    jsonl.flush().unwrap();
+    assert_eq!(parse(&jsonl).title.as_deref(), Some("Implement OAuth login"));
+}
+
+// Tests for model, permission mode and CLI version
+
+#[test]
+fn test_parse_jsonl_model_permission_mode_and_version() {
+    let jsonl = create_test_jsonl_old(&[
+        r#"{"sessionId":"meta-session","type":"user","version":"1.0.80","permissionMode":"default","message":{"role":"user","content":"Hello"},"timestamp":"2024-01-01T00:00:00Z"}"#,
+        r#"{"sessionId":"meta-session","type":"assistant","version":"1.0.80","message":{"role":"assistant","model":"claude-sonnet-4-5","content":[{"type":"text","text":"Hi"}]},"timestamp":"2024-01-01T00:00:01Z"}"#,
+        r#"{"sessionId":"meta-session","type":"user","version":"2.0.1","permissionMode":"bypassPermissions","message":{"role":"user","content":"Go ahead"},"timestamp":"2024-01-01T00:00:02Z"}"#,
+        r#"{"sessionId":"meta-session","type":"assistant","version":"2.0.1","message":{"role":"assistant","model":"claude-opus-4-1","content":[{"type":"text","text":"Done"}]},"timestamp":"2024-01-01T00:00:03Z"}"#,
+        r#"{"sessionId":"meta-session","type":"assistant","version":"2.0.1","message":{"role":"assistant","model":"<synthetic>","content":[{"type":"text","text":"API Error"}]},"timestamp":"2024-01-01T00:00:04Z"}"#,
+    ]);
+
+    let session = parse_session_file(&jsonl.path().to_path_buf(), "/Users/test/Projects/test-project", TEST_PID, TEST_CPU_USAGE, AgentType::Claude).unwrap();
+
+    // Latest values win, synthetic model entries are ignored
+    assert_eq!(session.model.as_deref(), Some("claude-opus-4-1"));
+    assert_eq!(session.permission_mode.as_deref(), Some("bypassPermissions"));
+    assert_eq!(session.cli_version.as_deref(), Some("2.0.1"));
+}
+
+#[test]
+fn test_parse_jsonl_without_model_metadata() {
+    let jsonl = create_test_jsonl_old(&[
+        r#"{"sessionId":"old-cli","type":"user","message":{"role":"user","content":"Hello"},"timestamp":"2024-01-01T00:00:00Z"}"#,
+    ]);
+
+    let session = parse_session_file(&jsonl.path().to_path_buf(), "/Users/test/Projects/test-project", TEST_PID, TEST_CPU_USAGE, AgentType::Claude).unwrap();
+    assert_eq!(session.model, None);
+    assert_eq!(session.permission_mode, None);
+    assert_eq!(session.cli_version, None);
+}
+
+// Tests for context usage and compaction count
+
+#[test]
+fn test_context_stats_from_latest_usage_and_compactions() {
+    let jsonl = create_test_jsonl_old(&[
+        r#"{"sessionId":"ctx-session","type":"user","message":{"role":"user","content":"Hello"},"timestamp":"2024-01-01T00:00:00Z"}"#,
+        r#"{"sessionId":"ctx-session","type":"assistant","message":{"role":"assistant","model":"claude-sonnet-4-5","content":[{"type":"text","text":"Hi"}],"usage":{"input_tokens":10,"cache_read_input_tokens":150000,"cache_creation_input_tokens":5000,"output_tokens":200}},"timestamp":"2024-01-01T00:00:01Z"}"#,
+        r#"{"sessionId":"ctx-session","type":"system","subtype":"compact_boundary","content":"Conversation compacted","timestamp":"2024-01-01T00:00:02Z"}"#,
+        r#"{"sessionId":"ctx-session","type":"user","isCompactSummary":true,"message":{"role":"user","content":"Summary of the conversation"},"timestamp":"2024-01-01T00:00:03Z"}"#,
+        r#"{"sessionId":"ctx-session","type":"assistant","message":{"role":"assistant","model":"claude-sonnet-4-5","content":[{"type":"text","text":"Continuing"}],"usage":{"input_tokens":20000,"cache_read_input_tokens":30000,"cache_creation_input_tokens":0,"output_tokens":50}},"timestamp":"2024-01-01T00:00:04Z"}"#,
+    ]);
+
+    let session = parse_session_file(&jsonl.path().to_path_buf(), "/Users/test/Projects/test-project", TEST_PID, TEST_CPU_USAGE, AgentType::Claude).unwrap();
+    assert_eq!(session.compaction_count, 1);
+    assert_eq!(session.context_tokens, Some(50_000));
+    assert_eq!(session.context_usage_percent, Some(25.0));
+}
+
+#[test]
+fn test_context_stats_read_incrementally() {
+    let mut jsonl = create_test_jsonl_old(&[
+        r#"{"sessionId":"ctx-incremental","type":"assistant","message":{"role":"assistant","model":"claude-sonnet-4-5","content":[{"type":"text","text":"Hi"}],"usage":{"input_tokens":1000,"cache_read_input_tokens":0,"cache_creation_input_tokens":0}},"timestamp":"2024-01-01T00:00:00Z"}"#,
+    ]);
+    let path = jsonl.path().to_path_buf();
+
+    let session = parse_session_file(&path, "/tmp/project", TEST_PID, TEST_CPU_USAGE, AgentType::Claude).unwrap();
+    assert_eq!(session.context_tokens, Some(1000));
+    assert_eq!(session.compaction_count, 0);
+
+    // A compaction appended after the first poll is picked up without losing earlier state
+    writeln!(jsonl, r#"{{"sessionId":"ctx-incremental","type":"system","subtype":"compact_boundary","timestamp":"2024-01-01T00:00:01Z"}}"#).unwrap();
+    jsonl.flush().unwrap();
+
+    let session = parse_session_file(&path, "/tmp/project", TEST_PID, TEST_CPU_USAGE, AgentType::Claude).unwrap();
+    assert_eq!(session.compaction_count, 1);
+    assert_eq!(session.context_tokens, None);
+    assert_eq!(session.context_usage_percent, None);
+}
+
+#[test]
+fn test_token_totals_count_each_response_once() {
+    let jsonl = create_test_jsonl_old(&[
+        // One response split into two entries repeats its usage
+        r#"{"sessionId":"tokens-session","type":"assistant","message":{"id":"msg_1","role":"assistant","model":"claude-sonnet-4-5","content":[{"type":"thinking","thinking":"..."}],"usage":{"input_tokens":10,"cache_read_input_tokens":1000,"cache_creation_input_tokens":90,"output_tokens":200}},"timestamp":"2024-01-01T00:00:00Z"}"#,
+        r#"{"sessionId":"tokens-session","type":"assistant","message":{"id":"msg_1","role":"assistant","model":"claude-sonnet-4-5","content":[{"type":"text","text":"Hi"}],"usage":{"input_tokens":10,"cache_read_input_tokens":1000,"cache_creation_input_tokens":90,"output_tokens":200}},"timestamp":"2024-01-01T00:00:01Z"}"#,
+        r#"{"sessionId":"tokens-session","type":"assistant","isSidechain":true,"message":{"id":"msg_2","role":"assistant","model":"claude-haiku-4-5","content":[{"type":"text","text":"Subagent"}],"usage":{"input_tokens":500,"output_tokens":50}},"timestamp":"2024-01-01T00:00:02Z"}"#,
+    ]);
+
+    let session = parse_session_file(&jsonl.path().to_path_buf(), "/tmp/project", TEST_PID, TEST_CPU_USAGE, AgentType::Claude).unwrap();
+    assert_eq!(session.total_input_tokens, 1600);
+    assert_eq!(session.total_output_tokens, 250);
+    // Subagent requests don't change the main context
+    assert_eq!(session.context_tokens, Some(1100));
+}
+
+#[test]
+fn test_context_window_size() {
+    use crate::session::context::{context_usage_percent, context_window_size};
+
+    assert_eq!(context_window_size(Some("claude-sonnet-4-5"), 1000), 200_000);
+    assert_eq!(context_window_size(Some("sonnet[1m]"), 1000), 1_000_000);
+    // Usage beyond the default window can only come from a long-context model
+    assert_eq!(context_window_size(Some("claude-sonnet-4-5"), 300_000), 1_000_000);
+    assert_eq!(context_usage_percent(100_000, None), 50.0);
+    assert_eq!(context_usage_percent(100_000, Some("sonnet[1m]")), 10.0);
+}
+
+#[test]
+fn test_read_appended_lines_waits_for_complete_lines() {
+    use crate::session::incremental::{read_appended_lines, TranscriptCursor};
+
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "first\nsecond\npartial").unwrap();
+    file.flush().unwrap();
+
+    let mut cursor = TranscriptCursor::default();
+    let read = read_appended_lines(file.path(), &mut cursor).unwrap();
+    let lines: Vec<_> = read.lines.iter().map(|l| (l.line_number, l.text.as_str())).collect();
+    assert_eq!(lines, vec![(1, "first"), (2, "second")]);
+
+    writeln!(file, " line").unwrap();
+    file.flush().unwrap();
+    let read = read_appended_lines(file.path(), &mut cursor).unwrap();
+    assert_eq!(read.lines.len(), 1);
+    assert_eq!(read.lines[0].line_number, 3);
+    assert_eq!(read.lines[0].text, "partial line");
+    assert!(!read.reset);
+
+    // A truncated file is read again from the start
+    std::fs::write(file.path(), "new\n").unwrap();
+    let read = read_appended_lines(file.path(), &mut cursor).unwrap();
+    assert!(read.reset);
+    assert_eq!(read.lines[0].line_number, 1);
+}
+
+// Tests for status tracking and idle detection
+
+fn waiting_session(id: &str, last_activity_at: &str) -> crate::session::Session {
+    let line = format!(
+        r#"{{"sessionId":"{}","type":"assistant","message":{{"role":"assistant","content":[{{"type":"text","text":"Done"}}]}},"timestamp":"{}"}}"#,
+        id, last_activity_at
+    );
+    let jsonl = create_test_jsonl_old(&[&line]);
+    parse_session_file(&jsonl.path().to_path_buf(), "/tmp/project", TEST_PID, TEST_CPU_USAGE, AgentType::Claude).unwrap()
+}
+
+#[test]
+fn test_status_tracker_demotes_long_waits_to_idle() {
+    use crate::session::tracker::StatusTracker;
+    use chrono::{DateTime, Utc};
+
+    let now: DateTime<Utc> = "2024-01-01T12:00:00Z".parse().unwrap();
+    let mut tracker = StatusTracker::new();
+
+    // First seen: status is assumed to have begun with the last transcript entry
+    let mut session = waiting_session("tracker-recent", "2024-01-01T11:55:00Z");
+    tracker.track(&mut session, now, 600);
+    assert_eq!(session.status, SessionStatus::Waiting);
+    assert_eq!(session.status_since, "2024-01-01T11:55:00.000Z");
+
+    // Waiting longer than the threshold becomes Idle, starting when the threshold passed
+    let mut session = waiting_session("tracker-old", "2024-01-01T10:00:00Z");
+    tracker.track(&mut session, now, 600);
+    assert_eq!(session.status, SessionStatus::Idle);
+    assert_eq!(session.status_since, "2024-01-01T10:10:00.000Z");
+
+    // A threshold of 0 disables idle detection
+    let mut session = waiting_session("tracker-old", "2024-01-01T10:00:00Z");
+    tracker.track(&mut session, now, 0);
+    assert_eq!(session.status, SessionStatus::Waiting);
+    assert_eq!(session.status_since, "2024-01-01T10:00:00.000Z");
+}
+
+#[test]
+fn test_status_tracker_restarts_clock_on_transition() {
+    use crate::session::tracker::StatusTracker;
+    use chrono::{DateTime, Utc};
+
+    let mut tracker = StatusTracker::new();
+    let mut session = waiting_session("tracker-transition", "2024-01-01T10:00:00Z");
+    tracker.track(&mut session, "2024-01-01T10:01:00Z".parse().unwrap(), 600);
+
+    let later: DateTime<Utc> = "2024-01-01T10:30:00Z".parse().unwrap();
+    session.status = SessionStatus::Thinking;
+    tracker.track(&mut session, later, 600);
+    assert_eq!(session.status_since, "2024-01-01T10:30:00.000Z");
+
+    // Back to waiting - the idle clock starts over
+    session.status = SessionStatus::Waiting;
+    tracker.track(&mut session, later + chrono::Duration::minutes(5), 600);
+    assert_eq!(session.status, SessionStatus::Waiting);
+    assert_eq!(session.status_since, "2024-01-01T10:35:00.000Z");
+}
+
+// Tests for session lifecycle events
+
+#[test]
+fn test_status_tracker_emits_lifecycle_events() {
+    use crate::session::events::SessionEventKind;
+    use crate::session::tracker::StatusTracker;
+    use chrono::{DateTime, Utc};
+    use std::collections::HashSet;
+
+    let start: DateTime<Utc> = "2024-01-01T10:00:00Z".parse().unwrap();
+    let mut tracker = StatusTracker::new();
+    let mut session = waiting_session("events-session", "2024-01-01T10:00:00Z");
+
+    let events = tracker.track(&mut session, start, 600);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, SessionEventKind::SessionStarted {
+        status: SessionStatus::Waiting,
+        project_path: "/tmp/project".to_string(),
+    });
+
+    // Unchanged status produces no events
+    assert!(tracker.track(&mut session, start, 600).is_empty());
+
+    session.status = SessionStatus::Thinking;
+    session.active_subagent_count = 2;
+    let events = tracker.track(&mut session, start + chrono::Duration::minutes(1), 600);
+    let kinds: Vec<_> = events.iter().map(|e| e.kind.clone()).collect();
+    assert_eq!(kinds, vec![
+        SessionEventKind::StatusChanged { from: SessionStatus::Waiting, to: SessionStatus::Thinking },
+        SessionEventKind::SubagentStarted { active_subagent_count: 2 },
+    ]);
+    assert_eq!(events[0].at, "2024-01-01T10:01:00.000Z");
+
+    // Demotion to Idle is a transition too, dated when the threshold passed
+    session.status = SessionStatus::Waiting;
+    session.active_subagent_count = 0;
+    tracker.track(&mut session, start + chrono::Duration::minutes(2), 600);
+    session.status = SessionStatus::Waiting;
+    let events = tracker.track(&mut session, start + chrono::Duration::minutes(20), 600);
+    assert_eq!(events[0].kind, SessionEventKind::StatusChanged { from: SessionStatus::Waiting, to: SessionStatus::Idle });
+    assert_eq!(events[0].at, "2024-01-01T10:12:00.000Z");
+
+    let events = tracker.retain(&HashSet::new(), start + chrono::Duration::minutes(30));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, SessionEventKind::SessionEnded);
+    assert_eq!(events[0].session_id, "events-session");
+}
+
+#[test]
+fn test_recent_events_history_and_serialization() {
+    use crate::session::events::{record_events, recent_events, SessionEvent, SessionEventKind};
+
+    let event = |kind| SessionEvent {
+        seq: 0,
+        at: "2024-01-01T10:00:00.000Z".to_string(),
+        session_id: "history-session".to_string(),
+        agent_type: AgentType::Claude,
+        project_name: "project".to_string(),
+        kind,
+    };
+    record_events(vec![
+        event(SessionEventKind::SessionStarted { status: SessionStatus::Thinking, project_path: "/tmp/project".to_string() }),
+        event(SessionEventKind::StatusChanged { from: SessionStatus::Thinking, to: SessionStatus::Waiting }),
+    ]);
+
+    let ours: Vec<SessionEvent> = recent_events(None).into_iter()
+        .filter(|e| e.session_id == "history-session")
+        .collect();
+    assert_eq!(ours.len(), 2);
+    assert!(ours[1].seq > ours[0].seq);
+
+    // Only events after the given sequence number are returned
+    let after_first = recent_events(Some(ours[0].seq));
+    assert!(after_first.iter().all(|e| e.seq > ours[0].seq));
+    assert!(after_first.iter().any(|e| e.seq == ours[1].seq));
+
+    let json = serde_json::to_value(&ours[1]).unwrap();
+    assert_eq!(json["type"], "statusChanged");
+    assert_eq!(json["from"], "thinking");
+    assert_eq!(json["to"], "waiting");
+    assert_eq!(json["sessionId"], "history-session");
+}
+
+// Tests for grouping sessions by repository
+
+#[test]
+fn test_group_sessions_by_repository() {
+    use crate::session::groups::group_sessions;
+
+    let mut main = waiting_session("group-main", "2024-01-01T10:00:00Z");
+    main.repo_id = Some("/src/app/.git".to_string());
+    let mut worktree = waiting_session("group-worktree", "2024-01-01T10:00:00Z");
+    worktree.repo_id = Some("/src/app/.git".to_string());
+    worktree.worktree = Some("feature".to_string());
+    worktree.status = SessionStatus::Processing;
+    let outside = waiting_session("group-outside", "2024-01-01T10:00:00Z");
+
+    let groups = group_sessions(&[main, outside, worktree]);
+    assert_eq!(groups.len(), 2);
+
+    assert_eq!(groups[0].name, "app");
+    assert!(groups[0].is_repo);
+    assert_eq!(groups[0].session_ids, vec!["group-main", "group-worktree"]);
+    assert_eq!(groups[0].status_counts.waiting, 1);
+    assert_eq!(groups[0].status_counts.processing, 1);
+
+    assert_eq!(groups[1].id, "/tmp/project");
+    assert!(!groups[1].is_repo);
+    assert_eq!(groups[1].session_ids, vec!["group-outside"]);
+}
+
+// Tests for touched files and edit conflicts
+
+#[test]
+fn test_touched_files_from_edit_tools() {
+    use crate::session::files::get_touched_files;
+
+    let jsonl = create_test_jsonl_old(&[
+        r#"{"sessionId":"files-session","cwd":"/tmp/project","type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Edit","input":{"file_path":"/tmp/project/src/main.rs","old_string":"a","new_string":"b"}},{"type":"tool_use","id":"t2","name":"Read","input":{"file_path":"/tmp/project/README.md"}}]},"timestamp":"2024-01-01T00:00:00Z"}"#,
+        r#"{"sessionId":"files-session","cwd":"/tmp/project","type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"Write","input":{"file_path":"notes.md","content":"x"}}]},"timestamp":"2024-01-01T00:00:05Z"}"#,
+        r#"{"sessionId":"files-session","cwd":"/tmp/project","type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t4","name":"MultiEdit","input":{"file_path":"/tmp/project/src/main.rs","edits":[]}},{"type":"tool_use","id":"t5","name":"NotebookEdit","input":{"notebook_path":"/tmp/project/analysis.ipynb","new_source":""}}]},"timestamp":"2024-01-01T00:00:10Z"}"#,
+    ]);
+    parse_session_file(&jsonl.path().to_path_buf(), "/tmp/project", TEST_PID, TEST_CPU_USAGE, AgentType::Claude).unwrap();
+
+    let files = get_touched_files("files-session").unwrap();
+    let summary: Vec<(&str, usize, &str)> = files.iter()
+        .map(|f| (f.path.as_str(), f.edit_count, f.last_tool.as_str()))
+        .collect();
+    assert_eq!(summary, vec![
+        ("/tmp/project/analysis.ipynb", 1, "NotebookEdit"),
+        ("/tmp/project/src/main.rs", 2, "MultiEdit"),
+        ("/tmp/project/notes.md", 1, "Write"),
+    ]);
+    assert_eq!(files[1].last_touched_at, "2024-01-01T00:00:10Z");
+    assert_eq!(files[1].recent_edits, vec!["2024-01-01T00:00:00Z", "2024-01-01T00:00:10Z"]);
+    assert_eq!(get_touched_files("unknown-session"), None);
+}
+
+#[test]
+fn test_find_edit_conflicts_within_window() {
+    use crate::session::files::{find_edit_conflicts, TouchedFile};
+
+    let touched = |path: &str, at: &str| TouchedFile {
+        path: path.to_string(),
+        edit_count: 1,
+        last_touched_at: at.to_string(),
+        last_tool: "Edit".to_string(),
+        recent_edits: vec![at.to_string()],
+    };
+    let sessions = vec![
+        ("a".to_string(), vec![touched("/repo/lib.rs", "2024-01-01T10:00:00Z"), touched("/repo/old.rs", "2024-01-01T08:00:00Z")]),
+        ("b".to_string(), vec![touched("/repo/lib.rs", "2024-01-01T10:05:00Z"), touched("/repo/old.rs", "2024-01-01T10:05:00Z")]),
+        // Same file name in another worktree is a different path
+        ("c".to_string(), vec![touched("/repo-feature/lib.rs", "2024-01-01T10:01:00Z")]),
+    ];
+
+    let conflicts = find_edit_conflicts(&sessions, chrono::Duration::minutes(10));
+    assert_eq!(conflicts.len(), 2);
+    assert_eq!(conflicts["a"].len(), 1);
+    assert_eq!(conflicts["a"][0].path, "/repo/lib.rs");
+    assert_eq!(conflicts["a"][0].other_session_ids, vec!["b"]);
+    assert_eq!(conflicts["b"][0].other_session_ids, vec!["a"]);
+    assert!(!conflicts.contains_key("c"));
+
+    // Edits further apart than the window don't conflict
+    let conflicts = find_edit_conflicts(&sessions, chrono::Duration::minutes(1));
+    assert!(conflicts.is_empty());
+}
+
+#[test]
+fn test_edit_conflicts_compare_earlier_edits() {
+    use crate::session::files::{find_edit_conflicts, TouchedFile};
+
+    let touched = |edits: &[&str]| TouchedFile {
+        path: "/repo/lib.rs".to_string(),
+        edit_count: edits.len(),
+        last_touched_at: edits.last().unwrap().to_string(),
+        last_tool: "Edit".to_string(),
+        recent_edits: edits.iter().map(|t| t.to_string()).collect(),
+    };
+    // Interleaved edits, then "a" keeps editing long after "b" stopped
+    let sessions = vec![
+        ("a".to_string(), vec![touched(&["2024-01-01T10:00:00Z", "2024-01-01T10:04:00Z", "2024-01-01T11:00:00Z"])]),
+        ("b".to_string(), vec![touched(&["2024-01-01T10:02:00Z"])]),
+    ];
+
+    let conflicts = find_edit_conflicts(&sessions, chrono::Duration::minutes(10));
+    assert_eq!(conflicts["a"][0].other_session_ids, vec!["b"]);
+    assert_eq!(conflicts["b"][0].other_session_ids, vec!["a"]);
+}
+
+// Tests for resource history
+
+#[test]
+fn test_resource_history_keeps_ten_minutes_of_spaced_samples() {
+    use crate::session::resources::ResourceHistory;
+
+    let start = chrono::DateTime::parse_from_rfc3339("2024-01-01T10:00:00Z").unwrap().with_timezone(&chrono::Utc);
+    let mut history = ResourceHistory::default();
+    // One poll every 2 seconds for 15 minutes
+    for i in 0..450 {
+        history.record(start + chrono::Duration::seconds(i * 2), i as f32, 1024 * i as u64);
+    }
+
+    let samples = history.samples();
+    // Polls closer than 5 seconds to the last sample are skipped (leaving one
+    // every 6 seconds), samples older than 10 minutes are dropped
+    assert_eq!(samples.len(), 101);
+    assert_eq!(samples.first().unwrap().at, "2024-01-01T10:04:54Z");
+    assert_eq!(samples.last().unwrap().at, "2024-01-01T10:14:54Z");
+    assert_eq!(samples.last().unwrap().cpu_usage, 447.0);
+}
+
+#[test]
+fn test_resource_samples_follow_live_sessions() {
+    use crate::session::resources::{cleanup_stale_resource_entries, get_resource_history, record_resource_samples};
+
+    let mut session = waiting_session("resources-live", "2024-01-01T10:00:00Z");
+    session.tree_cpu_usage = 150.0;
+    session.tree_memory = 2 * 1024 * 1024 * 1024;
+    record_resource_samples(&[session], chrono::Utc::now());
+
+    let samples = get_resource_history("resources-live").unwrap();
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].cpu_usage, 150.0);
+    assert_eq!(samples[0].memory, 2 * 1024 * 1024 * 1024);
+
+    cleanup_stale_resource_entries(&std::collections::HashSet::new());
+    assert!(get_resource_history("resources-live").is_none());
+}
+
+#[test]
+fn test_paused_sessions_sort_and_count_as_inactive() {
+    use crate::session::groups::group_sessions;
+
+    let mut paused = waiting_session("group-paused", "2024-01-01T10:00:00Z");
+    paused.status = SessionStatus::Paused;
+    assert_eq!(status_sort_priority(&SessionStatus::Paused), status_sort_priority(&SessionStatus::Idle));
+
+    let groups = group_sessions(&[paused]);
+    assert_eq!(groups[0].status_counts.paused, 1);
+    assert_eq!(groups[0].status_counts.waiting, 0);
+}
+
+#[test]
+fn test_repo_identity_skips_projects_only_inside_a_container() {
+    use crate::process::ContainerRef;
+    use crate::session::groups::apply_repo_identity;
+
+    // A repository on the host at the path a container's project has inside the container
+    let root = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(root.path().join(".git")).unwrap();
+    let project_path = root.path().to_string_lossy().to_string();
+
+    let mut host = waiting_session("identity-host", "2024-01-01T10:00:00Z");
+    host.project_path = project_path.clone();
+    let mut bind_mounted = waiting_session("identity-bind-mounted", "2024-01-01T10:00:00Z");
+    bind_mounted.project_path = project_path.clone();
+    bind_mounted.container = Some(ContainerRef { id: "abc".to_string(), project_on_host: true, ..Default::default() });
+    let mut inside = waiting_session("identity-inside", "2024-01-01T10:00:00Z");
+    inside.project_path = project_path;
+    inside.container = Some(ContainerRef { id: "abc".to_string(), project_on_host: false, ..Default::default() });
+
+    let mut sessions = vec![host, bind_mounted, inside];
+    apply_repo_identity(&mut sessions);
+    assert!(sessions[0].repo_id.is_some());
+    assert_eq!(sessions[1].repo_id, sessions[0].repo_id);
+    assert_eq!(sessions[2].repo_id, None);
+}
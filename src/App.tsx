@@ -1,16 +1,21 @@
 import { useState } from 'react';
 import { SessionGrid } from './components/SessionGrid';
-import { Settings, useHotkeyInit } from './components/Settings';
+import { HistoryView } from './components/HistoryView';
+import { ActivityView } from './components/ActivityView';
+import { Settings, useHotkeyInit, useIdleThresholdInit, useRemoteHostsInit, useVisibleOriginsInit } from './components/Settings';
 import { useSessions } from './hooks/useSessions';
 import { Button } from '@/components/ui/button';
 import { Badge } from '@/components/ui/badge';
 
 function App() {
   const [showSettings, setShowSettings] = useState(false);
+  const [showHistory, setShowHistory] = useState(false);
+  const [showActivity, setShowActivity] = useState(false);
   const {
     sessions,
     totalCount,
     waitingCount,
+    groups,
     isLoading,
     error,
     refresh,
@@ -19,6 +24,9 @@ function App() {
 
   // Initialize hotkey on app start
   useHotkeyInit();
+  useIdleThresholdInit();
+  useRemoteHostsInit();
+  useVisibleOriginsInit();
 
   return (
     <div className="min-h-screen bg-background flex flex-col">
@@ -43,6 +51,32 @@ function App() {
           )}
         </div>
         <div className="flex items-center gap-1">
+          <Button
+            variant={showHistory ? 'secondary' : 'ghost'}
+            size="icon-sm"
+            onClick={() => {
+              setShowHistory(!showHistory);
+              setShowActivity(false);
+            }}
+            title={showHistory ? 'Active sessions' : 'Session history'}
+          >
+            <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M12 8v4l3 3m6-3a9 9 0 11-18 0 9 9 0 0118 0z" />
+            </svg>
+          </Button>
+          <Button
+            variant={showActivity ? 'secondary' : 'ghost'}
+            size="icon-sm"
+            onClick={() => {
+              setShowActivity(!showActivity);
+              setShowHistory(false);
+            }}
+            title={showActivity ? 'Active sessions' : 'Activity reports'}
+          >
+            <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 19v-6a2 2 0 00-2-2H5a2 2 0 00-2 2v6a2 2 0 002 2h2a2 2 0 002-2zm0 0V9a2 2 0 012-2h2a2 2 0 012 2v10m-6 0a2 2 0 002 2h2a2 2 0 002-2m0 0V5a2 2 0 012-2h2a2 2 0 012 2v14a2 2 0 01-2 2h-2a2 2 0 01-2-2z" />
+            </svg>
+          </Button>
           <Button
             variant="ghost"
             size="icon-sm"
@@ -83,7 +117,11 @@ function App() {
 
       {/* Main content area */}
       <main className="flex-1 overflow-y-auto p-6">
-        {error ? (
+        {showHistory ? (
+          <HistoryView />
+        ) : showActivity ? (
+          <ActivityView />
+        ) : error ? (
           <div className="flex items-center justify-center h-full">
             <div className="p-6 text-destructive text-sm text-center bg-destructive/10 rounded-xl border border-destructive/20 max-w-md">
               <svg className="w-8 h-8 mx-auto mb-3 opacity-50" fill="none" stroke="currentColor" viewBox="0 0 24 24">
@@ -107,6 +145,7 @@ function App() {
         ) : (
           <SessionGrid
             sessions={sessions}
+            groups={groups}
             onSessionClick={focusSession}
           />
         )}
@@ -1,5 +1,5 @@
 import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
-import { formatTimeAgo, truncatePath, statusConfig } from '../lib/formatters';
+import { formatTimeAgo, truncatePath, statusConfig, formatBytes, formatModelName, formatTokenCount, formatDuration, formatGitChanges, formatRemoteProvider, formatSeconds } from '../lib/formatters';
 
 describe('formatTimeAgo', () => {
   beforeEach(() => {
@@ -59,12 +59,87 @@ describe('truncatePath', () => {
   });
 });
 
+describe('formatBytes', () => {
+  it('formats sizes with the largest fitting unit', () => {
+    expect(formatBytes(512)).toBe('512 B');
+    expect(formatBytes(2048)).toBe('2 KB');
+    expect(formatBytes(5 * 1024 * 1024)).toBe('5.0 MB');
+    expect(formatBytes(3 * 1024 * 1024 * 1024)).toBe('3.0 GB');
+  });
+});
+
+describe('formatModelName', () => {
+  it('strips the claude prefix and release date', () => {
+    expect(formatModelName('claude-opus-4-1-20250805')).toBe('opus-4-1');
+    expect(formatModelName('claude-sonnet-4-5')).toBe('sonnet-4-5');
+  });
+
+  it('keeps aliases and other model ids unchanged', () => {
+    expect(formatModelName('opus')).toBe('opus');
+    expect(formatModelName('gpt-5')).toBe('gpt-5');
+  });
+});
+
+describe('formatDuration', () => {
+  it('formats elapsed time without a suffix', () => {
+    const now = Date.now();
+    expect(formatDuration(new Date(now - 30 * 1000).toISOString())).toBe('<1m');
+    expect(formatDuration(new Date(now - 5 * 60 * 1000).toISOString())).toBe('5m');
+    expect(formatDuration(new Date(now - 3 * 60 * 60 * 1000).toISOString())).toBe('3h');
+    expect(formatDuration(new Date(now - 50 * 60 * 60 * 1000).toISOString())).toBe('2d');
+  });
+});
+
+describe('formatTokenCount', () => {
+  it('formats token counts compactly', () => {
+    expect(formatTokenCount(800)).toBe('800');
+    expect(formatTokenCount(1500)).toBe('1.5k');
+    expect(formatTokenCount(145_000)).toBe('145k');
+    expect(formatTokenCount(1_000_000)).toBe('1.0M');
+  });
+});
+
+describe('formatSeconds', () => {
+  it('formats report time spans in hours and minutes', () => {
+    expect(formatSeconds(0)).toBe('0m');
+    expect(formatSeconds(30)).toBe('<1m');
+    expect(formatSeconds(45 * 60)).toBe('45m');
+    expect(formatSeconds(2 * 3600)).toBe('2h');
+    expect(formatSeconds(5400)).toBe('1h 30m');
+  });
+});
+
+describe('formatGitChanges', () => {
+  const clean = {
+    modified: 0, untracked: 0, conflicted: 0, insertions: 0, deletions: 0,
+    upstream: null, ahead: 0, behind: 0, lastCommitSubject: null, operation: null,
+  };
+
+  it('returns nothing for a clean working tree', () => {
+    expect(formatGitChanges(clean)).toEqual([]);
+  });
+
+  it('lists line changes, file counts and upstream divergence', () => {
+    expect(formatGitChanges({ ...clean, insertions: 10, deletions: 2, modified: 3, untracked: 1, ahead: 2, behind: 1 }))
+      .toEqual(['+10 -2', '3 modified', '1 untracked', '↑2', '↓1']);
+  });
+});
+
+describe('formatRemoteProvider', () => {
+  it('names known hosting services', () => {
+    expect(formatRemoteProvider('github')).toBe('GitHub');
+    expect(formatRemoteProvider('gitlab')).toBe('GitLab');
+    expect(formatRemoteProvider('unknown')).toBe('Remote');
+  });
+});
+
 describe('statusConfig', () => {
   it('has configuration for all session statuses', () => {
     expect(statusConfig.waiting).toBeDefined();
     expect(statusConfig.thinking).toBeDefined();
     expect(statusConfig.processing).toBeDefined();
     expect(statusConfig.idle).toBeDefined();
+    expect(statusConfig.paused).toBeDefined();
   });
 
   it('each status has required properties', () => {
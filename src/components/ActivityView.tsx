@@ -0,0 +1,134 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { ActivityReport, ProjectDayActivity, WeeklySessions } from '../types/session';
+import { Button } from '@/components/ui/button';
+import { formatSeconds, formatTokenCount } from '@/lib/formatters';
+
+export function ActivityView() {
+  const [days, setDays] = useState<ProjectDayActivity[]>([]);
+  const [weeks, setWeeks] = useState<WeeklySessions[]>([]);
+  const [isLoading, setIsLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+  const [exportedPath, setExportedPath] = useState<string | null>(null);
+
+  const fetchActivity = useCallback(async () => {
+    setIsLoading(true);
+    try {
+      const [projectTime, weeklySessions] = await Promise.all([
+        invoke<ProjectDayActivity[]>('get_project_time', { days: 30 }),
+        invoke<WeeklySessions[]>('get_weekly_sessions', { weeks: 12 }),
+      ]);
+      setDays(projectTime);
+      setWeeks(weeklySessions);
+      setError(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchActivity();
+  }, [fetchActivity]);
+
+  const handleExport = async (report: ActivityReport) => {
+    try {
+      setExportedPath(await invoke<string>('export_activity_csv', { report }));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  if (isLoading && days.length === 0 && weeks.length === 0) {
+    return <div className="text-sm text-muted-foreground text-center py-12">Loading activity...</div>;
+  }
+
+  // Most recent day first
+  const byDay = days.slice().reverse();
+
+  return (
+    <div className="space-y-6">
+      {error && (
+        <div className="p-3 rounded-lg bg-destructive/10 border border-destructive/20 text-destructive text-sm">
+          {error}
+        </div>
+      )}
+      {exportedPath && (
+        <div className="p-3 rounded-lg bg-muted/50 border border-border text-sm text-muted-foreground">
+          Saved to <code className="font-mono">{exportedPath}</code>
+        </div>
+      )}
+
+      <section>
+        <div className="flex items-center justify-between mb-2">
+          <h2 className="font-semibold text-foreground">Time per project, last 30 days</h2>
+          <Button size="sm" variant="outline" onClick={() => handleExport('projectTime')}>
+            Export CSV
+          </Button>
+        </div>
+        {byDay.length === 0 ? (
+          <div className="text-sm text-muted-foreground py-4">No activity recorded yet</div>
+        ) : (
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="text-xs text-muted-foreground text-left">
+                <th className="font-normal py-1">Day</th>
+                <th className="font-normal py-1">Project</th>
+                <th className="font-normal py-1 text-right">Processing</th>
+                <th className="font-normal py-1 text-right">Waiting</th>
+                <th className="font-normal py-1 text-right">Idle</th>
+                <th className="font-normal py-1 text-right">Tokens</th>
+              </tr>
+            </thead>
+            <tbody>
+              {byDay.map((row) => (
+                <tr key={`${row.day}-${row.projectPath}`} className="border-t border-border">
+                  <td className="py-1 text-muted-foreground">{row.day}</td>
+                  <td className="py-1 text-foreground truncate max-w-48" title={row.projectPath}>{row.project}</td>
+                  <td className="py-1 text-right">{formatSeconds(row.processingSecs)}</td>
+                  <td className="py-1 text-right">{formatSeconds(row.waitingSecs)}</td>
+                  <td className="py-1 text-right text-muted-foreground">{formatSeconds(row.idleSecs)}</td>
+                  <td className="py-1 text-right text-muted-foreground">
+                    {formatTokenCount(row.inputTokens + row.outputTokens)}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </section>
+
+      <section>
+        <div className="flex items-center justify-between mb-2">
+          <h2 className="font-semibold text-foreground">Sessions started per week</h2>
+          <Button size="sm" variant="outline" onClick={() => handleExport('weeklySessions')}>
+            Export CSV
+          </Button>
+        </div>
+        {weeks.length === 0 ? (
+          <div className="text-sm text-muted-foreground py-4">No sessions recorded yet</div>
+        ) : (
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="text-xs text-muted-foreground text-left">
+                <th className="font-normal py-1">Week of</th>
+                <th className="font-normal py-1 text-right">Sessions</th>
+                <th className="font-normal py-1 text-right">Projects</th>
+              </tr>
+            </thead>
+            <tbody>
+              {weeks.slice().reverse().map((week) => (
+                <tr key={week.weekStart} className="border-t border-border">
+                  <td className="py-1 text-muted-foreground">{week.weekStart}</td>
+                  <td className="py-1 text-right">{week.sessionsStarted}</td>
+                  <td className="py-1 text-right">{week.projectCount}</td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </section>
+    </div>
+  );
+}
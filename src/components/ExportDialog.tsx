@@ -0,0 +1,124 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { AgentType, ExportFormat, ExportOptions } from '../types/session';
+import { Button } from '@/components/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogFooter,
+} from '@/components/ui/dialog';
+
+const FORMAT_OPTIONS: { value: ExportFormat; label: string }[] = [
+  { value: 'markdown', label: 'Markdown' },
+  { value: 'html', label: 'HTML (single file)' },
+  { value: 'json', label: 'JSON' },
+];
+
+// Matches DEFAULT_MAX_TOOL_OUTPUT_CHARS in the backend
+const MAX_TOOL_OUTPUT_OPTIONS = [
+  { chars: 2000, label: '2,000 characters' },
+  { chars: 10000, label: '10,000 characters' },
+  { chars: 0, label: 'Full output' },
+];
+
+interface ExportDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  sessionId: string;
+  agentType: AgentType;
+}
+
+export function ExportDialog({ open, onOpenChange, sessionId, agentType }: ExportDialogProps) {
+  const [format, setFormat] = useState<ExportFormat>('markdown');
+  const [includeThinking, setIncludeThinking] = useState(false);
+  const [maxToolOutputChars, setMaxToolOutputChars] = useState(2000);
+  const [savedPath, setSavedPath] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleOpenChange = (isOpen: boolean) => {
+    if (!isOpen) {
+      setSavedPath(null);
+      setError(null);
+    }
+    onOpenChange(isOpen);
+  };
+
+  const handleExport = async () => {
+    setError(null);
+    const options: ExportOptions = { format, includeThinking, maxToolOutputChars };
+    try {
+      setSavedPath(await invoke<string>('export_session', { sessionId, agentType, options }));
+    } catch (err) {
+      console.error('Failed to export session:', err);
+      setError(String(err));
+    }
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={handleOpenChange}>
+      <DialogContent onClick={(e) => e.stopPropagation()}>
+        <DialogHeader>
+          <DialogTitle>Export Transcript</DialogTitle>
+        </DialogHeader>
+        <div className="py-2 space-y-4">
+          <div className="space-y-2">
+            <label className="text-sm font-medium text-foreground" htmlFor="export-format">
+              Format
+            </label>
+            <select
+              id="export-format"
+              className="w-full h-9 rounded-lg border border-border bg-muted/50 px-3 text-sm text-foreground"
+              value={format}
+              onChange={(e) => setFormat(e.target.value as ExportFormat)}
+            >
+              {FORMAT_OPTIONS.map((option) => (
+                <option key={option.value} value={option.value}>
+                  {option.label}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div className="space-y-2">
+            <label className="text-sm font-medium text-foreground" htmlFor="export-tool-output">
+              Tool Output
+            </label>
+            <select
+              id="export-tool-output"
+              className="w-full h-9 rounded-lg border border-border bg-muted/50 px-3 text-sm text-foreground"
+              value={maxToolOutputChars}
+              onChange={(e) => setMaxToolOutputChars(Number(e.target.value))}
+            >
+              {MAX_TOOL_OUTPUT_OPTIONS.map((option) => (
+                <option key={option.chars} value={option.chars}>
+                  {option.label}
+                </option>
+              ))}
+            </select>
+          </div>
+          <label className="flex items-center gap-2 text-sm text-foreground">
+            <input
+              type="checkbox"
+              checked={includeThinking}
+              onChange={(e) => setIncludeThinking(e.target.checked)}
+            />
+            Include thinking
+          </label>
+          {savedPath && (
+            <p className="text-sm text-muted-foreground">
+              Saved to <code className="font-mono">{savedPath}</code>
+            </p>
+          )}
+          {error && <p className="text-sm text-red-400">{error}</p>}
+        </div>
+        <DialogFooter>
+          <Button variant="outline" onClick={() => handleOpenChange(false)}>
+            Close
+          </Button>
+          <Button onClick={handleExport}>Export</Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -0,0 +1,136 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { HistorySession, ProjectHistory, RedactionResult } from '../types/session';
+import { Card, CardContent } from '@/components/ui/card';
+import { Badge } from '@/components/ui/badge';
+import { Button } from '@/components/ui/button';
+import { ExportDialog } from '@/components/ExportDialog';
+import { formatTimeAgo, truncatePath, formatBytes } from '@/lib/formatters';
+
+export function HistoryView() {
+  const [projects, setProjects] = useState<ProjectHistory[]>([]);
+  const [isLoading, setIsLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+  const [exporting, setExporting] = useState<HistorySession | null>(null);
+  const [notice, setNotice] = useState<string | null>(null);
+
+  const fetchHistory = useCallback(async () => {
+    setIsLoading(true);
+    try {
+      const history = await invoke<ProjectHistory[]>('get_session_history');
+      setProjects(history);
+      setError(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchHistory();
+  }, [fetchHistory]);
+
+  const handleResume = async (session: HistorySession) => {
+    try {
+      await invoke('resume_session', {
+        sessionId: session.id,
+        projectPath: session.projectPath,
+        agentType: session.agentType,
+      });
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  // Transcripts can only be redacted once their agent has exited
+  const handleRedact = async (session: HistorySession) => {
+    try {
+      const result = await invoke<RedactionResult>('redact_session_secrets', { sessionId: session.id });
+      setNotice(
+        result.redactedCount === 0
+          ? 'No secrets found.'
+          : `Redacted ${result.redactedCount} ${result.redactedCount === 1 ? 'secret' : 'secrets'}. The original was saved to ${result.backupPath}.`
+      );
+      setError(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  if (isLoading && projects.length === 0) {
+    return <div className="text-sm text-muted-foreground text-center py-12">Loading history...</div>;
+  }
+
+  return (
+    <div className="space-y-6">
+      {error && (
+        <div className="p-3 rounded-lg bg-destructive/10 border border-destructive/20 text-destructive text-sm">
+          {error}
+        </div>
+      )}
+      {notice && (
+        <div className="p-3 rounded-lg bg-muted/50 border border-border text-muted-foreground text-sm break-all">
+          {notice}
+        </div>
+      )}
+      {projects.map((project) => (
+        <section key={project.projectPath}>
+          <div className="flex items-baseline gap-2 mb-2">
+            <h2 className="font-semibold text-foreground">{project.projectName}</h2>
+            <span className="text-xs text-muted-foreground truncate">{truncatePath(project.projectPath)}</span>
+          </div>
+          <div className="space-y-2">
+            {project.sessions.map((session) => (
+              <Card key={session.id} className="py-0 gap-0 group">
+                <CardContent className="p-3 flex items-center gap-3">
+                  <div className="flex-1 min-w-0">
+                    <div className="text-sm text-foreground truncate">
+                      {session.title || session.firstPrompt || session.id}
+                    </div>
+                    {session.title && session.firstPrompt && (
+                      <div className="text-xs text-muted-foreground truncate mt-0.5">
+                        {session.firstPrompt}
+                      </div>
+                    )}
+                    <div className="flex items-center gap-3 mt-1 text-xs text-muted-foreground">
+                      <span>{formatTimeAgo(session.lastActivityAt)}</span>
+                      <span>{session.messageCount} messages</span>
+                      <span>{formatBytes(session.sizeBytes)}</span>
+                      {session.gitBranch && <span className="truncate">{session.gitBranch}</span>}
+                    </div>
+                  </div>
+                  <Badge variant="outline" className="shrink-0">
+                    {session.agentType === 'claude' ? 'Claude' : 'OpenCode'}
+                  </Badge>
+                  {session.agentType === 'claude' && (
+                    <Button size="sm" variant="ghost" className="shrink-0" onClick={() => handleRedact(session)}>
+                      Redact
+                    </Button>
+                  )}
+                  <Button size="sm" variant="ghost" className="shrink-0" onClick={() => setExporting(session)}>
+                    Export
+                  </Button>
+                  <Button size="sm" variant="outline" className="shrink-0" onClick={() => handleResume(session)}>
+                    Resume
+                  </Button>
+                </CardContent>
+              </Card>
+            ))}
+          </div>
+        </section>
+      ))}
+      {projects.length === 0 && !error && (
+        <div className="text-sm text-muted-foreground text-center py-12">No past sessions found</div>
+      )}
+      {exporting && (
+        <ExportDialog
+          open
+          onOpenChange={(open) => !open && setExporting(null)}
+          sessionId={exporting.id}
+          agentType={exporting.agentType}
+        />
+      )}
+    </div>
+  );
+}
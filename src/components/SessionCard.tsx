@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import { Session } from '../types/session';
+import { AuditEntry, ResourceSample, SecretFinding, Session, TerminationResult, TouchedFile } from '../types/session';
 import { Card, CardContent } from '@/components/ui/card';
 import { Badge } from '@/components/ui/badge';
 import { Button } from '@/components/ui/button';
@@ -18,7 +18,9 @@ import {
   DialogFooter,
 } from '@/components/ui/dialog';
 import { Input } from '@/components/ui/input';
-import { formatTimeAgo, truncatePath, statusConfig } from '@/lib/formatters';
+import { ExportDialog } from '@/components/ExportDialog';
+import { Sparkline } from '@/components/Sparkline';
+import { formatTimeAgo, truncatePath, statusConfig, formatModelName, formatTokenCount, formatDuration, formatGitChanges, formatRemoteProvider, formatOrigin, formatBytes, formatSeconds, CONTEXT_WARNING_PERCENT } from '@/lib/formatters';
 import { openUrl } from '@tauri-apps/plugin-opener';
 import { invoke } from '@tauri-apps/api/core';
 
@@ -46,6 +48,13 @@ const AgentStatusIcon = ({ type, statusColor }: { type: 'claude' | 'opencode', s
   return <OpenCodeIcon className={`w-4 h-4 ${statusColor}`} />;
 };
 
+const AUDIT_KIND_LABELS: Record<AuditEntry['kind'], string> = {
+  command: 'Bash',
+  webFetch: 'Fetch',
+  webSearch: 'Search',
+  fileEdit: 'Edit',
+};
+
 interface SessionCardProps {
   session: Session;
   onClick: () => void;
@@ -93,6 +102,8 @@ function setCustomUrl(sessionId: string, url: string) {
   localStorage.setItem(CUSTOM_URLS_KEY, JSON.stringify(urls));
 }
 
+const RESOURCE_HISTORY_REFRESH_MS = 10000;
+
 export function SessionCard({ session, onClick }: SessionCardProps) {
   const config = statusConfig[session.status];
   const [customName, setCustomNameState] = useState<string>('');
@@ -101,6 +112,13 @@ export function SessionCard({ session, onClick }: SessionCardProps) {
   const [isUrlOpen, setIsUrlOpen] = useState(false);
   const [renameValue, setRenameValue] = useState('');
   const [urlValue, setUrlValue] = useState('');
+  const [touchedFiles, setTouchedFiles] = useState<TouchedFile[] | null>(null);
+  const [auditEntries, setAuditEntries] = useState<AuditEntry[] | null>(null);
+  const [secretFindings, setSecretFindings] = useState<SecretFinding[] | null>(null);
+  const [isExportOpen, setIsExportOpen] = useState(false);
+  const [isProcessesOpen, setIsProcessesOpen] = useState(false);
+  const [processError, setProcessError] = useState<string | null>(null);
+  const [resourceHistory, setResourceHistory] = useState<ResourceSample[]>([]);
 
   // Load custom data on mount
   useEffect(() => {
@@ -110,16 +128,30 @@ export function SessionCard({ session, onClick }: SessionCardProps) {
     setCustomUrlState(urls[session.id] || '');
   }, [session.id]);
 
-  const displayName = customName || session.projectName;
+  // The backend samples every few seconds; refreshing the sparklines less often is enough
+  useEffect(() => {
+    const fetchHistory = () => {
+      invoke<ResourceSample[]>('get_resource_history', { sessionId: session.id })
+        .then(setResourceHistory)
+        .catch(() => setResourceHistory([]));
+    };
+    fetchHistory();
+    const interval = setInterval(fetchHistory, RESOURCE_HISTORY_REFRESH_MS);
+    return () => clearInterval(interval);
+  }, [session.id]);
+
+  // Generated title distinguishes sessions in the same project; custom names still win
+  const defaultName = session.title || session.projectName;
+  const displayName = customName || defaultName;
 
   const handleRename = () => {
-    setRenameValue(customName || session.projectName);
+    setRenameValue(displayName);
     setIsRenameOpen(true);
   };
 
   const handleSaveRename = () => {
     const newName = renameValue.trim();
-    if (newName === session.projectName) {
+    if (newName === defaultName) {
       setCustomName(session.id, '');
       setCustomNameState('');
     } else {
@@ -165,15 +197,66 @@ export function SessionCard({ session, onClick }: SessionCardProps) {
     }
   };
 
-  const handleOpenGitHub = async () => {
-    if (session.githubUrl) {
-      await openUrl(session.githubUrl);
+  const handleShowFiles = async () => {
+    try {
+      setTouchedFiles(await invoke<TouchedFile[]>('get_session_files', { sessionId: session.id }));
+    } catch (error) {
+      console.error('Failed to load touched files:', error);
     }
   };
 
+  const handleShowAudit = async () => {
+    try {
+      setAuditEntries(await invoke<AuditEntry[]>('get_session_audit', { sessionId: session.id }));
+    } catch (error) {
+      console.error('Failed to load audit log:', error);
+    }
+  };
+
+  const handleShowSecrets = async () => {
+    try {
+      setSecretFindings(await invoke<SecretFinding[]>('get_session_secrets', { sessionId: session.id }));
+    } catch (error) {
+      console.error('Failed to load secret findings:', error);
+    }
+  };
+
+  const handlePauseToggle = async () => {
+    const command = session.status === 'paused' ? 'continue_session' : 'pause_session';
+    try {
+      await invoke(command, { sessionId: session.id });
+    } catch (error) {
+      console.error(`Failed to ${command === 'pause_session' ? 'pause' : 'continue'} session:`, error);
+    }
+  };
+
+  const handleKillChild = async (childPid: number) => {
+    setProcessError(null);
+    try {
+      await invoke('kill_child_process', { sessionId: session.id, childPid });
+    } catch (error) {
+      console.error('Failed to stop child process:', error);
+      setProcessError(String(error));
+    }
+  };
+
+  const conflictPaths = new Set(session.editConflicts.map(c => c.path));
+
+  const remote = session.remote;
+  const handleOpenRemoteUrl = async (url: string | null) => {
+    if (url) {
+      await openUrl(url);
+    }
+  };
+
+  const contextNearlyFull = (session.contextUsagePercent ?? 0) >= CONTEXT_WARNING_PERCENT;
+
   const handleKillSession = async () => {
     try {
-      await invoke('kill_session', { pid: session.pid });
+      const result = await invoke<TerminationResult>('kill_session', { sessionId: session.id });
+      if (result.remainingPids.length > 0) {
+        console.warn(`Processes still running after killing session: ${result.remainingPids.join(', ')}`);
+      }
     } catch (error) {
       console.error('Failed to kill session:', error);
     }
@@ -270,19 +353,87 @@ export function SessionCard({ session, onClick }: SessionCardProps) {
                     </svg>
                     {customUrl ? 'Edit URL' : 'Set URL'}
                   </DropdownMenuItem>
-                  {session.githubUrl && (
-                    <DropdownMenuItem onClick={handleOpenGitHub}>
-                      <svg
-                        className="w-4 h-4 mr-2"
-                        fill="currentColor"
-                        viewBox="0 0 24 24"
-                      >
-                        <path d="M12 0c-6.626 0-12 5.373-12 12 0 5.302 3.438 9.8 8.207 11.387.599.111.793-.261.793-.577v-2.234c-3.338.726-4.033-1.416-4.033-1.416-.546-1.387-1.333-1.756-1.333-1.756-1.089-.745.083-.729.083-.729 1.205.084 1.839 1.237 1.839 1.237 1.07 1.834 2.807 1.304 3.492.997.107-.775.418-1.305.762-1.604-2.665-.305-5.467-1.334-5.467-5.931 0-1.311.469-2.381 1.236-3.221-.124-.303-.535-1.524.117-3.176 0 0 1.008-.322 3.301 1.23.957-.266 1.983-.399 3.003-.404 1.02.005 2.047.138 3.006.404 2.291-1.552 3.297-1.23 3.297-1.23.653 1.653.242 2.874.118 3.176.77.84 1.235 1.911 1.235 3.221 0 4.609-2.807 5.624-5.479 5.921.43.372.823 1.102.823 2.222v3.293c0 .319.192.694.801.576 4.765-1.589 8.199-6.086 8.199-11.386 0-6.627-5.373-12-12-12z" />
+                  <DropdownMenuItem onClick={handleShowFiles}>
+                    <svg className="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                      <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 12h6m-6 4h6m2 5H7a2 2 0 01-2-2V5a2 2 0 012-2h5.586a1 1 0 01.707.293l5.414 5.414a1 1 0 01.293.707V19a2 2 0 01-2 2z" />
+                    </svg>
+                    Files Touched
+                  </DropdownMenuItem>
+                  <DropdownMenuItem onClick={handleShowAudit}>
+                    <svg className="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                      <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M8 9l3 3-3 3m5 0h3M5 20h14a2 2 0 002-2V6a2 2 0 00-2-2H5a2 2 0 00-2 2v12a2 2 0 002 2z" />
+                    </svg>
+                    Activity Audit
+                  </DropdownMenuItem>
+                  <DropdownMenuItem onClick={() => setIsProcessesOpen(true)}>
+                    <svg className="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                      <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M4 6h16M4 12h16M4 18h7" />
+                    </svg>
+                    Child Processes
+                  </DropdownMenuItem>
+                  <DropdownMenuItem onClick={() => setIsExportOpen(true)}>
+                    <svg className="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                      <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M4 16v1a3 3 0 003 3h10a3 3 0 003-3v-1m-4-4l-4 4m0 0l-4-4m4 4V4" />
+                    </svg>
+                    Export Transcript
+                  </DropdownMenuItem>
+                  {remote && (
+                    <DropdownMenuItem onClick={() => handleOpenRemoteUrl(remote.repoUrl)}>
+                      {remote.provider === 'github' ? (
+                        <svg
+                          className="w-4 h-4 mr-2"
+                          fill="currentColor"
+                          viewBox="0 0 24 24"
+                        >
+                          <path d="M12 0c-6.626 0-12 5.373-12 12 0 5.302 3.438 9.8 8.207 11.387.599.111.793-.261.793-.577v-2.234c-3.338.726-4.033-1.416-4.033-1.416-.546-1.387-1.333-1.756-1.333-1.756-1.089-.745.083-.729.083-.729 1.205.084 1.839 1.237 1.839 1.237 1.07 1.834 2.807 1.304 3.492.997.107-.775.418-1.305.762-1.604-2.665-.305-5.467-1.334-5.467-5.931 0-1.311.469-2.381 1.236-3.221-.124-.303-.535-1.524.117-3.176 0 0 1.008-.322 3.301 1.23.957-.266 1.983-.399 3.003-.404 1.02.005 2.047.138 3.006.404 2.291-1.552 3.297-1.23 3.297-1.23.653 1.653.242 2.874.118 3.176.77.84 1.235 1.911 1.235 3.221 0 4.609-2.807 5.624-5.479 5.921.43.372.823 1.102.823 2.222v3.293c0 .319.192.694.801.576 4.765-1.589 8.199-6.086 8.199-11.386 0-6.627-5.373-12-12-12z" />
+                        </svg>
+                      ) : (
+                        <svg
+                          className="w-4 h-4 mr-2"
+                          fill="none"
+                          stroke="currentColor"
+                          viewBox="0 0 24 24"
+                        >
+                          <path
+                            strokeLinecap="round"
+                            strokeLinejoin="round"
+                            strokeWidth={2}
+                            d="M10 6H6a2 2 0 00-2 2v10a2 2 0 002 2h10a2 2 0 002-2v-4M14 4h6m0 0v6m0-6L10 14"
+                          />
+                        </svg>
+                      )}
+                      Open {formatRemoteProvider(remote.provider)}
+                    </DropdownMenuItem>
+                  )}
+                  {remote?.branchUrl && (
+                    <DropdownMenuItem onClick={() => handleOpenRemoteUrl(remote.branchUrl)}>
+                      <svg className="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                        <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M6 3v12M18 9a3 3 0 100-6 3 3 0 000 6zM6 21a3 3 0 100-6 3 3 0 000 6zM18 9a9 9 0 01-9 9" />
+                      </svg>
+                      Open Branch
+                    </DropdownMenuItem>
+                  )}
+                  {remote?.compareUrl && (
+                    <DropdownMenuItem onClick={() => handleOpenRemoteUrl(remote.compareUrl)}>
+                      <svg className="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                        <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M8 7h12m0 0l-4-4m4 4l-4 4m0 6H4m0 0l4 4m-4-4l4-4" />
                       </svg>
-                      Open GitHub
+                      Compare with {remote.defaultBranch}
                     </DropdownMenuItem>
                   )}
                   <DropdownMenuSeparator />
+                  <DropdownMenuItem onClick={handlePauseToggle}>
+                    {session.status === 'paused' ? (
+                      <svg className="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                        <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M5 3l14 9-14 9V3z" />
+                      </svg>
+                    ) : (
+                      <svg className="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                        <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M10 9v6m4-6v6" />
+                      </svg>
+                    )}
+                    {session.status === 'paused' ? 'Continue Session' : 'Pause Session'}
+                  </DropdownMenuItem>
                   <DropdownMenuItem onClick={handleKillSession} className="text-destructive focus:text-destructive">
                     <svg
                       className="w-4 h-4 mr-2"
@@ -314,6 +465,38 @@ export function SessionCard({ session, onClick }: SessionCardProps) {
               <span className="text-xs text-muted-foreground truncate">
                 {session.gitBranch}
               </span>
+              {session.worktree && (
+                <span className="text-xs text-muted-foreground/70 truncate" title="Worktree">
+                  ({session.worktree})
+                </span>
+              )}
+            </div>
+          )}
+
+          {/* Working tree: interrupted merge/rebase, changes and last commit */}
+          {session.gitStatus && (
+            <div className="flex items-center gap-2 mb-3 text-xs text-muted-foreground min-w-0">
+              {session.gitStatus.operation && (
+                <Badge variant="outline" className="text-[10px] px-1.5 py-0 border-orange-500/50 text-orange-600 dark:text-orange-400">
+                  {session.gitStatus.operation === 'merge' ? 'Merging' : 'Rebasing'}
+                </Badge>
+              )}
+              {formatGitChanges(session.gitStatus).map((part) => (
+                <span key={part} className="shrink-0">{part}</span>
+              ))}
+              {session.gitStatus.lastCommitSubject && (
+                <span className="truncate opacity-70" title={session.gitStatus.lastCommitSubject}>
+                  {session.gitStatus.lastCommitSubject}
+                </span>
+              )}
+            </div>
+          )}
+
+          {/* Model and CLI version */}
+          {(session.model || session.cliVersion) && (
+            <div className="flex items-center gap-2 mb-3 text-xs text-muted-foreground">
+              {session.model && <span className="truncate">{formatModelName(session.model)}</span>}
+              {session.cliVersion && <span className="shrink-0">v{session.cliVersion}</span>}
             </div>
           )}
 
@@ -326,12 +509,163 @@ export function SessionCard({ session, onClick }: SessionCardProps) {
             )}
           </div>
 
+          {/* CPU and memory of the agent and its processes */}
+          <div
+            className="flex items-center gap-3 mt-3 text-xs text-muted-foreground"
+            title={`Agent and ${session.childProcesses.length} child processes, last 10 minutes`}
+          >
+            <span className="flex items-center gap-1">
+              CPU {session.treeCpuUsage.toFixed(0)}%
+              <Sparkline values={resourceHistory.map(s => s.cpuUsage)} max={100} className="opacity-70" />
+            </span>
+            <span className="flex items-center gap-1">
+              {formatBytes(session.treeMemory)}
+              <Sparkline values={resourceHistory.map(s => s.memory)} className="opacity-70" />
+            </span>
+            {session.processStartedAt && (
+              <span className="ml-auto shrink-0" title={`Process started ${session.processStartedAt}`}>
+                up {formatDuration(session.processStartedAt)}
+              </span>
+            )}
+          </div>
+
+          {/* Context window fill level */}
+          {session.contextUsagePercent !== null && (
+            <div
+              className="mt-3"
+              title={`${formatTokenCount(session.contextTokens ?? 0)} tokens in context${session.compactionCount > 0 ? `, compacted ${session.compactionCount}x` : ''}`}
+            >
+              <div className="flex items-center justify-between text-xs text-muted-foreground mb-1">
+                <span className={contextNearlyFull ? 'text-orange-400' : undefined}>
+                  {contextNearlyFull ? 'Context nearly full' : 'Context'}
+                </span>
+                <span>
+                  {Math.round(session.contextUsagePercent)}%
+                  {session.compactionCount > 0 && ` · ${session.compactionCount} compaction${session.compactionCount === 1 ? '' : 's'}`}
+                </span>
+              </div>
+              <div className="h-1 rounded-full bg-white/10 overflow-hidden">
+                <div
+                  className={`h-full rounded-full ${contextNearlyFull ? 'bg-orange-400' : 'bg-white/40'}`}
+                  style={{ width: `${session.contextUsagePercent}%` }}
+                />
+              </div>
+            </div>
+          )}
+
           {/* Footer: Status Badge + Time */}
           <div className="flex items-center justify-between pt-3 mt-3 border-t border-border">
             <div className="flex items-center gap-2">
-              <Badge variant="outline" className={config.badgeClassName}>
+              <Badge
+                variant="outline"
+                className={config.badgeClassName}
+                title={`Since ${new Date(session.statusSince).toLocaleString()}`}
+              >
                 {config.label}
+                <span className="opacity-70">{formatDuration(session.statusSince)}</span>
               </Badge>
+              {session.permissionMode === 'bypassPermissions' && (
+                <Badge
+                  variant="outline"
+                  className="border-red-500/50 text-red-300 bg-red-500/20"
+                  title="Running with --dangerously-skip-permissions"
+                >
+                  Bypass permissions
+                </Badge>
+              )}
+              {session.permissionMode === 'acceptEdits' && (
+                <Badge variant="outline" className="border-white/20 text-white/60 bg-white/5">
+                  Accept edits
+                </Badge>
+              )}
+              {session.permissionMode === 'plan' && (
+                <Badge variant="outline" className="border-white/20 text-white/60 bg-white/5">
+                  Plan
+                </Badge>
+              )}
+              {session.bindingAmbiguous && (
+                <span
+                  className="text-xs text-orange-400"
+                  title="Several sessions share this directory and this one could not be matched to its transcript with certainty"
+                >
+                  ?
+                </span>
+              )}
+              {session.flaggedRules.length > 0 && (
+                <Badge
+                  variant="outline"
+                  className="border-red-500/50 text-red-300 bg-red-500/20 cursor-pointer"
+                  title={`Audit rules matched: ${session.flaggedRules.join(', ')}`}
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    handleShowAudit();
+                  }}
+                >
+                  {session.flaggedRules.length === 1 ? session.flaggedRules[0] : `${session.flaggedRules.length} alerts`}
+                </Badge>
+              )}
+              {session.secretFindingCount > 0 && (
+                <Badge
+                  variant="outline"
+                  className="border-red-500/50 text-red-300 bg-red-500/20 cursor-pointer"
+                  title="Credentials were found in the transcript"
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    handleShowSecrets();
+                  }}
+                >
+                  {session.secretFindingCount === 1 ? '1 secret' : `${session.secretFindingCount} secrets`}
+                </Badge>
+              )}
+              {session.origin !== 'terminal' && (
+                <Badge
+                  variant="outline"
+                  className="border-sky-500/50 text-sky-300 bg-sky-500/20"
+                  title={`Started from ${formatOrigin(session.origin)}`}
+                >
+                  {formatOrigin(session.origin)}
+                </Badge>
+              )}
+              {session.host && (
+                <Badge
+                  variant="outline"
+                  className="border-indigo-500/50 text-indigo-300 bg-indigo-500/20"
+                  title={`Running on ${session.host}`}
+                >
+                  {session.host}
+                </Badge>
+              )}
+              {session.container && (
+                <Badge
+                  variant="outline"
+                  className="border-cyan-500/50 text-cyan-300 bg-cyan-500/20"
+                  title={`Running in ${session.container.runtime ?? 'a'} container ${session.container.id}`}
+                >
+                  {session.container.name ?? session.container.id.slice(0, 12)}
+                </Badge>
+              )}
+              {session.editConflicts.length > 0 && (
+                <Badge
+                  variant="outline"
+                  className="border-orange-500/50 text-orange-300 bg-orange-500/20"
+                  title={`Another session edited the same files recently:\n${session.editConflicts.map(c => c.path).join('\n')}`}
+                >
+                  Edit conflict
+                </Badge>
+              )}
+              {session.childProcesses.length > 0 && (
+                <Badge
+                  variant="outline"
+                  className="cursor-pointer"
+                  title={session.childProcesses.map(c => c.command || c.name).join('\n')}
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    setIsProcessesOpen(true);
+                  }}
+                >
+                  {session.childProcesses.length === 1 ? '1 process' : `${session.childProcesses.length} processes`}
+                </Badge>
+              )}
               {session.activeSubagentCount > 0 && (
                 <span className="text-xs text-muted-foreground">
                   [+{session.activeSubagentCount}]
@@ -364,7 +698,7 @@ export function SessionCard({ session, onClick }: SessionCardProps) {
               autoFocus
             />
             <p className="text-xs text-muted-foreground mt-2">
-              Original: {session.projectName}
+              Original: {defaultName}
             </p>
           </div>
           <DialogFooter className="flex gap-2">
@@ -416,6 +750,156 @@ export function SessionCard({ session, onClick }: SessionCardProps) {
           </DialogFooter>
         </DialogContent>
       </Dialog>
+
+      {/* Files Touched Dialog */}
+      <Dialog open={touchedFiles !== null} onOpenChange={(open) => !open && setTouchedFiles(null)}>
+        <DialogContent onClick={(e) => e.stopPropagation()}>
+          <DialogHeader>
+            <DialogTitle>Files Touched</DialogTitle>
+          </DialogHeader>
+          <div className="py-2 max-h-80 overflow-y-auto">
+            {touchedFiles?.length === 0 && (
+              <p className="text-sm text-muted-foreground">This session hasn't edited any files yet.</p>
+            )}
+            {touchedFiles?.map((file) => (
+              <div key={file.path} className="flex items-center justify-between gap-3 py-1 text-xs">
+                <span
+                  className={`truncate ${conflictPaths.has(file.path) ? 'text-orange-400' : 'text-foreground'}`}
+                  title={conflictPaths.has(file.path) ? `${file.path} (also edited by another session)` : file.path}
+                >
+                  {truncatePath(file.path)}
+                </span>
+                <span className="shrink-0 text-muted-foreground">
+                  {file.editCount}x · {formatTimeAgo(file.lastTouchedAt)}
+                </span>
+              </div>
+            ))}
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setTouchedFiles(null)}>
+              Close
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+
+      {/* Audit Dialog */}
+      <Dialog open={auditEntries !== null} onOpenChange={(open) => !open && setAuditEntries(null)}>
+        <DialogContent className="max-w-2xl" onClick={(e) => e.stopPropagation()}>
+          <DialogHeader>
+            <DialogTitle>Activity Audit</DialogTitle>
+          </DialogHeader>
+          <div className="py-2 max-h-96 overflow-y-auto space-y-2">
+            {auditEntries?.length === 0 && (
+              <p className="text-sm text-muted-foreground">No commands or web requests yet.</p>
+            )}
+            {auditEntries?.slice().reverse().map((entry) => (
+              <div key={entry.toolUseId || `${entry.lineNumber}-${entry.detail}`} className="text-xs">
+                <div className="flex items-center gap-2">
+                  <span className="shrink-0 text-muted-foreground w-16">
+                    {AUDIT_KIND_LABELS[entry.kind]}
+                  </span>
+                  <code
+                    className={`truncate font-mono ${entry.matchedRules.length > 0 ? 'text-red-300' : 'text-foreground'}`}
+                    title={entry.detail}
+                  >
+                    {entry.detail}
+                  </code>
+                  <span className={`shrink-0 ml-auto ${entry.outcome?.isError ? 'text-orange-400' : 'text-muted-foreground'}`}>
+                    {entry.outcome === null
+                      ? 'running'
+                      : entry.outcome.exitCode !== null && entry.kind === 'command'
+                        ? `exit ${entry.outcome.exitCode}`
+                        : entry.outcome.isError ? 'error' : 'ok'}
+                  </span>
+                </div>
+                {(entry.matchedRules.length > 0 || entry.outcome?.error) && (
+                  <div className="pl-[4.5rem] text-muted-foreground truncate" title={entry.outcome?.error ?? undefined}>
+                    {entry.matchedRules.length > 0 && <span className="text-red-300 mr-2">{entry.matchedRules.join(', ')}</span>}
+                    {entry.outcome?.error}
+                  </div>
+                )}
+              </div>
+            ))}
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setAuditEntries(null)}>
+              Close
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+
+      {/* Secrets Dialog */}
+      <Dialog open={secretFindings !== null} onOpenChange={(open) => !open && setSecretFindings(null)}>
+        <DialogContent className="max-w-xl" onClick={(e) => e.stopPropagation()}>
+          <DialogHeader>
+            <DialogTitle>Secrets in Transcript</DialogTitle>
+          </DialogHeader>
+          <div className="py-2 max-h-96 overflow-y-auto space-y-1">
+            {secretFindings && secretFindings.length > 0 && (
+              <p className="text-sm text-muted-foreground">
+                The agent is still writing to this transcript. Redact it from History once the session has ended.
+              </p>
+            )}
+            {secretFindings?.length === 0 && (
+              <p className="text-sm text-muted-foreground">No secrets found.</p>
+            )}
+            {secretFindings?.map((finding, i) => (
+              <div key={`${finding.lineNumber}-${i}`} className="flex items-center gap-2 text-xs">
+                <span className="shrink-0 text-muted-foreground w-16">line {finding.lineNumber}</span>
+                <span className="shrink-0 text-red-300">{finding.kind}</span>
+                <code className="truncate font-mono text-foreground">{finding.preview}</code>
+              </div>
+            ))}
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setSecretFindings(null)}>
+              Close
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+
+      {/* Child Processes Dialog */}
+      <Dialog open={isProcessesOpen} onOpenChange={(open) => { setIsProcessesOpen(open); setProcessError(null); }}>
+        <DialogContent className="max-w-2xl" onClick={(e) => e.stopPropagation()}>
+          <DialogHeader>
+            <DialogTitle>Child Processes</DialogTitle>
+          </DialogHeader>
+          <div className="py-2 max-h-96 overflow-y-auto space-y-1">
+            {processError && <p className="text-sm text-red-400">{processError}</p>}
+            {session.childProcesses.length === 0 && (
+              <p className="text-sm text-muted-foreground">The agent isn't running any processes.</p>
+            )}
+            {session.childProcesses.map((child) => (
+              <div key={child.pid} className="flex items-center gap-3 text-xs">
+                <code className="truncate font-mono text-foreground" title={child.command}>
+                  {child.command || child.name}
+                </code>
+                <span className="shrink-0 ml-auto text-muted-foreground">
+                  {child.cpuUsage.toFixed(0)}% · {formatBytes(child.memory)} · {formatSeconds(child.runtimeSecs)}
+                </span>
+                <Button size="sm" variant="ghost" className="shrink-0 h-6 px-2 text-destructive" onClick={() => handleKillChild(child.pid)}>
+                  Stop
+                </Button>
+              </div>
+            ))}
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setIsProcessesOpen(false)}>
+              Close
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+
+      <ExportDialog
+        open={isExportOpen}
+        onOpenChange={setIsExportOpen}
+        sessionId={session.id}
+        agentType={session.agentType}
+      />
     </>
   );
 }
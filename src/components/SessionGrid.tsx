@@ -1,12 +1,22 @@
-import { Session } from '../types/session';
+import { Session, SessionGroup, StatusCounts } from '../types/session';
 import { SessionCard } from './SessionCard';
 
 interface SessionGridProps {
   sessions: Session[];
+  groups: SessionGroup[];
   onSessionClick: (session: Session) => void;
 }
 
-export function SessionGrid({ sessions, onSessionClick }: SessionGridProps) {
+const STATUS_COUNT_LABELS: { key: keyof StatusCounts; label: string; className: string }[] = [
+  { key: 'waiting', label: 'waiting', className: 'text-yellow-600 dark:text-yellow-400' },
+  { key: 'thinking', label: 'thinking', className: 'text-purple-600 dark:text-purple-400' },
+  { key: 'processing', label: 'processing', className: 'text-green-600 dark:text-green-400' },
+  { key: 'compacting', label: 'compacting', className: 'text-blue-600 dark:text-blue-400' },
+  { key: 'idle', label: 'idle', className: 'text-muted-foreground' },
+  { key: 'paused', label: 'paused', className: 'text-sky-600 dark:text-sky-400' },
+];
+
+function SessionCards({ sessions, onSessionClick }: { sessions: Session[]; onSessionClick: (session: Session) => void }) {
   return (
     <div className="grid grid-cols-1 sm:grid-cols-2 lg:grid-cols-3 gap-4">
       {sessions.map((session) => (
@@ -19,3 +29,48 @@ export function SessionGrid({ sessions, onSessionClick }: SessionGridProps) {
     </div>
   );
 }
+
+export function SessionGrid({ sessions, groups, onSessionClick }: SessionGridProps) {
+  // Only group when at least one repository has several sessions, otherwise headers add nothing
+  const shouldGroup = groups.length > 1 && groups.length < sessions.length;
+  if (!shouldGroup) {
+    return <SessionCards sessions={sessions} onSessionClick={onSessionClick} />;
+  }
+
+  // Keep the stable card order within each group; groups follow their first session
+  const groupOf = new Map<string, SessionGroup>();
+  groups.forEach(g => g.sessionIds.forEach(id => groupOf.set(id, g)));
+  const ordered: { group: SessionGroup; sessions: Session[] }[] = [];
+  for (const session of sessions) {
+    const group = groupOf.get(session.id);
+    if (!group) continue;
+    let entry = ordered.find(o => o.group.id === group.id);
+    if (!entry) {
+      entry = { group, sessions: [] };
+      ordered.push(entry);
+    }
+    entry.sessions.push(session);
+  }
+
+  return (
+    <div className="flex flex-col gap-6">
+      {ordered.map(({ group, sessions: groupSessions }) => (
+        <section key={group.id}>
+          <div className="flex items-baseline gap-3 mb-2">
+            <h2 className="text-sm font-semibold text-foreground truncate" title={group.id}>
+              {group.name}
+            </h2>
+            <div className="flex items-center gap-2 text-xs">
+              {STATUS_COUNT_LABELS.filter(({ key }) => group.statusCounts[key] > 0).map(({ key, label, className }) => (
+                <span key={key} className={className}>
+                  {group.statusCounts[key]} {label}
+                </span>
+              ))}
+            </div>
+          </div>
+          <SessionCards sessions={groupSessions} onSessionClick={onSessionClick} />
+        </section>
+      ))}
+    </div>
+  );
+}
@@ -8,6 +8,7 @@ import {
   DialogFooter,
 } from '@/components/ui/dialog';
 import { Button } from '@/components/ui/button';
+import type { RemoteHost, RemoteHostStatus, SessionOrigin } from '@/types/session';
 
 interface SettingsProps {
   isOpen: boolean;
@@ -17,12 +18,75 @@ interface SettingsProps {
 const STORAGE_KEY = 'claude-sessions-hotkey';
 const DEFAULT_HOTKEY = 'Control+Space';
 
+const IDLE_THRESHOLD_KEY = 'agent-sessions-idle-threshold';
+const DEFAULT_IDLE_THRESHOLD = 15 * 60;
+const IDLE_THRESHOLD_OPTIONS = [
+  { label: '5 minutes', seconds: 5 * 60 },
+  { label: '15 minutes', seconds: 15 * 60 },
+  { label: '1 hour', seconds: 60 * 60 },
+  { label: '4 hours', seconds: 4 * 60 * 60 },
+  { label: '1 day', seconds: 24 * 60 * 60 },
+  { label: 'Never', seconds: 0 },
+];
+
+function getIdleThreshold(): number {
+  const stored = localStorage.getItem(IDLE_THRESHOLD_KEY);
+  return stored !== null ? Number(stored) : DEFAULT_IDLE_THRESHOLD;
+}
+
+const VISIBLE_ORIGINS_KEY = 'agent-sessions-visible-origins';
+const DEFAULT_VISIBLE_ORIGINS: SessionOrigin[] = ['terminal', 'headless'];
+const ORIGIN_OPTIONS: { label: string; origin: SessionOrigin }[] = [
+  { label: 'Terminal', origin: 'terminal' },
+  { label: 'Headless (-p)', origin: 'headless' },
+  { label: 'VS Code', origin: 'vsCode' },
+  { label: 'JetBrains', origin: 'jetBrains' },
+  { label: 'Zed', origin: 'zed' },
+  { label: 'Agent SDK', origin: 'sdk' },
+];
+
+function getVisibleOrigins(): SessionOrigin[] {
+  const stored = localStorage.getItem(VISIBLE_ORIGINS_KEY);
+  return stored !== null ? JSON.parse(stored) : DEFAULT_VISIBLE_ORIGINS;
+}
+
+const REMOTE_HOSTS_KEY = 'agent-sessions-remote-hosts';
+
+// One host per line, optionally followed by the collector command
+function parseRemoteHosts(text: string): RemoteHost[] {
+  return text
+    .split('\n')
+    .map((line) => line.trim())
+    .filter((line) => line.length > 0)
+    .map((line) => {
+      const space = line.indexOf(' ');
+      return space === -1
+        ? { host: line, command: null }
+        : { host: line.slice(0, space), command: line.slice(space + 1).trim() };
+    });
+}
+
 export function Settings({ isOpen, onClose }: SettingsProps) {
   const [hotkey, setHotkey] = useState(DEFAULT_HOTKEY);
   const [isRecording, setIsRecording] = useState(false);
   const [recordedKeys, setRecordedKeys] = useState<string[]>([]);
   const [error, setError] = useState<string | null>(null);
   const [saved, setSaved] = useState(false);
+  const [idleThreshold, setIdleThreshold] = useState(getIdleThreshold);
+  const [visibleOrigins, setVisibleOrigins] = useState(getVisibleOrigins);
+  const [remoteHosts, setRemoteHosts] = useState(() => localStorage.getItem(REMOTE_HOSTS_KEY) ?? '');
+  const [remoteStatus, setRemoteStatus] = useState<RemoteHostStatus[]>([]);
+
+  // Poll connection state while the dialog is open
+  useEffect(() => {
+    if (!isOpen) return;
+    const load = () => {
+      invoke<RemoteHostStatus[]>('get_remote_host_status').then(setRemoteStatus).catch(console.error);
+    };
+    load();
+    const interval = setInterval(load, 2000);
+    return () => clearInterval(interval);
+  }, [isOpen]);
 
   // Load saved hotkey on mount
   useEffect(() => {
@@ -105,6 +169,39 @@ export function Settings({ isOpen, onClose }: SettingsProps) {
     }
   };
 
+  const handleIdleThresholdChange = async (seconds: number) => {
+    try {
+      await invoke('set_idle_threshold', { seconds });
+      localStorage.setItem(IDLE_THRESHOLD_KEY, String(seconds));
+      setIdleThreshold(seconds);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  const handleOriginToggle = async (origin: SessionOrigin) => {
+    const origins = visibleOrigins.includes(origin)
+      ? visibleOrigins.filter((o) => o !== origin)
+      : [...visibleOrigins, origin];
+    try {
+      await invoke('set_visible_origins', { origins });
+      localStorage.setItem(VISIBLE_ORIGINS_KEY, JSON.stringify(origins));
+      setVisibleOrigins(origins);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  const handleRemoteHostsSave = async () => {
+    try {
+      await invoke('set_remote_hosts', { hosts: parseRemoteHosts(remoteHosts) });
+      localStorage.setItem(REMOTE_HOSTS_KEY, remoteHosts);
+      setError(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
   const handleClear = async () => {
     try {
       await invoke('unregister_shortcut');
@@ -160,6 +257,73 @@ export function Settings({ isOpen, onClose }: SettingsProps) {
           )}
         </div>
 
+        <div className="space-y-3">
+          <label className="text-sm font-medium text-foreground" htmlFor="idle-threshold">
+            Mark Idle After
+          </label>
+          <select
+            id="idle-threshold"
+            className="w-full h-9 rounded-lg border border-border bg-muted/50 px-3 text-sm text-foreground"
+            value={idleThreshold}
+            onChange={(e) => handleIdleThresholdChange(Number(e.target.value))}
+          >
+            {IDLE_THRESHOLD_OPTIONS.map((option) => (
+              <option key={option.seconds} value={option.seconds}>
+                {option.label}
+              </option>
+            ))}
+          </select>
+          <p className="text-xs text-muted-foreground">
+            Sessions waiting for input longer than this are shown as idle
+          </p>
+        </div>
+
+        <div className="space-y-3">
+          <label className="text-sm font-medium text-foreground">
+            Show Sessions Started From
+          </label>
+          <div className="grid grid-cols-2 gap-2">
+            {ORIGIN_OPTIONS.map((option) => (
+              <label key={option.origin} className="flex items-center gap-2 text-sm text-foreground">
+                <input
+                  type="checkbox"
+                  checked={visibleOrigins.includes(option.origin)}
+                  onChange={() => handleOriginToggle(option.origin)}
+                />
+                {option.label}
+              </label>
+            ))}
+          </div>
+        </div>
+
+        <div className="space-y-3">
+          <label className="text-sm font-medium text-foreground" htmlFor="remote-hosts">
+            Remote Hosts
+          </label>
+          <textarea
+            id="remote-hosts"
+            className="w-full min-h-16 rounded-lg border border-border bg-muted/50 px-3 py-2 text-sm font-mono text-foreground"
+            placeholder="devbox"
+            value={remoteHosts}
+            onChange={(e) => setRemoteHosts(e.target.value)}
+            onBlur={handleRemoteHostsSave}
+          />
+          <p className="text-xs text-muted-foreground">
+            One ssh host per line. agent-sessions must be installed there; add a command after the host to run it from elsewhere
+          </p>
+          {remoteStatus.map((status) => (
+            <div key={status.host} className="flex items-center gap-2 text-xs">
+              <span className={`w-1.5 h-1.5 rounded-full ${status.connected ? 'bg-emerald-400' : 'bg-destructive'}`} />
+              <span className="font-mono text-foreground">{status.host}</span>
+              <span className="text-muted-foreground truncate">
+                {status.connected
+                  ? `${status.sessionCount} session${status.sessionCount === 1 ? '' : 's'}`
+                  : status.error ?? 'Connecting...'}
+              </span>
+            </div>
+          ))}
+        </div>
+
         <DialogFooter>
           <Button variant="ghost" size="sm" onClick={handleClear}>
             Clear
@@ -181,3 +345,28 @@ export function useHotkeyInit() {
     }
   }, []);
 }
+
+export function useIdleThresholdInit() {
+  useEffect(() => {
+    if (localStorage.getItem(IDLE_THRESHOLD_KEY) !== null) {
+      invoke('set_idle_threshold', { seconds: getIdleThreshold() }).catch(console.error);
+    }
+  }, []);
+}
+
+export function useRemoteHostsInit() {
+  useEffect(() => {
+    const saved = localStorage.getItem(REMOTE_HOSTS_KEY);
+    if (saved) {
+      invoke('set_remote_hosts', { hosts: parseRemoteHosts(saved) }).catch(console.error);
+    }
+  }, []);
+}
+
+export function useVisibleOriginsInit() {
+  useEffect(() => {
+    if (localStorage.getItem(VISIBLE_ORIGINS_KEY) !== null) {
+      invoke('set_visible_origins', { origins: getVisibleOrigins() }).catch(console.error);
+    }
+  }, []);
+}
@@ -0,0 +1,30 @@
+interface SparklineProps {
+  values: number[];
+  // Top of the scale; defaults to the largest value
+  max?: number;
+  className?: string;
+}
+
+// A small line chart without axes, scaled to its own values
+export function Sparkline({ values, max, className }: SparklineProps) {
+  const width = 60;
+  const height = 16;
+  if (values.length < 2) {
+    return <svg width={width} height={height} className={className} />;
+  }
+
+  const top = Math.max(max ?? Math.max(...values), 1e-9);
+  const points = values
+    .map((value, i) => {
+      const x = (i / (values.length - 1)) * width;
+      const y = height - 1 - (Math.min(value, top) / top) * (height - 2);
+      return `${x.toFixed(1)},${y.toFixed(1)}`;
+    })
+    .join(' ');
+
+  return (
+    <svg width={width} height={height} viewBox={`0 0 ${width} ${height}`} className={className}>
+      <polyline points={points} fill="none" stroke="currentColor" strokeWidth={1} strokeLinejoin="round" />
+    </svg>
+  );
+}
@@ -1,6 +1,6 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { Session, SessionsResponse } from '../types/session';
+import { Session, SessionGroup, SessionsResponse } from '../types/session';
 
 const POLL_INTERVAL = 2000; // 2 seconds
 
@@ -13,7 +13,8 @@ function getOrderingPriority(status: string): number {
     case 'waiting':
       return 0; // All active states - same ordering priority
     case 'idle':
-      return 1; // Only idle causes reordering
+    case 'paused':
+      return 1; // Only idle and paused cause reordering
     default:
       return 2;
   }
@@ -79,6 +80,7 @@ export function useSessions() {
   const [sessions, setSessions] = useState<Session[]>([]);
   const [totalCount, setTotalCount] = useState(0);
   const [waitingCount, setWaitingCount] = useState(0);
+  const [groups, setGroups] = useState<SessionGroup[]>([]);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const sessionsRef = useRef<Session[]>([]);
@@ -100,6 +102,7 @@ export function useSessions() {
       setSessions([...stableSessions]);
       setTotalCount(response.totalCount);
       setWaitingCount(response.waitingCount);
+      setGroups(response.groups);
       setError(null);
 
       // Update tray icon title with counts
@@ -116,6 +119,7 @@ export function useSessions() {
       await invoke('focus_session', {
         pid: session.pid,
         projectPath: session.projectPath,
+        host: session.host,
       });
     } catch (err) {
       console.error('Failed to focus session:', err);
@@ -137,6 +141,7 @@ export function useSessions() {
     sessions,
     totalCount,
     waitingCount,
+    groups,
     isLoading,
     error,
     refresh: fetchSessions,
@@ -1,3 +1,5 @@
+import type { GitStatus, RemoteProvider, SessionOrigin } from '../types/session';
+
 export function formatTimeAgo(timestamp: string): string {
   const date = new Date(timestamp);
   const now = new Date();
@@ -14,10 +16,83 @@ export function formatTimeAgo(timestamp: string): string {
   return `${diffDays}d ago`;
 }
 
+// Format how long ago a timestamp was without a suffix, e.g. "5m", "3h", "2d"
+export function formatDuration(since: string): string {
+  const diffMins = Math.floor((Date.now() - new Date(since).getTime()) / 60000);
+
+  if (diffMins < 1) return '<1m';
+  if (diffMins < 60) return `${diffMins}m`;
+
+  const diffHours = Math.floor(diffMins / 60);
+  if (diffHours < 24) return `${diffHours}h`;
+
+  return `${Math.floor(diffHours / 24)}d`;
+}
+
 export function truncatePath(path: string): string {
   return path.replace(/^\/Users\/[^/]+/, '~');
 }
 
+// Shorten model ids for display, e.g. "claude-opus-4-1-20250805" -> "opus-4-1"
+export function formatModelName(model: string): string {
+  return model.replace(/^claude-/, '').replace(/-\d{8}$/, '');
+}
+
+// Context usage above which a session is close to auto-compacting
+export const CONTEXT_WARNING_PERCENT = 80;
+
+// Format a token count compactly, e.g. 1500 -> "1.5k", 145000 -> "145k"
+export function formatTokenCount(tokens: number): string {
+  if (tokens < 1000) return `${tokens}`;
+  if (tokens < 10_000) return `${(tokens / 1000).toFixed(1)}k`;
+  if (tokens < 1_000_000) return `${Math.round(tokens / 1000)}k`;
+  return `${(tokens / 1_000_000).toFixed(1)}M`;
+}
+
+// Format a time span in seconds for reports, e.g. 5400 -> "1h 30m"
+export function formatSeconds(secs: number): string {
+  const minutes = Math.floor(secs / 60);
+  if (minutes < 1) return secs > 0 ? '<1m' : '0m';
+  if (minutes < 60) return `${minutes}m`;
+  const hours = Math.floor(minutes / 60);
+  return minutes % 60 ? `${hours}h ${minutes % 60}m` : `${hours}h`;
+}
+
+// Summarize a working tree in short parts, e.g. ["+10 -2", "3 modified", "↑1"]
+export function formatGitChanges(status: GitStatus): string[] {
+  const parts: string[] = [];
+  if (status.insertions || status.deletions) parts.push(`+${status.insertions} -${status.deletions}`);
+  if (status.conflicted) parts.push(`${status.conflicted} conflicted`);
+  if (status.modified) parts.push(`${status.modified} modified`);
+  if (status.untracked) parts.push(`${status.untracked} untracked`);
+  if (status.ahead) parts.push(`↑${status.ahead}`);
+  if (status.behind) parts.push(`↓${status.behind}`);
+  return parts;
+}
+
+// Display name of a remote's hosting service
+export function formatRemoteProvider(provider: RemoteProvider): string {
+  switch (provider) {
+    case 'github': return 'GitHub';
+    case 'gitlab': return 'GitLab';
+    case 'bitbucket': return 'Bitbucket';
+    case 'gitea': return 'Gitea';
+    default: return 'Remote';
+  }
+}
+
+// Display name of what launched a session
+export function formatOrigin(origin: SessionOrigin): string {
+  switch (origin) {
+    case 'terminal': return 'Terminal';
+    case 'zed': return 'Zed';
+    case 'vsCode': return 'VS Code';
+    case 'jetBrains': return 'JetBrains';
+    case 'sdk': return 'SDK';
+    case 'headless': return 'Headless';
+  }
+}
+
 export const statusConfig = {
   waiting: {
     color: 'bg-white/50',
@@ -59,4 +134,21 @@ export const statusConfig = {
     badgeClassName: 'border-white/20 text-white/50 bg-white/5',
     label: 'Idle',
   },
+  paused: {
+    color: 'bg-sky-400',
+    fillColor: 'fill-sky-400',
+    cardBg: 'bg-sky-400/10',
+    cardBorder: 'border-sky-400/30',
+    badgeClassName: 'border-sky-400/40 text-sky-300 bg-sky-400/20',
+    label: 'Paused',
+  },
 } as const;
+
+export function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  const kb = bytes / 1024;
+  if (kb < 1024) return `${kb.toFixed(0)} KB`;
+  const mb = kb / 1024;
+  if (mb < 1024) return `${mb.toFixed(1)} MB`;
+  return `${(mb / 1024).toFixed(1)} GB`;
+}
@@ -1,4 +1,4 @@
-export type SessionStatus = 'waiting' | 'processing' | 'thinking' | 'compacting' | 'idle';
+export type SessionStatus = 'waiting' | 'processing' | 'thinking' | 'compacting' | 'idle' | 'paused';
 
 export type AgentType = 'claude' | 'opencode';
 
@@ -7,19 +7,280 @@ export interface Session {
   agentType: AgentType;
   projectName: string;
   projectPath: string;
+  title: string | null;
   gitBranch: string | null;
-  githubUrl: string | null;
+  repoId: string | null;
+  worktree: string | null;
+  gitStatus: GitStatus | null;
+  remote: GitRemote | null;
+  model: string | null;
+  permissionMode: string | null;
+  cliVersion: string | null;
+  contextTokens: number | null;
+  contextUsagePercent: number | null;
+  compactionCount: number;
+  totalInputTokens: number;
+  totalOutputTokens: number;
   status: SessionStatus;
+  statusSince: string;
   lastMessage: string | null;
   lastMessageRole: 'user' | 'assistant' | null;
   lastActivityAt: string;
   pid: number;
   cpuUsage: number;
+  memory: number;
+  processStartedAt: string | null;
+  treeCpuUsage: number;
+  treeMemory: number;
+  childProcesses: ChildProcess[];
+  container: ContainerRef | null;
+  // Remote machine the session was collected from, null for this one
+  host: string | null;
+  origin: SessionOrigin;
   activeSubagentCount: number;
+  editConflicts: EditConflict[];
+  flaggedRules: string[];
+  secretFindingCount: number;
+  bindingAmbiguous: boolean;
+}
+
+export type GitOperation = 'merge' | 'rebase';
+
+export interface GitStatus {
+  modified: number;
+  untracked: number;
+  conflicted: number;
+  insertions: number;
+  deletions: number;
+  upstream: string | null;
+  ahead: number;
+  behind: number;
+  lastCommitSubject: string | null;
+  operation: GitOperation | null;
+}
+
+export type RemoteProvider = 'github' | 'gitlab' | 'bitbucket' | 'gitea' | 'unknown';
+
+// Web links for the origin remote
+export interface GitRemote {
+  provider: RemoteProvider;
+  repoUrl: string;
+  defaultBranch: string | null;
+  branchUrl: string | null;
+  compareUrl: string | null;
+}
+
+// A process the agent started, e.g. a build or a dev server
+export interface ChildProcess {
+  pid: number;
+  parentPid: number;
+  name: string;
+  command: string;
+  cpuUsage: number;
+  memory: number;
+  startTime: number;
+  runtimeSecs: number;
+}
+
+// What launched the agent; only origins enabled in the settings are listed
+export type SessionOrigin = 'terminal' | 'zed' | 'vsCode' | 'jetBrains' | 'sdk' | 'headless';
+
+// Container an agent runs in; id is `mntns-<inode>` when the runtime is unknown
+export interface ContainerRef {
+  id: string;
+  runtime: string | null;
+  name: string | null;
+  // The project is bind-mounted from the host and shown at its host path
+  projectOnHost: boolean;
+}
+
+// A machine to collect sessions from over ssh, passed to set_remote_hosts
+export interface RemoteHost {
+  host: string;
+  command: string | null;
+}
+
+// Returned by get_remote_host_status
+export interface RemoteHostStatus {
+  host: string;
+  connected: boolean;
+  sessionCount: number;
+  lastUpdate: string | null;
+  error: string | null;
+}
+
+// CPU and memory of a session's process tree, returned by get_resource_history
+export interface ResourceSample {
+  at: string;
+  cpuUsage: number;
+  memory: number;
+}
+
+// One signal sent by kill_session
+export interface TerminationStep {
+  signal: 'SIGINT' | 'SIGTERM' | 'SIGKILL';
+  signalledPids: number[];
+  waitedMs: number;
+  allExited: boolean;
+}
+
+// Returned by kill_session
+export interface TerminationResult {
+  pid: number;
+  exited: boolean;
+  steps: TerminationStep[];
+  remainingPids: number[];
+}
+
+// A file another live session edited around the same time
+export interface EditConflict {
+  path: string;
+  otherSessionIds: string[];
+}
+
+// A file a session has edited, returned by get_session_files
+export interface TouchedFile {
+  path: string;
+  editCount: number;
+  lastTouchedAt: string;
+  lastTool: string;
+}
+
+export type AuditKind = 'command' | 'webFetch' | 'webSearch' | 'fileEdit';
+
+export interface AuditOutcome {
+  isError: boolean;
+  exitCode: number | null;
+  error: string | null;
+}
+
+// A command, web request or flagged edit, returned by get_session_audit
+export interface AuditEntry {
+  toolUseId: string;
+  kind: AuditKind;
+  detail: string;
+  at: string;
+  lineNumber: number;
+  outcome: AuditOutcome | null;
+  matchedRules: string[];
+}
+
+// A credential found in a transcript, returned by get_session_secrets
+export interface SecretFinding {
+  kind: string;
+  lineNumber: number;
+  preview: string;
+}
+
+// Returned by redact_session_secrets
+export interface RedactionResult {
+  redactedCount: number;
+  backupPath: string | null;
+}
+
+// Time and tokens of one project on one day, returned by get_project_time
+export interface ProjectDayActivity {
+  day: string;
+  // Name for display; rows are per projectPath
+  project: string;
+  projectPath: string;
+  processingSecs: number;
+  waitingSecs: number;
+  idleSecs: number;
+  inputTokens: number;
+  outputTokens: number;
+}
+
+// Returned by get_weekly_sessions
+export interface WeeklySessions {
+  weekStart: string;
+  sessionsStarted: number;
+  projectCount: number;
+}
+
+export type ActivityReport = 'projectTime' | 'weeklySessions';
+
+export type ExportFormat = 'markdown' | 'html' | 'json';
+
+// Passed to export_session
+export interface ExportOptions {
+  format: ExportFormat;
+  includeThinking: boolean;
+  maxToolOutputChars: number;
 }
 
 export interface SessionsResponse {
   sessions: Session[];
   totalCount: number;
   waitingCount: number;
+  groups: SessionGroup[];
+}
+
+export interface StatusCounts {
+  waiting: number;
+  processing: number;
+  thinking: number;
+  compacting: number;
+  idle: number;
+  paused: number;
+}
+
+// Sessions sharing a repository (across worktrees), or a project path outside any repository
+export interface SessionGroup {
+  id: string;
+  name: string;
+  isRepo: boolean;
+  sessionIds: string[];
+  statusCounts: StatusCounts;
+}
+
+export interface HistorySession {
+  id: string;
+  agentType: AgentType;
+  projectPath: string;
+  firstPrompt: string | null;
+  title: string | null;
+  lastActivityAt: string;
+  messageCount: number;
+  gitBranch: string | null;
+  sizeBytes: number;
+}
+
+export interface ProjectHistory {
+  projectName: string;
+  projectPath: string;
+  sessions: HistorySession[];
+}
+
+export type PolicyActionKind = 'notify' | 'pause' | 'terminate';
+
+// Returned by get_policy_actions, one per action a rule in policies.json took
+export interface PolicyActionRecord {
+  at: string;
+  rule: string;
+  action: PolicyActionKind;
+  sessionId: string;
+  projectName: string;
+  pid: number | null;
+  reason: string;
+  dryRun: boolean;
+  error: string | null;
 }
+
+export type SessionEventKind =
+  | { type: 'sessionStarted'; status: SessionStatus; projectPath: string }
+  | { type: 'statusChanged'; from: SessionStatus; to: SessionStatus }
+  | { type: 'sessionEnded' }
+  | { type: 'subagentStarted'; activeSubagentCount: number }
+  | { type: 'subagentFinished'; activeSubagentCount: number }
+  | { type: 'auditAlert'; rule: string; detail: string }
+  | { type: 'policyAction'; rule: string; action: PolicyActionKind; detail: string; dryRun: boolean };
+
+// Emitted as the "session-event" Tauri event and returned by get_recent_events
+export type SessionEvent = {
+  seq: number;
+  at: string;
+  sessionId: string;
+  agentType: AgentType;
+  projectName: string;
+} & SessionEventKind;